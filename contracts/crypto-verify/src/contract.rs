@@ -7,10 +7,12 @@ use sha3::Keccak256;
 use std::ops::Deref;
 
 use crate::ethereum::{
-    decode_address, ethereum_address_raw, get_recovery_param, verify_transaction,
+    decode_address, ethereum_address_raw, get_recovery_param, recover_transaction_sender,
+    verify_transaction,
 };
 use crate::msg::{
-    list_verifications, InstantiateMsg, ListVerificationsResponse, QueryMsg, VerifyResponse,
+    list_verifications, EthereumSenderResponse, InstantiateMsg, ListVerificationsResponse,
+    QueryMsg, VerifyResponse,
 };
 
 pub const VERSION: &str = "crypto-verify-v2";
@@ -63,6 +65,9 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
         } => to_binary(&query_verify_ethereum_transaction(
             deps, from, to, nonce, gas_limit, gas_price, value, data, chain_id, r, s, v,
         )?),
+        QueryMsg::RecoverEthereumTransactionSender { raw_tx } => {
+            to_binary(&query_recover_ethereum_transaction_sender(deps, &raw_tx)?)
+        }
         QueryMsg::VerifyTendermintSignature {
             message,
             signature,
@@ -175,6 +180,17 @@ pub fn query_verify_ethereum_transaction(
     Ok(VerifyResponse { verifies })
 }
 
+pub fn query_recover_ethereum_transaction_sender(
+    deps: Deps,
+    raw_tx: &[u8],
+) -> StdResult<EthereumSenderResponse> {
+    let (sender, chain_id) = recover_transaction_sender(deps.api, raw_tx)?;
+    Ok(EthereumSenderResponse {
+        sender: format!("0x{}", hex::encode(sender)),
+        chain_id,
+    })
+}
+
 pub fn query_verify_tendermint(
     deps: Deps,
     message: &[u8],
@@ -451,6 +467,27 @@ mod tests {
         assert_eq!(res, VerifyResponse { verifies: true });
     }
 
+    #[test]
+    fn recover_ethereum_transaction_sender_works() {
+        let deps = setup();
+
+        // The transaction from verify_ethereum_transaction_works in its signed RLP encoding
+        let raw_tx = hex!("f88981e1843b9aca00830226c894e137f5264b6b528244e1643a2d570b37660b7f148353177ca1536561726368207478207465737420302e363539303836393137333936343333352ba0b9299dab50b3cddcaecd64b29bfbd5cd30fac1a1adea1b359a13c4e5171492a6a0573059c66d894684488f92e7ce1f91b158ca57b0235485625b576a3b98c480ac");
+
+        let msg = QueryMsg::RecoverEthereumTransactionSender {
+            raw_tx: raw_tx.into(),
+        };
+        let raw = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let res: EthereumSenderResponse = from_slice(&raw).unwrap();
+        assert_eq!(
+            res,
+            EthereumSenderResponse {
+                sender: "0x0a65766695a712af41b5cfecaad217b1a11cb22a".to_string(),
+                chain_id: Some(4),
+            }
+        );
+    }
+
     #[test]
     fn tendermint_signatures_batch_verify_works() {
         let deps = setup();
@@ -1,5 +1,5 @@
 use cosmwasm_std::{Api, StdError, StdResult};
-use rlp::RlpStream;
+use rlp::{Rlp, RlpStream};
 use sha3::{Digest, Keccak256};
 
 #[allow(clippy::too_many_arguments)]
@@ -20,11 +20,7 @@ pub fn verify_transaction(
     let sign_bytes =
         serialize_unsigned_transaction(to, nonce, gas, gas_price, value, data, chain_id);
     let hash = Keccak256::digest(&sign_bytes);
-    let mut rs: Vec<u8> = Vec::with_capacity(64);
-    rs.resize(32 - r.len(), 0); // Left pad r to 32 bytes
-    rs.extend_from_slice(r);
-    rs.resize(32 + (32 - s.len()), 0); // Left pad s to 32 bytes
-    rs.extend_from_slice(s);
+    let rs = pad_signature(r, s)?;
 
     let recovery = get_recovery_param_with_chain_id(v, chain_id)?;
     let calculated_pubkey = api.secp256k1_recover_pubkey(&hash, &rs, recovery)?;
@@ -36,6 +32,69 @@ pub fn verify_transaction(
     Ok(valid)
 }
 
+/// Recovers the sender of a signed legacy Ethereum transaction in its raw RLP encoding,
+/// i.e. the list `[nonce, gasPrice, gasLimit, to, value, data, v, r, s]`.
+///
+/// If `v` uses the [EIP-155] encoding, the chain ID is derived from it and included in the
+/// signed payload. Returns the raw 20 byte sender address and the chain ID (if any).
+///
+/// [EIP-155]: https://github.com/ethereum/EIPs/blob/master/EIPS/eip-155.md
+pub fn recover_transaction_sender(
+    api: &dyn Api,
+    raw_tx: &[u8],
+) -> StdResult<([u8; 20], Option<u64>)> {
+    let tx = Rlp::new(raw_tx);
+    if !tx.is_list() || tx.item_count().map_err(rlp_err)? != 9 {
+        return Err(StdError::generic_err(
+            "Transaction must be an RLP list of 9 items",
+        ));
+    }
+    let v: u64 = tx.val_at(6).map_err(rlp_err)?;
+    let r = tx.at(7).and_then(|r| r.data()).map_err(rlp_err)?;
+    let s = tx.at(8).and_then(|s| s.data()).map_err(rlp_err)?;
+
+    let chain_id = if v >= 35 { Some((v - 35) / 2) } else { None };
+    let recovery = get_recovery_param_with_chain_id(v, chain_id.unwrap_or_default())?;
+
+    // The signed payload consists of the first 6 items as they are, followed by
+    // `[chain_id, 0, 0]` for EIP-155 transactions.
+    let mut stream = RlpStream::new();
+    stream.begin_list(if chain_id.is_some() { 9 } else { 6 });
+    for i in 0..6 {
+        stream.append_raw(tx.at(i).map_err(rlp_err)?.as_raw(), 1);
+    }
+    if let Some(chain_id) = chain_id {
+        stream.append(&chain_id);
+        stream.append(&Vec::<u8>::new()); // empty r
+        stream.append(&Vec::<u8>::new()); // empty s
+    }
+    let hash = Keccak256::digest(&stream.out().to_vec());
+
+    let rs = pad_signature(r, s)?;
+    let pubkey = api.secp256k1_recover_pubkey(&hash, &rs, recovery)?;
+    let sender = ethereum_address_raw(&pubkey)?;
+    Ok((sender, chain_id))
+}
+
+fn rlp_err(err: rlp::DecoderError) -> StdError {
+    StdError::parse_err("Ethereum transaction", err.to_string())
+}
+
+/// Left pads r and s to 32 bytes each and concatenates them
+fn pad_signature(r: &[u8], s: &[u8]) -> StdResult<Vec<u8>> {
+    if r.len() > 32 || s.len() > 32 {
+        return Err(StdError::generic_err(
+            "Signature components r and s must not exceed 32 bytes",
+        ));
+    }
+    let mut rs: Vec<u8> = Vec::with_capacity(64);
+    rs.resize(32 - r.len(), 0); // Left pad r to 32 bytes
+    rs.extend_from_slice(r);
+    rs.resize(32 + (32 - s.len()), 0); // Left pad s to 32 bytes
+    rs.extend_from_slice(s);
+    Ok(rs)
+}
+
 fn serialize_unsigned_transaction(
     to: [u8; 20],
     nonce: u64,
@@ -84,12 +143,22 @@ pub fn get_recovery_param(v: u8) -> StdResult<u8> {
 ///
 /// [EIP-155]: https://github.com/ethereum/EIPs/blob/master/EIPS/eip-155.md
 pub fn get_recovery_param_with_chain_id(v: u64, chain_id: u64) -> StdResult<u8> {
-    let recovery = v - chain_id * 2 - 35;
+    // Transactions signed before EIP-155 are still valid
+    if v == 27 || v == 28 {
+        return Ok((v - 27) as u8);
+    }
+    let recovery = chain_id
+        .checked_mul(2)
+        .and_then(|doubled| doubled.checked_add(35))
+        .and_then(|offset| v.checked_sub(offset));
     match recovery {
-        0 | 1 => Ok(recovery as u8),
+        Some(recovery @ (0 | 1)) => Ok(recovery as u8),
         _ => Err(StdError::generic_err(format!(
-            "Calculated recovery parameter must be 0 or 1 but is {}.",
-            recovery
+            "Value of v ({}) does not match chain ID {}. Expected 27, 28, {} or {}.",
+            v,
+            chain_id,
+            chain_id.saturating_mul(2).saturating_add(35),
+            chain_id.saturating_mul(2).saturating_add(36),
         ))),
     }
 }
@@ -169,6 +238,36 @@ mod tests {
         assert!(valid);
     }
 
+    #[test]
+    fn recover_transaction_sender_works() {
+        // Same transaction as in verify_transaction_works, signed and RLP encoded
+        let raw_tx = hex!("f88981e1843b9aca00830226c894e137f5264b6b528244e1643a2d570b37660b7f148353177ca1536561726368207478207465737420302e363539303836393137333936343333352ba0b9299dab50b3cddcaecd64b29bfbd5cd30fac1a1adea1b359a13c4e5171492a6a0573059c66d894684488f92e7ce1f91b158ca57b0235485625b576a3b98c480ac");
+
+        let api = MockApi::default();
+        let (sender, chain_id) = recover_transaction_sender(&api, &raw_tx).unwrap();
+        assert_eq!(sender, hex!("0a65766695a712af41b5cfecaad217b1a11cb22a"));
+        assert_eq!(chain_id, Some(4));
+
+        // Not a list
+        recover_transaction_sender(&api, &hex!("8401020304")).unwrap_err();
+        // Truncated
+        recover_transaction_sender(&api, &raw_tx[..100]).unwrap_err();
+    }
+
+    #[test]
+    fn get_recovery_param_with_chain_id_works() {
+        assert_eq!(get_recovery_param_with_chain_id(0x2b, 4).unwrap(), 0);
+        assert_eq!(get_recovery_param_with_chain_id(0x2c, 4).unwrap(), 1);
+        assert_eq!(get_recovery_param_with_chain_id(37, 1).unwrap(), 0);
+        assert_eq!(get_recovery_param_with_chain_id(27, 4).unwrap(), 0);
+        assert_eq!(get_recovery_param_with_chain_id(28, 4).unwrap(), 1);
+
+        // v smaller than the EIP-155 offset must not underflow
+        get_recovery_param_with_chain_id(30, 4).unwrap_err();
+        get_recovery_param_with_chain_id(0x2d, 4).unwrap_err();
+        get_recovery_param_with_chain_id(u64::MAX, u64::MAX).unwrap_err();
+    }
+
     #[test]
     fn serialize_unsigned_transaction_works() {
         // Test data from https://github.com/iov-one/iov-core/blob/v2.5.0/packages/iov-ethereum/src/serialization.spec.ts#L78-L93
@@ -48,6 +48,13 @@ pub enum QueryMsg {
         s: Binary,
         v: u64,
     },
+    /// Recovers the sender of a signed legacy Ethereum transaction.
+    /// EIP-155 transactions are supported and the chain ID is derived from `v`.
+    #[returns(EthereumSenderResponse)]
+    RecoverEthereumTransactionSender {
+        /// The raw RLP encoded transaction including the signature
+        raw_tx: Binary,
+    },
     /// Tendermint format (ed25519 verification scheme).
     #[returns(VerifyResponse)]
     VerifyTendermintSignature {
@@ -79,6 +86,14 @@ pub struct VerifyResponse {
     pub verifies: bool,
 }
 
+#[cw_serde]
+pub struct EthereumSenderResponse {
+    /// Ethereum address in lowercase hex format (42 characters, starting with 0x)
+    pub sender: String,
+    /// The chain ID if the transaction uses EIP-155 replay protection
+    pub chain_id: Option<u64>,
+}
+
 #[cw_serde]
 pub struct ListVerificationsResponse {
     pub verification_schemes: Vec<String>,
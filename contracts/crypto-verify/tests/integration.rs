@@ -26,7 +26,9 @@ use cosmwasm_vm::testing::{
 use cosmwasm_vm::{from_slice, Instance};
 use hex_literal::hex;
 
-use crypto_verify::msg::{InstantiateMsg, ListVerificationsResponse, QueryMsg, VerifyResponse};
+use crypto_verify::msg::{
+    EthereumSenderResponse, InstantiateMsg, ListVerificationsResponse, QueryMsg, VerifyResponse,
+};
 
 // Output of cargo wasm
 static WASM: &[u8] = include_bytes!("../target/wasm32-unknown-unknown/release/crypto_verify.wasm");
@@ -253,6 +255,22 @@ fn verify_ethereum_transaction_works() {
     assert_eq!(res, VerifyResponse { verifies: true });
 }
 
+#[test]
+fn recover_ethereum_transaction_sender_works() {
+    let mut deps = setup();
+
+    // The transaction from verify_ethereum_transaction_works in its signed RLP encoding
+    let raw_tx = hex!("f88981e1843b9aca00830226c894e137f5264b6b528244e1643a2d570b37660b7f148353177ca1536561726368207478207465737420302e363539303836393137333936343333352ba0b9299dab50b3cddcaecd64b29bfbd5cd30fac1a1adea1b359a13c4e5171492a6a0573059c66d894684488f92e7ce1f91b158ca57b0235485625b576a3b98c480ac");
+
+    let msg = QueryMsg::RecoverEthereumTransactionSender {
+        raw_tx: raw_tx.into(),
+    };
+    let raw = query(&mut deps, mock_env(), msg).unwrap();
+    let res: EthereumSenderResponse = from_slice(&raw, DESERIALIZATION_LIMIT).unwrap();
+    assert_eq!(res.sender, "0x0a65766695a712af41b5cfecaad217b1a11cb22a");
+    assert_eq!(res.chain_id, Some(4));
+}
+
 #[test]
 fn tendermint_signature_verify_works() {
     let mut deps = setup();
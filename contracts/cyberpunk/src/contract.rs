@@ -35,6 +35,8 @@ pub fn execute(
         MemoryLoop {} => execute_memory_loop(),
         MessageLoop {} => execute_message_loop(env),
         AllocateLargeMemory { pages } => execute_allocate_large_memory(pages),
+        StackOverflow {} => execute_stack_overflow(),
+        LargeResponse { size } => execute_large_response(size),
         Panic {} => execute_panic(),
         Unreachable {} => execute_unreachable(),
         MirrorEnv {} => execute_mirror_env(env),
@@ -119,6 +121,24 @@ fn execute_allocate_large_memory(pages: u32) -> Result<Response, ContractError>
     Err(StdError::generic_err("Unsupported architecture").into())
 }
 
+fn execute_stack_overflow() -> Result<Response, ContractError> {
+    // The result depends on the result of the inner call, such that the
+    // compiler cannot turn the recursion into a loop.
+    #[allow(unconditional_recursion)]
+    fn recurse(data: [u8; 256]) -> u8 {
+        let mut next = data;
+        next[0] = next[0].wrapping_add(1);
+        recurse(next).wrapping_add(next[255])
+    }
+
+    let result = recurse([7; 256]);
+    Ok(Response::new().set_data([result]))
+}
+
+fn execute_large_response(size: u32) -> Result<Response, ContractError> {
+    Ok(Response::new().set_data(vec![0x61; size as usize]))
+}
+
 fn execute_panic() -> Result<Response, ContractError> {
     // Uncomment your favourite panic case
 
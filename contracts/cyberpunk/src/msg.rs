@@ -19,6 +19,10 @@ pub enum ExecuteMsg {
     MessageLoop {},
     /// Allocate large amounts of memory without consuming much gas
     AllocateLargeMemory { pages: u32 },
+    /// Infinite recursion to exhaust the call stack
+    StackOverflow {},
+    /// Returns a response with `size` bytes of data to test the result length limit
+    LargeResponse { size: u32 },
     /// Trigger a panic to ensure framework handles gracefully
     Panic {},
     /// In contrast to Panic, this does not use the panic handler.
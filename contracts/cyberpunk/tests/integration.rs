@@ -21,6 +21,7 @@ use cosmwasm_std::{from_binary, Empty, Env, Response};
 use cosmwasm_vm::testing::{
     execute, instantiate, mock_env, mock_info, mock_instance, mock_instance_with_gas_limit, query,
 };
+use cosmwasm_vm::{call_execute, VmError};
 
 use cyberpunk::msg::{ExecuteMsg, QueryMsg};
 
@@ -80,3 +81,40 @@ fn test_env() {
 
     assert_eq!(received_env, env);
 }
+
+#[test]
+fn execute_stack_overflow() {
+    let mut deps = mock_instance(WASM, &[]);
+
+    let init_res: Response =
+        instantiate(&mut deps, mock_env(), mock_info("admin", &[]), Empty {}).unwrap();
+    assert_eq!(0, init_res.messages.len());
+
+    let msg = br#"{"stack_overflow":{}}"#;
+    let err = call_execute::<_, _, _, Empty>(&mut deps, &mock_env(), &mock_info("troll", &[]), msg)
+        .unwrap_err();
+    match err {
+        VmError::RuntimeErr { msg } => {
+            assert!(msg.contains("call stack exhausted"), "Message: {}", msg)
+        }
+        err => panic!("Unexpected error: {:?}", err),
+    }
+}
+
+#[test]
+fn execute_large_response() {
+    let mut deps = mock_instance(WASM, &[]);
+
+    let init_res: Response =
+        instantiate(&mut deps, mock_env(), mock_info("admin", &[]), Empty {}).unwrap();
+    assert_eq!(0, init_res.messages.len());
+
+    let res: Response = execute(
+        &mut deps,
+        mock_env(),
+        mock_info("admin", &[]),
+        ExecuteMsg::LargeResponse { size: 1024 * 1024 },
+    )
+    .unwrap();
+    assert_eq!(res.data.unwrap().len(), 1024 * 1024);
+}
@@ -259,6 +259,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
             to_binary(&query_recurse(deps, depth, work, env.contract.address)?)
         }
         QueryMsg::GetInt {} => to_binary(&query_int()),
+        QueryMsg::GetEnv {} => to_binary(&env),
     }
 }
 
@@ -607,4 +608,14 @@ mod tests {
         let get_int_query = query_int();
         assert_eq!(get_int_query.int, 0xf00baa);
     }
+
+    #[test]
+    fn get_env() {
+        let deps = mock_dependencies();
+        let env = mock_env();
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetEnv {}).unwrap();
+        let queried: Env = from_slice(&res).unwrap();
+        assert_eq!(queried, env);
+    }
 }
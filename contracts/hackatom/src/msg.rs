@@ -73,6 +73,10 @@ pub enum QueryMsg {
     /// GetInt returns a hardcoded u32 value
     #[returns(IntResponse)]
     GetInt {},
+    /// Returns the `Env` passed to this query as-is, allowing tests to verify what
+    /// the host passed into the contract. There is no `MessageInfo` for queries.
+    #[returns(cosmwasm_std::Env)]
+    GetEnv {},
 }
 
 #[cw_serde]
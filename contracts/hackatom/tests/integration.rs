@@ -19,7 +19,7 @@
 
 use cosmwasm_std::{
     assert_approx_eq, coins, from_binary, to_vec, Addr, AllBalanceResponse, BankMsg, Binary,
-    ContractResult, Empty, Response, SubMsg,
+    ContractResult, Empty, Env, Response, SubMsg,
 };
 use cosmwasm_vm::{
     call_execute, from_slice,
@@ -112,6 +112,18 @@ fn instantiate_and_query() {
     assert!(msg.contains("Error parsing"));
 }
 
+#[test]
+fn get_env_returns_env_passed_by_host() {
+    let mut deps = mock_instance(WASM, &[]);
+
+    let mut env = mock_env();
+    env.block.height = 4242;
+    env.transaction = None;
+    let res = query(&mut deps, env.clone(), QueryMsg::GetEnv {}).unwrap();
+    let queried: Env = from_binary(&res).unwrap();
+    assert_eq!(queried, env);
+}
+
 #[test]
 fn migrate_verifier() {
     let mut deps = mock_instance(WASM, &[]);
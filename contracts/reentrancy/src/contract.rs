@@ -0,0 +1,166 @@
+//! A contract calling itself, both via messages and via queries.
+//!
+//! This documents the reentrancy behaviour of the actor model: a message returned
+//! in the `Response` is only dispatched after the current execution finished and
+//! its state changes were committed. So the nested execution always sees the state
+//! written by its caller and there is no way to re-enter a contract in the middle of
+//! an execution. Queries can be nested directly, but they are read-only.
+
+use cosmwasm_std::{
+    entry_point, from_slice, to_binary, to_vec, Deps, DepsMut, Env, MessageInfo, QueryResponse,
+    Response, StdResult, Storage, WasmMsg, WasmQuery,
+};
+
+use crate::msg::{CountResponse, ExecuteMsg, InstantiateMsg, QueryMsg, QuerySelfResponse};
+
+const COUNT_KEY: &[u8] = b"count";
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> StdResult<Response> {
+    deps.storage.set(COUNT_KEY, &to_vec(&0u64)?);
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::CallSelf { depth } => execute_call_self(deps, env, depth),
+    }
+}
+
+fn load_count(storage: &dyn Storage) -> StdResult<u64> {
+    match storage.get(COUNT_KEY) {
+        Some(data) => from_slice(&data),
+        None => Ok(0),
+    }
+}
+
+fn execute_call_self(deps: DepsMut, env: Env, depth: u32) -> StdResult<Response> {
+    let count = load_count(deps.storage)? + 1;
+    deps.storage.set(COUNT_KEY, &to_vec(&count)?);
+
+    let mut response = Response::new()
+        .add_attribute("depth", depth.to_string())
+        .add_attribute("count", count.to_string());
+    if depth > 0 {
+        // Executed after this call returned, not nested inside of it
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.into(),
+            msg: to_binary(&ExecuteMsg::CallSelf { depth: depth - 1 })?,
+            funds: vec![],
+        });
+    }
+    Ok(response)
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
+    match msg {
+        QueryMsg::Count {} => to_binary(&query_count(deps)?),
+        QueryMsg::QuerySelf { depth } => to_binary(&query_self(deps, env, depth)?),
+    }
+}
+
+fn query_count(deps: Deps) -> StdResult<CountResponse> {
+    Ok(CountResponse {
+        count: load_count(deps.storage)?,
+    })
+}
+
+fn query_self(deps: Deps, env: Env, depth: u32) -> StdResult<QuerySelfResponse> {
+    if depth == 0 {
+        return Ok(QuerySelfResponse {
+            depth: 0,
+            count: load_count(deps.storage)?,
+        });
+    }
+    let inner: QuerySelfResponse = deps.querier.query(
+        &WasmQuery::Smart {
+            contract_addr: env.contract.address.into(),
+            msg: to_binary(&QueryMsg::QuerySelf { depth: depth - 1 })?,
+        }
+        .into(),
+    )?;
+    Ok(QuerySelfResponse {
+        depth: inner.depth + 1,
+        count: inner.count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_binary, OwnedDeps, SubMsg};
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, InstantiateMsg {}).unwrap();
+        deps
+    }
+
+    #[test]
+    fn call_self_sends_message_to_itself() {
+        let mut deps = setup();
+        let env = mock_env();
+
+        let info = mock_info("anyone", &[]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::CallSelf { depth: 2 },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&ExecuteMsg::CallSelf { depth: 1 }).unwrap(),
+                funds: vec![],
+            })]
+        );
+
+        // the state change is visible before the message is dispatched
+        let res = query(deps.as_ref(), env, QueryMsg::Count {}).unwrap();
+        let count: CountResponse = from_binary(&res).unwrap();
+        assert_eq!(count.count, 1);
+    }
+
+    #[test]
+    fn call_self_stops_at_depth_zero() {
+        let mut deps = setup();
+
+        let info = mock_info("anyone", &[]);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::CallSelf { depth: 0 },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 0);
+    }
+
+    #[test]
+    fn query_self_works_for_depth_zero() {
+        let deps = setup();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::QuerySelf { depth: 0 }).unwrap();
+        let res: QuerySelfResponse = from_binary(&res).unwrap();
+        assert_eq!(res, QuerySelfResponse { depth: 0, count: 0 });
+    }
+}
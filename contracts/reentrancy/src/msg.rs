@@ -0,0 +1,35 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Increments the call counter and executes itself again with `depth - 1`
+    /// until `depth` reaches 0.
+    CallSelf { depth: u32 },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// How often `CallSelf` was executed
+    #[returns(CountResponse)]
+    Count {},
+    /// Smart queries itself with `depth - 1` until `depth` reaches 0
+    #[returns(QuerySelfResponse)]
+    QuerySelf { depth: u32 },
+}
+
+#[cw_serde]
+pub struct CountResponse {
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct QuerySelfResponse {
+    /// The number of nested queries that were performed
+    pub depth: u32,
+    /// The call counter as seen by the innermost query
+    pub count: u64,
+}
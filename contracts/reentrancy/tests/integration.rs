@@ -0,0 +1,75 @@
+//! This integration test tries to run and call the generated wasm.
+//! It depends on a Wasm build being available, which you can create with `cargo wasm`.
+//! Then running `cargo integration-test` will validate we can properly call into that generated Wasm.
+//!
+//! You can easily convert unit tests to integration tests as follows:
+//! 1. Copy them over verbatim
+//! 2. Then change
+//!      let mut deps = mock_dependencies(20, &[]);
+//!    to
+//!      let mut deps = mock_instance(WASM, &[]);
+//! 3. If you access raw storage, where ever you see something like:
+//!      deps.storage.get(CONFIG_KEY).expect("no data stored");
+//!    replace it with:
+//!      deps.with_storage(|store| {
+//!          let data = store.get(CONFIG_KEY).expect("no data stored");
+//!          //...
+//!      });
+//! 4. Anywhere you see query(&deps, ...) you must replace it with query(&mut deps, ...)
+
+use cosmwasm_std::{from_binary, to_binary, Response, SubMsg, WasmMsg};
+use cosmwasm_vm::testing::{
+    execute, instantiate, mock_env, mock_info, mock_instance, query, MockApi, MockQuerier,
+    MockStorage,
+};
+use cosmwasm_vm::Instance;
+
+use reentrancy::msg::{CountResponse, ExecuteMsg, InstantiateMsg, QueryMsg, QuerySelfResponse};
+
+// This line will test the output of cargo wasm
+static WASM: &[u8] = include_bytes!("../target/wasm32-unknown-unknown/release/reentrancy.wasm");
+// You can uncomment this line instead to test productionified build from rust-optimizer
+// static WASM: &[u8] = include_bytes!("../contract.wasm");
+
+fn setup() -> Instance<MockApi, MockStorage, MockQuerier> {
+    let mut deps = mock_instance(WASM, &[]);
+    let info = mock_info("creator", &[]);
+    let _res: Response = instantiate(&mut deps, mock_env(), info, InstantiateMsg {}).unwrap();
+    deps
+}
+
+#[test]
+fn call_self_sends_message_to_itself() {
+    let mut deps = setup();
+    let env = mock_env();
+
+    let info = mock_info("anyone", &[]);
+    let res: Response = execute(
+        &mut deps,
+        env.clone(),
+        info,
+        ExecuteMsg::CallSelf { depth: 3 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::CallSelf { depth: 2 }).unwrap(),
+            funds: vec![],
+        })]
+    );
+
+    let res = query(&mut deps, env, QueryMsg::Count {}).unwrap();
+    let count: CountResponse = from_binary(&res).unwrap();
+    assert_eq!(count.count, 1);
+}
+
+#[test]
+fn query_self_works_for_depth_zero() {
+    let mut deps = setup();
+
+    let res = query(&mut deps, mock_env(), QueryMsg::QuerySelf { depth: 0 }).unwrap();
+    let res: QuerySelfResponse = from_binary(&res).unwrap();
+    assert_eq!(res, QuerySelfResponse { depth: 0, count: 0 });
+}
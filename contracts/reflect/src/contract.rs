@@ -121,6 +121,17 @@ pub fn query(deps: Deps<SpecialQuery>, _env: Env, msg: QueryMsg) -> StdResult<Qu
         QueryMsg::Capitalized { text } => to_binary(&query_capitalized(deps, text)?),
         QueryMsg::Chain { request } => to_binary(&query_chain(deps, &request)?),
         QueryMsg::Raw { contract, key } => to_binary(&query_raw(deps, contract, key)?),
+        QueryMsg::RawRange {
+            contract,
+            start,
+            end,
+            limit,
+            order,
+        } => to_binary(
+            &deps
+                .querier
+                .query_wasm_raw_range(contract, start, end, limit, order)?,
+        ),
         QueryMsg::SubMsgResult { id } => to_binary(&query_subcall(deps, id)?),
     }
 }
@@ -177,8 +188,9 @@ mod tests {
     use crate::testing::mock_dependencies_with_custom_querier;
     use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
     use cosmwasm_std::{
-        coin, coins, from_binary, AllBalanceResponse, BankMsg, BankQuery, Binary, Event,
-        StakingMsg, StdError, SubMsgResponse, SubMsgResult,
+        coin, coins, from_binary, AllBalanceResponse, BankMsg, BankQuery, Binary, ContractResult,
+        Decimal, DistributionMsg, Event, GovMsg, Order, RawRangeResponse, StakingMsg, StdError,
+        SubMsgResponse, SubMsgResult, SystemResult, VoteOption, WasmQuery, WeightedVoteOption,
     };
 
     #[test]
@@ -293,6 +305,54 @@ mod tests {
         assert_eq!(payload, res.messages);
     }
 
+    #[test]
+    fn reflect_gov_distribution_and_stargate_messages() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let payload: Vec<CosmosMsg<CustomMsg>> = vec![
+            GovMsg::Vote {
+                proposal_id: 4,
+                vote: VoteOption::NoWithVeto,
+            }
+            .into(),
+            GovMsg::VoteWeighted {
+                proposal_id: 5,
+                vote: WeightedVoteOption {
+                    option: VoteOption::Yes,
+                    weight: Decimal::percent(70),
+                },
+            }
+            .into(),
+            DistributionMsg::SetWithdrawAddress {
+                address: String::from("rewards"),
+            }
+            .into(),
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: String::from("validator"),
+            }
+            .into(),
+            CosmosMsg::Stargate {
+                type_url: String::from("/cosmos.bank.v1beta1.MsgSend"),
+                value: Binary::from(b"\x0a\x03foo"),
+            },
+        ];
+
+        // the messages survive a JSON round trip
+        let msg = ExecuteMsg::ReflectMsg {
+            msgs: payload.clone(),
+        };
+        let msg: ExecuteMsg = from_binary(&to_binary(&msg).unwrap()).unwrap();
+
+        let info = mock_info("creator", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let payload: Vec<_> = payload.into_iter().map(SubMsg::new).collect();
+        assert_eq!(payload, res.messages);
+    }
+
     #[test]
     fn change_owner_works() {
         let mut deps = mock_dependencies_with_custom_querier(&[]);
@@ -459,4 +519,45 @@ mod tests {
         assert_eq!(result.data, Some(data));
         assert_eq!(result.events, events);
     }
+
+    #[test]
+    fn raw_range_query_works() {
+        let mut deps = mock_dependencies_with_custom_querier(&[]);
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::RawRange {
+                contract_addr,
+                start,
+                limit,
+                order,
+                ..
+            } => {
+                assert_eq!(contract_addr, "other");
+                assert_eq!(start, &Some(Binary::from(b"a")));
+                assert_eq!(*limit, 1);
+                assert_eq!(*order, Order::Descending);
+                let response = RawRangeResponse::new(
+                    vec![(Binary::from(b"a"), Binary::from(b"1"))],
+                    Some(Binary::from(b"b")),
+                );
+                SystemResult::Ok(ContractResult::Ok(to_binary(&response).unwrap()))
+            }
+            _ => panic!("Unexpected query: {:?}", query),
+        });
+
+        let raw = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::RawRange {
+                contract: "other".to_string(),
+                start: Some(Binary::from(b"a")),
+                end: None,
+                limit: 1,
+                order: Order::Descending,
+            },
+        )
+        .unwrap();
+        let res: RawRangeResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.data, vec![(Binary::from(b"a"), Binary::from(b"1"))]);
+        assert_eq!(res.next_key, Some(Binary::from(b"b")));
+    }
 }
@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, CosmosMsg, CustomQuery, QueryRequest, SubMsg};
+use cosmwasm_std::{Binary, CosmosMsg, CustomQuery, Order, QueryRequest, SubMsg};
 
 #[cw_serde]
 pub struct InstantiateMsg {}
@@ -25,6 +25,15 @@ pub enum QueryMsg {
     /// Queries another contract and returns the data
     #[returns(RawResponse)]
     Raw { contract: String, key: Binary },
+    /// Queries a range of another contract's raw storage and returns the data
+    #[returns(cosmwasm_std::RawRangeResponse)]
+    RawRange {
+        contract: String,
+        start: Option<Binary>,
+        end: Option<Binary>,
+        limit: u16,
+        order: Order,
+    },
     /// If there was a previous ReflectSubMsg with this ID, returns cosmwasm_std::Reply
     #[returns(cosmwasm_std::Reply)]
     SubMsgResult { id: u64 },
@@ -18,16 +18,17 @@
 //! 4. Anywhere you see query(&deps, ...) you must replace it with query(&mut deps, ...)
 
 use cosmwasm_std::{
-    coin, coins, from_binary, BankMsg, BankQuery, Binary, Coin, ContractResult, Event,
-    QueryRequest, Reply, Response, StakingMsg, SubMsg, SubMsgResponse, SubMsgResult,
-    SupplyResponse, SystemResult,
+    coin, coins, from_binary, to_binary, BankMsg, BankQuery, Binary, Coin, ContractResult,
+    CosmosMsg, Decimal, DistributionMsg, Event, GovMsg, Order, QueryRequest, RawRangeResponse,
+    Reply, Response, StakingMsg, SubMsg, SubMsgResponse, SubMsgResult, SupplyResponse, SystemError,
+    SystemResult, VoteOption, WasmQuery, WeightedVoteOption,
 };
 use cosmwasm_vm::{
     testing::{
         execute, instantiate, mock_env, mock_info, mock_instance, mock_instance_options, query,
         reply, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
     },
-    Backend, Instance,
+    validate_contract, Backend, Instance, ValidationOptions,
 };
 
 use reflect::msg::{
@@ -121,6 +122,52 @@ fn reflect() {
     assert_eq!(payload, res.messages);
 }
 
+#[test]
+fn reflect_gov_distribution_and_stargate_messages() {
+    let mut deps = mock_instance(WASM, &[]);
+
+    let msg = InstantiateMsg {};
+    let info = mock_info("creator", &coins(2, "token"));
+    let _res: Response<CustomMsg> = instantiate(&mut deps, mock_env(), info, msg).unwrap();
+
+    let payload: Vec<CosmosMsg<CustomMsg>> = vec![
+        GovMsg::Vote {
+            proposal_id: 4,
+            vote: VoteOption::NoWithVeto,
+        }
+        .into(),
+        GovMsg::VoteWeighted {
+            proposal_id: 5,
+            vote: WeightedVoteOption {
+                option: VoteOption::Yes,
+                weight: Decimal::percent(70),
+            },
+        }
+        .into(),
+        DistributionMsg::SetWithdrawAddress {
+            address: String::from("rewards"),
+        }
+        .into(),
+        DistributionMsg::WithdrawDelegatorReward {
+            validator: String::from("validator"),
+        }
+        .into(),
+        CosmosMsg::Stargate {
+            type_url: String::from("/cosmos.bank.v1beta1.MsgSend"),
+            value: Binary::from(b"\x0a\x03foo"),
+        },
+    ];
+    let msg = ExecuteMsg::ReflectMsg {
+        msgs: payload.clone(),
+    };
+    let info = mock_info("creator", &[]);
+    let res: Response<CustomMsg> = execute(&mut deps, mock_env(), info, msg).unwrap();
+
+    // should return payload
+    let payload: Vec<_> = payload.into_iter().map(SubMsg::new).collect();
+    assert_eq!(payload, res.messages);
+}
+
 #[test]
 fn reflect_requires_owner() {
     let mut deps = mock_instance(WASM, &[]);
@@ -229,6 +276,98 @@ fn dispatch_custom_query() {
     assert_eq!(value.text, "DEMO ONE");
 }
 
+#[test]
+fn raw_range_query() {
+    // the contract can only be uploaded to chains with the raw_range capability
+    let report = validate_contract(WASM, &ValidationOptions::default());
+    assert!(report.required_capabilities.contains("raw_range"));
+
+    let mut custom = mock_dependencies_with_custom_querier(&[]);
+    custom.querier.update_wasm(|query| match query {
+        WasmQuery::RawRange {
+            contract_addr,
+            start,
+            end,
+            limit,
+            order,
+        } if contract_addr == "other" => {
+            let data: Vec<(Binary, Binary)> = [("a", "1"), ("b", "2"), ("c", "3")]
+                .iter()
+                .map(|(key, value)| (Binary::from(key.as_bytes()), Binary::from(value.as_bytes())))
+                .filter(|(key, _)| start.as_ref().map_or(true, |start| key >= start))
+                .filter(|(key, _)| end.as_ref().map_or(true, |end| key < end))
+                .collect();
+            let mut data = match order {
+                Order::Ascending => data,
+                Order::Descending => data.into_iter().rev().collect(),
+            };
+            let next_key = data.get(*limit as usize).map(|(key, _)| key.clone());
+            data.truncate(*limit as usize);
+            let response = RawRangeResponse::new(data, next_key);
+            SystemResult::Ok(ContractResult::Ok(to_binary(&response).unwrap()))
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "unexpected wasm query".to_string(),
+        }),
+    });
+    let (instance_options, memory_limit) = mock_instance_options();
+    let mut deps = Instance::from_code(WASM, custom, instance_options, memory_limit).unwrap();
+
+    // the request is sent from the contract through the VM to the querier and back
+    let res = query(
+        &mut deps,
+        mock_env(),
+        QueryMsg::RawRange {
+            contract: "other".to_string(),
+            start: Some(Binary::from(b"b")),
+            end: None,
+            limit: 1,
+            order: Order::Ascending,
+        },
+    )
+    .unwrap();
+    let res: RawRangeResponse = from_binary(&res).unwrap();
+    assert_eq!(res.data, vec![(Binary::from(b"b"), Binary::from(b"2"))]);
+    assert_eq!(res.next_key, Some(Binary::from(b"c")));
+
+    let res = query(
+        &mut deps,
+        mock_env(),
+        QueryMsg::RawRange {
+            contract: "other".to_string(),
+            start: None,
+            end: Some(Binary::from(b"c")),
+            limit: 10,
+            order: Order::Descending,
+        },
+    )
+    .unwrap();
+    let res: RawRangeResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        res.data,
+        vec![
+            (Binary::from(b"b"), Binary::from(b"2")),
+            (Binary::from(b"a"), Binary::from(b"1")),
+        ]
+    );
+    assert_eq!(res.next_key, None);
+
+    // errors of the querier are passed to the contract
+    let err = query(
+        &mut deps,
+        mock_env(),
+        QueryMsg::RawRange {
+            contract: "unknown".to_string(),
+            start: None,
+            end: None,
+            limit: 10,
+            order: Order::Ascending,
+        },
+    )
+    .unwrap_err();
+    assert!(err.contains("unexpected wasm query"), "{}", err);
+}
+
 #[test]
 fn reflect_subcall() {
     let mut deps = mock_instance(WASM, &[]);
@@ -8,9 +8,9 @@ use clap::{App, Arg};
 use colored::Colorize;
 
 use cosmwasm_vm::capabilities_from_csv;
-use cosmwasm_vm::internals::{check_wasm, compile};
+use cosmwasm_vm::{validate_contract, ValidationOptions};
 
-const DEFAULT_AVAILABLE_CAPABILITIES: &str = "iterator,staking,stargate,cosmwasm_1_1";
+const DEFAULT_AVAILABLE_CAPABILITIES: &str = "iterator,staking,stargate,cosmwasm_1_1,cosmwasm_1_2";
 
 pub fn main() {
     let matches = App::new("Contract checking")
@@ -89,11 +89,15 @@ fn check_contract(
     let mut wasm = Vec::<u8>::new();
     file.read_to_end(&mut wasm)?;
 
-    // Check wasm
-    check_wasm(&wasm, available_capabilities)?;
-
-    // Compile module
-    compile(&wasm, None, &[])?;
+    // Check and compile wasm, reporting all problems at once
+    let options = ValidationOptions {
+        available_capabilities: available_capabilities.clone(),
+        ..ValidationOptions::default()
+    };
+    let report = validate_contract(&wasm, &options);
+    if !report.is_valid() {
+        anyhow::bail!(report.errors.join("\n"));
+    }
 
     Ok(())
 }
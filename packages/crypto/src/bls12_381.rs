@@ -0,0 +1,339 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+};
+use sha2::Sha256;
+
+use crate::errors::{CryptoError, CryptoResult};
+
+/// Length of a compressed G1 point
+pub const BLS12_381_G1_POINT_LEN: usize = 48;
+
+/// Length of a compressed G2 point
+pub const BLS12_381_G2_POINT_LEN: usize = 96;
+
+/// Domain separation tag of the proof of possession ciphersuite with public keys in G1
+/// and signatures in G2, as used by Ethereum consensus.
+pub const BLS12_381_G2_POP_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Hashes a message to a point in G1 using the hash_to_curve construction of
+/// [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380) (`expand_message_xmd` with SHA-256)
+/// and returns it in compressed form.
+pub fn bls12_381_hash_to_g1(message: &[u8], dst: &[u8]) -> [u8; BLS12_381_G1_POINT_LEN] {
+    let point = <G1Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(message, dst);
+    G1Affine::from(point).to_compressed()
+}
+
+/// Hashes a message to a point in G2 using the hash_to_curve construction of
+/// [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380) (`expand_message_xmd` with SHA-256)
+/// and returns it in compressed form.
+pub fn bls12_381_hash_to_g2(message: &[u8], dst: &[u8]) -> [u8; BLS12_381_G2_POINT_LEN] {
+    let point = <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(message, dst);
+    G2Affine::from(point).to_compressed()
+}
+
+/// Adds up a list of compressed G1 points, e.g. public keys to be aggregated.
+///
+/// An empty list results in an error.
+pub fn bls12_381_aggregate_g1(points: &[&[u8]]) -> CryptoResult<[u8; BLS12_381_G1_POINT_LEN]> {
+    if points.is_empty() {
+        return Err(CryptoError::aggregation_err("Empty list of points"));
+    }
+    let mut sum = G1Projective::identity();
+    for point in points {
+        sum += read_g1(point)?;
+    }
+    Ok(G1Affine::from(sum).to_compressed())
+}
+
+/// Adds up a list of compressed G2 points, e.g. signatures to be aggregated.
+///
+/// An empty list results in an error.
+pub fn bls12_381_aggregate_g2(points: &[&[u8]]) -> CryptoResult<[u8; BLS12_381_G2_POINT_LEN]> {
+    if points.is_empty() {
+        return Err(CryptoError::aggregation_err("Empty list of points"));
+    }
+    let mut sum = G2Projective::identity();
+    for point in points {
+        sum += read_g2(point)?;
+    }
+    Ok(G2Affine::from(sum).to_compressed())
+}
+
+/// Checks the pairing equation `e(p, q) == e(r, s)`, where `p` and `r` are
+/// compressed G1 points and `q` and `s` are compressed G2 points.
+///
+/// This is the building block for verifying BLS signatures in either group,
+/// e.g. drand beacons which sign in G1 or G2 depending on the network.
+pub fn bls12_381_pairing_equality(p: &[u8], q: &[u8], r: &[u8], s: &[u8]) -> CryptoResult<bool> {
+    let p = read_g1(p)?;
+    let q = G2Prepared::from(read_g2(q)?);
+    let r = read_g1(r)?;
+    let s = G2Prepared::from(read_g2(s)?);
+
+    // e(p, q) == e(r, s) <=> e(p, q) * e(-r, s) == 1
+    let result = multi_miller_loop(&[(&p, &q), (&-r, &s)]).final_exponentiation();
+    Ok(result == Gt::identity())
+}
+
+/// Verifies an aggregate BLS signature with public keys in G1 and signatures in G2
+/// (the "minimal-pubkey-size" variant used by Ethereum consensus).
+///
+/// Messages are hashed to G2 using the given domain separation tag (see [`BLS12_381_G2_POP_DST`]).
+///
+/// Two variants are supported in the input:
+///  - Equal number of messages and public keys: every key signed its own message.
+///  - One message and any number of public keys: all keys signed the same message.
+///    The public keys are aggregated first, which is much cheaper than checking
+///    one pairing per key.
+///
+/// Any other variants of input vectors result in an error.
+/// An empty list of public keys is never valid and returns false.
+///
+/// Callers must make sure the public keys are protected against rogue key attacks,
+/// e.g. by requiring a proof of possession when registering them.
+pub fn bls12_381_aggregate_verify(
+    messages: &[&[u8]],
+    signature: &[u8],
+    public_keys: &[&[u8]],
+    dst: &[u8],
+) -> CryptoResult<bool> {
+    // Structural checks
+    let messages_len = messages.len();
+    let public_keys_len = public_keys.len();
+    if messages_len != public_keys_len && messages_len != 1 {
+        return Err(CryptoError::aggregation_err(
+            "Mismatched / erroneous number of messages / public keys",
+        ));
+    }
+    if public_keys_len == 0 {
+        return Ok(false);
+    }
+
+    // Validation
+    let signature = read_signature(signature)?;
+    let public_keys = public_keys
+        .iter()
+        .map(|public_key| read_pubkey(public_key))
+        .collect::<CryptoResult<Vec<_>>>()?;
+
+    let pairs: Vec<(G1Affine, G2Prepared)> = if messages_len == 1 {
+        let aggregated = public_keys
+            .iter()
+            .fold(G1Projective::identity(), |sum, pk| sum + pk);
+        vec![(
+            G1Affine::from(aggregated),
+            hash_to_g2_prepared(messages[0], dst),
+        )]
+    } else {
+        public_keys
+            .into_iter()
+            .zip(messages.iter())
+            .map(|(pk, message)| (pk, hash_to_g2_prepared(message, dst)))
+            .collect()
+    };
+
+    // e(g1, signature) == prod(e(pk_i, H(m_i))) <=> e(-g1, signature) * prod(e(pk_i, H(m_i))) == 1
+    let neg_generator = -G1Affine::generator();
+    let signature = G2Prepared::from(signature);
+    let mut terms: Vec<(&G1Affine, &G2Prepared)> = Vec::with_capacity(pairs.len() + 1);
+    terms.push((&neg_generator, &signature));
+    terms.extend(pairs.iter().map(|(pk, hash)| (pk, hash)));
+
+    Ok(multi_miller_loop(&terms).final_exponentiation() == Gt::identity())
+}
+
+fn hash_to_g2_prepared(message: &[u8], dst: &[u8]) -> G2Prepared {
+    let point = <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(message, dst);
+    G2Prepared::from(G2Affine::from(point))
+}
+
+fn read_g1(data: &[u8]) -> CryptoResult<G1Affine> {
+    let bytes: &[u8; BLS12_381_G1_POINT_LEN] =
+        data.try_into().map_err(|_| CryptoError::invalid_point())?;
+    Option::from(G1Affine::from_compressed(bytes)).ok_or_else(CryptoError::invalid_point)
+}
+
+fn read_g2(data: &[u8]) -> CryptoResult<G2Affine> {
+    let bytes: &[u8; BLS12_381_G2_POINT_LEN] =
+        data.try_into().map_err(|_| CryptoError::invalid_point())?;
+    Option::from(G2Affine::from_compressed(bytes)).ok_or_else(CryptoError::invalid_point)
+}
+
+/// Reads a public key, which must be a valid G1 point other than the identity
+fn read_pubkey(data: &[u8]) -> CryptoResult<G1Affine> {
+    match read_g1(data) {
+        Ok(point) if !bool::from(point.is_identity()) => Ok(point),
+        _ => Err(CryptoError::invalid_pubkey_format()),
+    }
+}
+
+fn read_signature(data: &[u8]) -> CryptoResult<G2Affine> {
+    read_g2(data).map_err(|_| CryptoError::invalid_signature_format())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls12_381::Scalar;
+
+    const MSG: &[u8] = b"Hello World!";
+
+    fn keypair(secret: u64) -> (Scalar, [u8; BLS12_381_G1_POINT_LEN]) {
+        let secret_key = Scalar::from(secret);
+        let public_key = G1Affine::from(G1Affine::generator() * secret_key);
+        (secret_key, public_key.to_compressed())
+    }
+
+    fn sign(secret_key: &Scalar, message: &[u8]) -> G2Projective {
+        let hash = <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(
+            message,
+            BLS12_381_G2_POP_DST,
+        );
+        hash * secret_key
+    }
+
+    #[test]
+    fn hash_to_curve_works() {
+        let g1 = bls12_381_hash_to_g1(MSG, b"dst");
+        assert_eq!(g1, bls12_381_hash_to_g1(MSG, b"dst"));
+        assert_ne!(g1, bls12_381_hash_to_g1(MSG, b"other dst"));
+        assert!(read_g1(&g1).is_ok());
+
+        let g2 = bls12_381_hash_to_g2(MSG, b"dst");
+        assert_eq!(g2, bls12_381_hash_to_g2(MSG, b"dst"));
+        assert_ne!(g2, bls12_381_hash_to_g2(b"other message", b"dst"));
+        assert!(read_g2(&g2).is_ok());
+    }
+
+    #[test]
+    fn pairing_equality_works() {
+        let (secret_key, public_key) = keypair(42);
+        let signature = G2Affine::from(sign(&secret_key, MSG)).to_compressed();
+        let hash = bls12_381_hash_to_g2(MSG, BLS12_381_G2_POP_DST);
+        let generator = G1Affine::generator().to_compressed();
+
+        // e(g1, sig) == e(pk, H(m))
+        assert!(bls12_381_pairing_equality(&generator, &signature, &public_key, &hash).unwrap());
+
+        let (_, other_public_key) = keypair(43);
+        assert!(
+            !bls12_381_pairing_equality(&generator, &signature, &other_public_key, &hash).unwrap()
+        );
+
+        let err =
+            bls12_381_pairing_equality(&[0u8; 47], &signature, &public_key, &hash).unwrap_err();
+        assert!(matches!(err, CryptoError::InvalidPoint { .. }));
+    }
+
+    #[test]
+    fn aggregate_verify_works_for_distinct_messages() {
+        let messages: [&[u8]; 3] = [b"one", b"two", b"three"];
+        let keys: Vec<_> = (1..=3).map(keypair).collect();
+        let signature = keys
+            .iter()
+            .zip(messages.iter())
+            .fold(G2Projective::identity(), |sum, ((sk, _), msg)| {
+                sum + sign(sk, msg)
+            });
+        let signature = G2Affine::from(signature).to_compressed();
+        let public_keys: Vec<&[u8]> = keys.iter().map(|(_, pk)| pk.as_slice()).collect();
+
+        assert!(bls12_381_aggregate_verify(
+            &messages,
+            &signature,
+            &public_keys,
+            BLS12_381_G2_POP_DST
+        )
+        .unwrap());
+
+        // wrong order of messages fails
+        let swapped: [&[u8]; 3] = [b"two", b"one", b"three"];
+        assert!(!bls12_381_aggregate_verify(
+            &swapped,
+            &signature,
+            &public_keys,
+            BLS12_381_G2_POP_DST
+        )
+        .unwrap());
+
+        // wrong dst fails
+        assert!(!bls12_381_aggregate_verify(&messages, &signature, &public_keys, b"dst").unwrap());
+    }
+
+    #[test]
+    fn aggregate_verify_works_for_single_message() {
+        let keys: Vec<_> = (1..=4).map(keypair).collect();
+        let signatures: Vec<_> = keys
+            .iter()
+            .map(|(sk, _)| G2Affine::from(sign(sk, MSG)).to_compressed())
+            .collect();
+        let signatures: Vec<&[u8]> = signatures.iter().map(|s| s.as_slice()).collect();
+        let signature = bls12_381_aggregate_g2(&signatures).unwrap();
+        let public_keys: Vec<&[u8]> = keys.iter().map(|(_, pk)| pk.as_slice()).collect();
+
+        assert!(
+            bls12_381_aggregate_verify(&[MSG], &signature, &public_keys, BLS12_381_G2_POP_DST)
+                .unwrap()
+        );
+
+        // missing signer fails
+        assert!(!bls12_381_aggregate_verify(
+            &[MSG],
+            &signature,
+            &public_keys[1..],
+            BLS12_381_G2_POP_DST
+        )
+        .unwrap());
+
+        // aggregated public key works the same way
+        let aggregated = bls12_381_aggregate_g1(&public_keys).unwrap();
+        assert!(bls12_381_aggregate_verify(
+            &[MSG],
+            &signature,
+            &[&aggregated],
+            BLS12_381_G2_POP_DST
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn aggregate_verify_fails_for_invalid_input() {
+        let (secret_key, public_key) = keypair(42);
+        let signature = G2Affine::from(sign(&secret_key, MSG)).to_compressed();
+
+        // empty
+        assert!(
+            !bls12_381_aggregate_verify(&[MSG], &signature, &[], BLS12_381_G2_POP_DST).unwrap()
+        );
+
+        // mismatched lengths
+        let err = bls12_381_aggregate_verify(
+            &[MSG, MSG],
+            &signature,
+            &[&public_key, &public_key, &public_key],
+            BLS12_381_G2_POP_DST,
+        )
+        .unwrap_err();
+        assert!(matches!(err, CryptoError::AggregationErr { .. }));
+
+        // identity public key
+        let identity = G1Affine::identity().to_compressed();
+        let err =
+            bls12_381_aggregate_verify(&[MSG], &signature, &[&identity], BLS12_381_G2_POP_DST)
+                .unwrap_err();
+        assert!(matches!(err, CryptoError::InvalidPubkeyFormat { .. }));
+
+        // invalid signature
+        let err = bls12_381_aggregate_verify(
+            &[MSG],
+            &signature[1..],
+            &[&public_key],
+            BLS12_381_G2_POP_DST,
+        )
+        .unwrap_err();
+        assert!(matches!(err, CryptoError::InvalidSignatureFormat { .. }));
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use ed25519_zebra::{batch, Signature, VerificationKey};
 use rand_core::OsRng;
 
@@ -30,6 +31,16 @@ pub fn ed25519_verify(message: &[u8], signature: &[u8], public_key: &[u8]) -> Cr
     }
 }
 
+/// Checks that a public key is a valid raw ED25519 public key (32 bytes)
+/// encoding a point on the curve.
+///
+/// This allows validating keys when they are registered, without the need for a signature.
+pub fn ed25519_pubkey_validate(public_key: &[u8]) -> CryptoResult<()> {
+    let pubkey = read_pubkey(public_key)?;
+    VerificationKey::try_from(pubkey).map_err(|_| CryptoError::invalid_pubkey_format())?;
+    Ok(())
+}
+
 /// Performs batch Ed25519 signature verification.
 ///
 /// Batch verification asks whether all signatures in some set are valid, rather than asking whether
@@ -60,6 +71,9 @@ pub fn ed25519_verify(message: &[u8], signature: &[u8], public_key: &[u8]) -> Cr
 ///  - The "one-public key, with zero messages and zero signatures" case, is considered the empty
 /// case.
 ///  - The empty case (no messages, no signatures and no public keys) returns true.
+///
+/// With the `rayon` feature enabled, the batch is split into one chunk per thread
+/// and the chunks are verified in parallel.
 pub fn ed25519_batch_verify(
     messages: &[&[u8]],
     signatures: &[&[u8]],
@@ -87,7 +101,7 @@ pub fn ed25519_batch_verify(
     debug_assert_eq!(messages.len(), signatures_len);
     debug_assert_eq!(messages.len(), public_keys.len());
 
-    let mut batch = batch::Verifier::new();
+    let mut items = Vec::with_capacity(signatures_len);
 
     for ((&message, &signature), &public_key) in messages
         .iter()
@@ -98,15 +112,33 @@ pub fn ed25519_batch_verify(
         let signature = read_signature(signature)?;
         let pubkey = read_pubkey(public_key)?;
 
-        // Enqueing
+        items.push((pubkey, signature, message));
+    }
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        let threads = rayon::current_num_threads();
+        let chunk_size = (items.len() / threads).max(1);
+        Ok(items.par_chunks(chunk_size).all(verify_batch_items))
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    Ok(verify_batch_items(&items))
+}
+
+/// Verifies validated (public key, signature, message) items in a single batch
+fn verify_batch_items(items: &[([u8; 32], [u8; 64], &[u8])]) -> bool {
+    let mut batch = batch::Verifier::new();
+
+    // Enqueing
+    for &(pubkey, signature, message) in items {
         batch.queue((pubkey.into(), signature.into(), message));
     }
 
     // Batch verification
-    match batch.verify(OsRng) {
-        Ok(()) => Ok(true),
-        Err(_) => Ok(false),
-    }
+    batch.verify(OsRng).is_ok()
 }
 
 /// Error raised when signature is not 64 bytes long
@@ -205,6 +237,30 @@ mod tests {
         assert!(!ed25519_verify(message, &signature_bytes, &other_public_key_bytes).unwrap());
     }
 
+    #[test]
+    fn test_ed25519_pubkey_validate() {
+        let secret_key = SigningKey::new(OsRng);
+        let public_key_bytes: [u8; 32] = VerificationKey::from(&secret_key).into();
+        ed25519_pubkey_validate(&public_key_bytes).unwrap();
+
+        let public_key = hex::decode(COSMOS_ED25519_PUBLIC_KEY_HEX).unwrap();
+        ed25519_pubkey_validate(&public_key).unwrap();
+
+        // wrong length
+        match ed25519_pubkey_validate(&public_key[1..]).unwrap_err() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        // not on the curve (y = 2 has no matching x)
+        let mut not_on_curve = [0u8; 32];
+        not_on_curve[0] = 2;
+        match ed25519_pubkey_validate(&not_on_curve).unwrap_err() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+
     #[test]
     fn test_cosmos_ed25519_verify() {
         let secret_key = SigningKey::try_from(
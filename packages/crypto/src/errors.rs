@@ -1,46 +1,69 @@
+use alloc::string::String;
+use core::fmt;
 #[cfg(feature = "backtraces")]
 use std::backtrace::Backtrace;
-use std::fmt::Debug;
-use thiserror::Error;
 
 pub type CryptoResult<T> = core::result::Result<T, CryptoError>;
 
-#[derive(Error, Debug)]
+#[derive(Debug)]
 pub enum CryptoError {
-    #[error("Batch verify error: {msg}")]
     BatchErr {
         msg: String,
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
-    #[error("Crypto error: {msg}")]
+    AggregationErr {
+        msg: String,
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
     GenericErr {
         msg: String,
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
-    #[error("Invalid hash format")]
     InvalidHashFormat {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
-    #[error("Invalid public key format")]
     InvalidPubkeyFormat {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
-    #[error("Invalid signature format")]
     InvalidSignatureFormat {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
-    #[error("Invalid recovery parameter. Supported values: 0 and 1.")]
+    InvalidPoint {
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
     InvalidRecoveryParam {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
 }
 
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::BatchErr { msg, .. } => write!(f, "Batch verify error: {}", msg),
+            CryptoError::AggregationErr { msg, .. } => write!(f, "Aggregation error: {}", msg),
+            CryptoError::GenericErr { msg, .. } => write!(f, "Crypto error: {}", msg),
+            CryptoError::InvalidHashFormat { .. } => f.write_str("Invalid hash format"),
+            CryptoError::InvalidPubkeyFormat { .. } => f.write_str("Invalid public key format"),
+            CryptoError::InvalidSignatureFormat { .. } => f.write_str("Invalid signature format"),
+            CryptoError::InvalidPoint { .. } => f.write_str("Invalid curve point"),
+            CryptoError::InvalidRecoveryParam { .. } => {
+                f.write_str("Invalid recovery parameter. Supported values: 0 and 1.")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CryptoError {}
+
 impl CryptoError {
     pub fn batch_err(msg: impl Into<String>) -> Self {
         CryptoError::BatchErr {
@@ -50,6 +73,14 @@ impl CryptoError {
         }
     }
 
+    pub fn aggregation_err(msg: impl Into<String>) -> Self {
+        CryptoError::AggregationErr {
+            msg: msg.into(),
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
     pub fn generic_err(msg: impl Into<String>) -> Self {
         CryptoError::GenericErr {
             msg: msg.into(),
@@ -79,6 +110,13 @@ impl CryptoError {
         }
     }
 
+    pub fn invalid_point() -> Self {
+        CryptoError::InvalidPoint {
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
     pub fn invalid_recovery_param() -> Self {
         CryptoError::InvalidRecoveryParam {
             #[cfg(feature = "backtraces")]
@@ -95,6 +133,8 @@ impl CryptoError {
             CryptoError::InvalidPubkeyFormat { .. } => 5,
             CryptoError::InvalidRecoveryParam { .. } => 6,
             CryptoError::BatchErr { .. } => 7,
+            CryptoError::InvalidPoint { .. } => 8,
+            CryptoError::AggregationErr { .. } => 9,
             CryptoError::GenericErr { .. } => 10,
         }
     }
@@ -116,6 +156,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn aggregation_err_works() {
+        let error = CryptoError::aggregation_err("something went wrong in an aggregated way");
+        match error {
+            CryptoError::AggregationErr { msg, .. } => {
+                assert_eq!(msg, "something went wrong in an aggregated way")
+            }
+            _ => panic!("wrong error type!"),
+        }
+    }
+
     #[test]
     fn generic_err_works() {
         let error = CryptoError::generic_err("something went wrong in a general way");
@@ -145,6 +196,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn invalid_point_works() {
+        let error = CryptoError::invalid_point();
+        match error {
+            CryptoError::InvalidPoint { .. } => {}
+            _ => panic!("wrong error type!"),
+        }
+    }
+
     #[test]
     fn invalid_pubkey_format_works() {
         let error = CryptoError::invalid_pubkey_format();
@@ -153,4 +213,25 @@ mod tests {
             _ => panic!("wrong error type!"),
         }
     }
+
+    #[test]
+    fn display_works() {
+        let error = CryptoError::batch_err("Mismatched lengths");
+        assert_eq!(error.to_string(), "Batch verify error: Mismatched lengths");
+        let error = CryptoError::invalid_pubkey_format();
+        assert_eq!(error.to_string(), "Invalid public key format");
+    }
+
+    #[test]
+    fn code_works() {
+        // Those values are part of the VM and host interface and must not change
+        assert_eq!(CryptoError::invalid_hash_format().code(), 3);
+        assert_eq!(CryptoError::invalid_signature_format().code(), 4);
+        assert_eq!(CryptoError::invalid_pubkey_format().code(), 5);
+        assert_eq!(CryptoError::invalid_recovery_param().code(), 6);
+        assert_eq!(CryptoError::batch_err("x").code(), 7);
+        assert_eq!(CryptoError::invalid_point().code(), 8);
+        assert_eq!(CryptoError::aggregation_err("x").code(), 9);
+        assert_eq!(CryptoError::generic_err("x").code(), 10);
+    }
 }
@@ -0,0 +1,83 @@
+//! Hash functions with a uniform interface.
+//!
+//! All functions take the input as a byte slice and return the digest as a fixed size array.
+
+use digest::Digest; // trait
+use ripemd::Ripemd160;
+use sha2::{Sha256, Sha512};
+use sha3::Keccak256;
+
+/// SHA-256 as specified in FIPS 180-4
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// SHA-512 as specified in FIPS 180-4
+pub fn sha512(data: &[u8]) -> [u8; 64] {
+    Sha512::digest(data).into()
+}
+
+/// The original Keccak-256 as used by Ethereum.
+///
+/// Please note that this is different from SHA3-256, which uses a different padding.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// RIPEMD-160 as used in Bitcoin addresses
+pub fn ripemd160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn sha256_works() {
+        // echo -n "" | sha256sum
+        assert_eq!(
+            sha256(b""),
+            hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+        // echo -n "abc" | sha256sum
+        assert_eq!(
+            sha256(b"abc"),
+            hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+    }
+
+    #[test]
+    fn sha512_works() {
+        // echo -n "abc" | sha512sum
+        assert_eq!(
+            sha512(b"abc"),
+            hex!("ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f")
+        );
+    }
+
+    #[test]
+    fn keccak256_works() {
+        assert_eq!(
+            keccak256(b""),
+            hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        assert_eq!(
+            keccak256(b"abc"),
+            hex!("4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45")
+        );
+    }
+
+    #[test]
+    fn ripemd160_works() {
+        assert_eq!(
+            ripemd160(b""),
+            hex!("9c1185a5c5e9fc54612808977ee8f548b2258d31")
+        );
+        assert_eq!(
+            ripemd160(b"abc"),
+            hex!("8eb208f7e05d987a9b044a8e98c6b087f15a0bfc")
+        );
+    }
+}
@@ -2,20 +2,46 @@
 //! Please don't use any of these types directly, as
 //! they might change frequently, or be removed in the future.
 //! This crate does not adhere to semantic versioning.
+//!
+//! Without the default `std` feature, this crate is `#![no_std]` and only requires `alloc`.
+//! The ed25519 functions are only available with `std`.
+#![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(feature = "backtraces", feature(backtrace))]
 
+extern crate alloc;
+
+#[cfg(feature = "bls12_381")]
+mod bls12_381;
+#[cfg(feature = "std")]
 mod ed25519;
 mod errors;
+mod hashes;
 mod identity_digest;
 mod secp256k1;
 
+#[cfg(feature = "bls12_381")]
+#[doc(hidden)]
+pub use crate::bls12_381::{
+    bls12_381_aggregate_g1, bls12_381_aggregate_g2, bls12_381_aggregate_verify,
+    bls12_381_hash_to_g1, bls12_381_hash_to_g2, bls12_381_pairing_equality,
+};
+#[cfg(feature = "bls12_381")]
+#[doc(hidden)]
+pub use crate::bls12_381::{BLS12_381_G1_POINT_LEN, BLS12_381_G2_POINT_LEN, BLS12_381_G2_POP_DST};
+#[cfg(feature = "std")]
 #[doc(hidden)]
 pub use crate::ed25519::EDDSA_PUBKEY_LEN;
+#[cfg(feature = "std")]
 #[doc(hidden)]
-pub use crate::ed25519::{ed25519_batch_verify, ed25519_verify};
+pub use crate::ed25519::{ed25519_batch_verify, ed25519_pubkey_validate, ed25519_verify};
 #[doc(hidden)]
 pub use crate::errors::{CryptoError, CryptoResult};
 #[doc(hidden)]
-pub use crate::secp256k1::{secp256k1_recover_pubkey, secp256k1_verify};
+pub use crate::hashes::{keccak256, ripemd160, sha256, sha512};
+#[doc(hidden)]
+pub use crate::secp256k1::{
+    normalize_recovery_id, secp256k1_pubkey_validate, secp256k1_recover_pubkey, secp256k1_verify,
+    secp256k1_verify_batch, secp256k1_verify_short_hash, secp256k1_verify_strict,
+};
 #[doc(hidden)]
 pub use crate::secp256k1::{ECDSA_PUBKEY_MAX_LEN, ECDSA_SIGNATURE_LEN, MESSAGE_HASH_MAX_LEN};
@@ -1,3 +1,5 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
 use digest::{Digest, Update}; // trait
 use k256::{
     ecdsa::recoverable,
@@ -10,8 +12,8 @@ use crate::errors::{CryptoError, CryptoResult};
 use crate::identity_digest::Identity256;
 
 /// Max length of a message hash for secp256k1 verification in bytes.
-/// This is typically a 32 byte output of e.g. SHA-256 or Keccak256. In theory shorter values
-/// are possible but currently not supported by the implementation. Let us know when you need them.
+/// This is typically a 32 byte output of e.g. SHA-256 or Keccak256. Shorter values
+/// are supported by [`secp256k1_verify_short_hash`] only.
 pub const MESSAGE_HASH_MAX_LEN: usize = 32;
 
 /// ECDSA (secp256k1) parameters
@@ -35,10 +37,134 @@ pub const ECDSA_PUBKEY_MAX_LEN: usize = ECDSA_UNCOMPRESSED_PUBKEY_LEN;
 /// - signature:  Serialized "compact" signature (64 bytes).
 /// - public key: [Serialized according to SEC 2](https://www.oreilly.com/library/view/programming-bitcoin/9781492031482/ch04.html)
 /// (33 or 65 bytes).
+///
+/// Signatures with a high S value are normalized before verification, i.e. both
+/// encodings of the same signature are accepted. Use [`secp256k1_verify_strict`]
+/// to reject them instead.
 pub fn secp256k1_verify(
     message_hash: &[u8],
     signature: &[u8],
     public_key: &[u8],
+) -> CryptoResult<bool> {
+    verify(message_hash, signature, public_key, false)
+}
+
+/// Like [`secp256k1_verify`] but rejects signatures with a high S value (i.e. S > n/2)
+/// instead of normalizing them.
+///
+/// This removes signature malleability as required by protocols like Ethereum
+/// or the Cosmos SDK since 0.40. Non low-S signatures result in an
+/// [`CryptoError::InvalidSignatureFormat`] error.
+pub fn secp256k1_verify_strict(
+    message_hash: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> CryptoResult<bool> {
+    verify(message_hash, signature, public_key, true)
+}
+
+/// Like [`secp256k1_verify`] but accepts message hashes shorter than 32 bytes,
+/// e.g. SHA-1 or RIPEMD-160 digests (20 bytes) produced by some hardware signers.
+///
+/// As specified in [SEC 1](https://www.secg.org/sec1-v2.pdf), section 4.1.4,
+/// a hash shorter than the group order is interpreted as a big-endian integer,
+/// which is equivalent to left-padding it with zeros to 32 bytes.
+///
+/// Empty hashes and hashes longer than [`MESSAGE_HASH_MAX_LEN`] result in an
+/// [`CryptoError::InvalidHashFormat`] error.
+pub fn secp256k1_verify_short_hash(
+    message_hash: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> CryptoResult<bool> {
+    let padded = pad_hash(message_hash)?;
+    secp256k1_verify(&padded, signature, public_key)
+}
+
+/// Verifies a list of secp256k1 signatures using [`secp256k1_verify`].
+///
+/// Returns true if all signatures are valid. The same input variants as in
+/// [`ed25519_batch_verify`](crate::ed25519_batch_verify) are supported:
+///  - Equal number of message hashes, signatures, and public keys.
+///  - One message hash, and an equal number of signatures and public keys (multisig).
+///  - One public key, and an equal number of message hashes and signatures.
+///
+/// Any other variants of input vectors result in an error. The empty case returns true.
+///
+/// The formats of all message hashes, signatures and public keys are validated before any
+/// signature is verified, such that format errors are reported independently of the
+/// verification results. Verification stops at the first invalid signature.
+///
+/// With the `rayon` feature enabled, the signatures are verified in parallel.
+pub fn secp256k1_verify_batch(
+    message_hashes: &[&[u8]],
+    signatures: &[&[u8]],
+    public_keys: &[&[u8]],
+) -> CryptoResult<bool> {
+    // Structural checks
+    let message_hashes_len = message_hashes.len();
+    let signatures_len = signatures.len();
+    let public_keys_len = public_keys.len();
+
+    let mut message_hashes = message_hashes.to_vec();
+    let mut public_keys = public_keys.to_vec();
+    if message_hashes_len == signatures_len && message_hashes_len == public_keys_len { // We're good to go
+    } else if message_hashes_len == 1 && signatures_len == public_keys_len {
+        // Replicate message hash, for multisig
+        message_hashes = message_hashes.repeat(signatures_len);
+    } else if public_keys_len == 1 && message_hashes_len == signatures_len {
+        // Replicate pubkey
+        public_keys = public_keys.repeat(message_hashes_len);
+    } else {
+        return Err(CryptoError::batch_err(
+            "Mismatched / erroneous number of message hashes / signatures / public keys",
+        ));
+    }
+    debug_assert_eq!(message_hashes.len(), signatures_len);
+    debug_assert_eq!(message_hashes.len(), public_keys.len());
+
+    let mut items = Vec::with_capacity(signatures_len);
+
+    for ((message_hash, &signature), public_key) in message_hashes
+        .into_iter()
+        .zip(signatures.iter())
+        .zip(public_keys)
+    {
+        // Validation
+        read_hash(message_hash)?;
+        read_signature(signature)?;
+        check_pubkey(public_key)?;
+
+        items.push((message_hash, signature, public_key));
+    }
+
+    #[cfg(feature = "rayon")]
+    let failure = {
+        use rayon::prelude::*;
+
+        items
+            .par_iter()
+            .map(|&(message_hash, signature, public_key)| {
+                secp256k1_verify(message_hash, signature, public_key)
+            })
+            .find_first(|result| !matches!(result, Ok(true)))
+    };
+    #[cfg(not(feature = "rayon"))]
+    let failure = items
+        .iter()
+        .map(|&(message_hash, signature, public_key)| {
+            secp256k1_verify(message_hash, signature, public_key)
+        })
+        .find(|result| !matches!(result, Ok(true)));
+
+    failure.unwrap_or(Ok(true))
+}
+
+fn verify(
+    message_hash: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+    strict: bool,
 ) -> CryptoResult<bool> {
     let message_hash = read_hash(message_hash)?;
     let signature = read_signature(signature)?;
@@ -51,6 +177,9 @@ pub fn secp256k1_verify(
         Signature::from_bytes(&signature).map_err(|e| CryptoError::generic_err(e.to_string()))?;
     // Non low-S signatures require normalization
     if let Some(normalized) = signature.normalize_s() {
+        if strict {
+            return Err(CryptoError::invalid_signature_format());
+        }
         signature = normalized;
     }
 
@@ -63,12 +192,50 @@ pub fn secp256k1_verify(
     }
 }
 
+/// Checks that a public key is a valid secp256k1 point in one of the two supported
+/// [SEC 2](https://www.oreilly.com/library/view/programming-bitcoin/9781492031482/ch04.html)
+/// encodings (33 bytes compressed or 65 bytes uncompressed).
+///
+/// This allows validating keys when they are registered, without the need for a signature.
+pub fn secp256k1_pubkey_validate(public_key: &[u8]) -> CryptoResult<()> {
+    check_pubkey(public_key)?;
+    VerifyingKey::from_sec1_bytes(public_key).map_err(|_| CryptoError::invalid_pubkey_format())?;
+    Ok(())
+}
+
+/// Converts the `v` value of an Ethereum signature into a recovery parameter (0 or 1).
+///
+/// The following encodings are supported:
+/// - 0 and 1: the plain recovery parameter
+/// - 27 and 28: the legacy Ethereum encoding (recovery parameter + 27)
+/// - `chain_id * 2 + 35` and `chain_id * 2 + 36`: the replay protected encoding of
+///   [EIP-155](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-155.md).
+///   This is only accepted if `chain_id` is set.
+///
+/// All other values result in an [`CryptoError::InvalidRecoveryParam`] error.
+pub fn normalize_recovery_id(v: u64, chain_id: Option<u64>) -> CryptoResult<u8> {
+    match v {
+        0 | 1 => return Ok(v as u8),
+        27 | 28 => return Ok((v - 27) as u8),
+        _ => {}
+    }
+    let offset = chain_id
+        .and_then(|chain_id| chain_id.checked_mul(2))
+        .and_then(|doubled| doubled.checked_add(35))
+        .ok_or_else(CryptoError::invalid_recovery_param)?;
+    match v.checked_sub(offset) {
+        Some(recovery @ (0 | 1)) => Ok(recovery as u8),
+        _ => Err(CryptoError::invalid_recovery_param()),
+    }
+}
+
 /// Recovers a public key from a message hash and a signature.
 ///
 /// This is required when working with Ethereum where public keys
 /// are not stored on chain directly.
 ///
-/// `recovery_param` must be 0 or 1. The values 2 and 3 are unsupported by this implementation,
+/// `recovery_param` must be 0 or 1. Ethereum `v` values can be converted using
+/// [`normalize_recovery_id`]. The values 2 and 3 are unsupported by this implementation,
 /// which is the same restriction as Ethereum has (https://github.com/ethereum/go-ethereum/blob/v1.9.25/internal/ethapi/api.go#L466-L469).
 /// All other values are invalid.
 ///
@@ -113,6 +280,16 @@ fn read_hash(data: &[u8]) -> Result<[u8; 32], InvalidSecp256k1HashFormat> {
     data.try_into().map_err(|_| InvalidSecp256k1HashFormat)
 }
 
+/// Left-pads a non-empty hash of up to 32 bytes with zeros
+fn pad_hash(data: &[u8]) -> Result<[u8; 32], InvalidSecp256k1HashFormat> {
+    if data.is_empty() || data.len() > MESSAGE_HASH_MAX_LEN {
+        return Err(InvalidSecp256k1HashFormat);
+    }
+    let mut padded = [0u8; MESSAGE_HASH_MAX_LEN];
+    padded[MESSAGE_HASH_MAX_LEN - data.len()..].copy_from_slice(data);
+    Ok(padded)
+}
+
 /// Error raised when signature is not 64 bytes long (32 bytes r, 32 bytes s)
 struct InvalidSecp256k1SignatureFormat;
 
@@ -264,6 +441,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_secp256k1_verify_strict() {
+        let public_key = base64::decode(COSMOS_SECP256K1_PUBKEY_BASE64).unwrap();
+        let message = hex::decode(COSMOS_SECP256K1_MSG_HEX1).unwrap();
+        let message_hash = Sha256::digest(&message);
+
+        // low-S signature works in both modes
+        let signature = hex::decode(COSMOS_SECP256K1_SIGNATURE_HEX1).unwrap();
+        assert!(secp256k1_verify(&message_hash, &signature, &public_key).unwrap());
+        assert!(secp256k1_verify_strict(&message_hash, &signature, &public_key).unwrap());
+
+        // Same signature with S replaced by n - S
+        let high_s = hex!("c9dd20e07464d3a688ff4b710b1fbc027e495e797cfa0b4804da2ed11795922788d21fa67f7089a55d64706d120cf0b28e5499587e74a1d327f7b778b1f95a48");
+        assert!(secp256k1_verify(&message_hash, &high_s, &public_key).unwrap());
+        match secp256k1_verify_strict(&message_hash, &high_s, &public_key).unwrap_err() {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+
     #[test]
     fn test_cosmos_extra_secp256k1_verify() {
         use std::fs::File;
@@ -306,6 +503,131 @@ mod tests {
         }
     }
 
+    #[test]
+    fn secp256k1_pubkey_validate_works() {
+        // compressed
+        let public_key = base64::decode(COSMOS_SECP256K1_PUBKEY_BASE64).unwrap();
+        secp256k1_pubkey_validate(&public_key).unwrap();
+
+        // uncompressed
+        let secret_key = SigningKey::random(&mut OsRng);
+        let public_key = VerifyingKey::from(&secret_key).to_encoded_point(false);
+        secp256k1_pubkey_validate(public_key.as_bytes()).unwrap();
+
+        // wrong length
+        match secp256k1_pubkey_validate(&public_key.as_bytes()[1..]).unwrap_err() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        // wrong prefix
+        let mut wrong_prefix = base64::decode(COSMOS_SECP256K1_PUBKEY_BASE64).unwrap();
+        wrong_prefix[0] = 0x04;
+        match secp256k1_pubkey_validate(&wrong_prefix).unwrap_err() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        // not on the curve (x = 5 has no matching y)
+        let mut not_on_curve = [0u8; 33];
+        not_on_curve[0] = 0x02;
+        not_on_curve[32] = 5;
+        match secp256k1_pubkey_validate(&not_on_curve).unwrap_err() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn secp256k1_verify_short_hash_works() {
+        // A 20 byte hash (RIPEMD-160 of "abc") and its left-padded 32 byte form
+        let short_hash = hex!("8eb208f7e05d987a9b044a8e98c6b087f15a0bfc");
+        let mut padded = [0u8; 32];
+        padded[12..].copy_from_slice(&short_hash);
+
+        // Recovering the signer of an arbitrary signature over the padded hash
+        // gives us a matching public key without the need for a prehash signer
+        let signature = hex!("45c0b7f8c09a9e1f1cea0c25785594427b6bf8f9f878a8af0b1abbb48e16d0920d8becd0c220f67c51217eecfd7184ef0732481c843857e6bc7fc095c4f6b788");
+        let public_key = secp256k1_recover_pubkey(&padded, &signature, 0).unwrap();
+
+        assert!(secp256k1_verify_short_hash(&short_hash, &signature, &public_key).unwrap());
+        assert!(secp256k1_verify_short_hash(&padded, &signature, &public_key).unwrap());
+        assert!(!secp256k1_verify_short_hash(&short_hash[1..], &signature, &public_key).unwrap());
+
+        // The regular function only accepts 32 byte hashes
+        match secp256k1_verify(&short_hash, &signature, &public_key).unwrap_err() {
+            CryptoError::InvalidHashFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        // empty and too long
+        for hash in [&[][..], &[0u8; 33][..]] {
+            match secp256k1_verify_short_hash(hash, &signature, &public_key).unwrap_err() {
+                CryptoError::InvalidHashFormat { .. } => {}
+                err => panic!("Unexpected error: {}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn secp256k1_verify_batch_works() {
+        let public_key = base64::decode(COSMOS_SECP256K1_PUBKEY_BASE64).unwrap();
+        let message_hashes: Vec<Vec<u8>> = [
+            COSMOS_SECP256K1_MSG_HEX1,
+            COSMOS_SECP256K1_MSG_HEX2,
+            COSMOS_SECP256K1_MSG_HEX3,
+        ]
+        .iter()
+        .map(|msg| Sha256::digest(&hex::decode(msg).unwrap()).to_vec())
+        .collect();
+        let signatures: Vec<Vec<u8>> = [
+            COSMOS_SECP256K1_SIGNATURE_HEX1,
+            COSMOS_SECP256K1_SIGNATURE_HEX2,
+            COSMOS_SECP256K1_SIGNATURE_HEX3,
+        ]
+        .iter()
+        .map(|sig| hex::decode(sig).unwrap())
+        .collect();
+        let message_hashes: Vec<&[u8]> = message_hashes.iter().map(|m| m.as_slice()).collect();
+        let signatures: Vec<&[u8]> = signatures.iter().map(|s| s.as_slice()).collect();
+
+        // one public key for all signatures
+        assert!(secp256k1_verify_batch(&message_hashes, &signatures, &[&public_key]).unwrap());
+
+        // one public key per signature
+        let public_keys: Vec<&[u8]> = vec![&public_key; 3];
+        assert!(secp256k1_verify_batch(&message_hashes, &signatures, &public_keys).unwrap());
+
+        // empty
+        assert!(secp256k1_verify_batch(&[], &[], &[]).unwrap());
+
+        // wrong order fails
+        let swapped = [message_hashes[1], message_hashes[0], message_hashes[2]];
+        assert!(!secp256k1_verify_batch(&swapped, &signatures, &public_keys).unwrap());
+
+        // mismatched lengths
+        match secp256k1_verify_batch(&message_hashes, &signatures[1..], &public_keys).unwrap_err() {
+            CryptoError::BatchErr { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        // invalid format
+        let invalid_signatures = [signatures[0], signatures[1], &signatures[2][1..]];
+        match secp256k1_verify_batch(&message_hashes, &invalid_signatures, &public_keys)
+            .unwrap_err()
+        {
+            CryptoError::InvalidSignatureFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+
+        // formats are validated before verification
+        let invalid_public_keys = [&public_key[..], &public_key[..], &public_key[1..]];
+        match secp256k1_verify_batch(&swapped, &signatures, &invalid_public_keys).unwrap_err() {
+            CryptoError::InvalidPubkeyFormat { .. } => {}
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+
     #[test]
     fn secp256k1_recover_pubkey_works() {
         // Test data from https://github.com/ethereumjs/ethereumjs-util/blob/v6.1.0/test/index.js#L496
@@ -351,6 +673,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn normalize_recovery_id_works() {
+        // plain
+        assert_eq!(normalize_recovery_id(0, None).unwrap(), 0);
+        assert_eq!(normalize_recovery_id(1, None).unwrap(), 1);
+        assert_eq!(normalize_recovery_id(1, Some(4)).unwrap(), 1);
+
+        // legacy Ethereum
+        assert_eq!(normalize_recovery_id(27, None).unwrap(), 0);
+        assert_eq!(normalize_recovery_id(28, None).unwrap(), 1);
+        assert_eq!(normalize_recovery_id(28, Some(1)).unwrap(), 1);
+
+        // EIP-155
+        assert_eq!(normalize_recovery_id(37, Some(1)).unwrap(), 0);
+        assert_eq!(normalize_recovery_id(38, Some(1)).unwrap(), 1);
+        assert_eq!(normalize_recovery_id(0x2b, Some(4)).unwrap(), 0);
+        assert_eq!(normalize_recovery_id(11614, Some(5789)).unwrap(), 1);
+
+        // EIP-155 without or with wrong chain ID
+        for (v, chain_id) in [
+            (37, None),
+            (38, Some(2)),
+            (39, Some(1)),
+            (2, None),
+            (29, None),
+            (34, Some(0)),
+            (u64::MAX, Some(u64::MAX)),
+        ] {
+            match normalize_recovery_id(v, chain_id).unwrap_err() {
+                CryptoError::InvalidRecoveryParam { .. } => {}
+                err => panic!("Unexpected error: {}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn secp256k1_recover_pubkey_works_for_normalized_legacy_ethereum_v() {
+        let r_s = hex!("45c0b7f8c09a9e1f1cea0c25785594427b6bf8f9f878a8af0b1abbb48e16d0920d8becd0c220f67c51217eecfd7184ef0732481c843857e6bc7fc095c4f6b788");
+        let message_hash = hex!("5ae8317d34d1e595e3fa7247db80c0af4320cce1116de187f8f7e2e099c0d8d0");
+        let recovery_param = normalize_recovery_id(28, None).unwrap();
+        assert_eq!(
+            secp256k1_recover_pubkey(&message_hash, &r_s, recovery_param).unwrap(),
+            secp256k1_recover_pubkey(&message_hash, &r_s, 1).unwrap()
+        );
+
+        // the raw Ethereum value is not accepted
+        match secp256k1_recover_pubkey(&message_hash, &r_s, 28).unwrap_err() {
+            CryptoError::InvalidRecoveryParam { .. } => {}
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
     #[test]
     fn secp256k1_recover_pubkey_fails_for_invalid_recovery_param() {
         let r_s = hex!("45c0b7f8c09a9e1f1cea0c25785594427b6bf8f9f878a8af0b1abbb48e16d0920d8becd0c220f67c51217eecfd7184ef0732481c843857e6bc7fc095c4f6b788");
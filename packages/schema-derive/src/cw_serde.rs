@@ -1,22 +1,21 @@
-use syn::{parse_quote, DeriveInput};
+use quote::quote;
+use syn::{parse_quote, spanned::Spanned, DeriveInput, Error, Lit, Meta, NestedMeta, Result};
+
+const ATTR_PATH: &str = "schema";
+
+pub fn cw_serde_impl(mut input: DeriveInput) -> Result<DeriveInput> {
+    let options = take_schema_options(&mut input)?;
+
+    let rename_all = match (&input.data, options.casing) {
+        (_, Some(casing)) => Some(casing),
+        (syn::Data::Enum(_), None) => Some("snake_case".to_string()),
+        _ => None,
+    };
+    let serde_rename_all = rename_all.map(|casing| quote! { rename_all = #casing, });
+    let schemars_title = options.title.map(|title| quote! { , title = #title });
 
-pub fn cw_serde_impl(input: DeriveInput) -> DeriveInput {
     match input.data {
-        syn::Data::Struct(_) => parse_quote! {
-            #[derive(
-                ::cosmwasm_schema::serde::Serialize,
-                ::cosmwasm_schema::serde::Deserialize,
-                ::std::clone::Clone,
-                ::std::fmt::Debug,
-                ::std::cmp::PartialEq,
-                ::cosmwasm_schema::schemars::JsonSchema
-            )]
-            #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
-            #[serde(deny_unknown_fields, crate = "::cosmwasm_schema::serde")]
-            #[schemars(crate = "::cosmwasm_schema::schemars")]
-            #input
-        },
-        syn::Data::Enum(_) => parse_quote! {
+        syn::Data::Struct(_) | syn::Data::Enum(_) => Ok(parse_quote! {
             #[derive(
                 ::cosmwasm_schema::serde::Serialize,
                 ::cosmwasm_schema::serde::Deserialize,
@@ -26,14 +25,80 @@ pub fn cw_serde_impl(input: DeriveInput) -> DeriveInput {
                 ::cosmwasm_schema::schemars::JsonSchema
             )]
             #[allow(clippy::derive_partial_eq_without_eq)] // Allow users of `#[cw_serde]` to not implement Eq without clippy complaining
-            #[serde(deny_unknown_fields, rename_all = "snake_case", crate = "::cosmwasm_schema::serde")]
-            #[schemars(crate = "::cosmwasm_schema::schemars")]
+            #[serde(deny_unknown_fields, #serde_rename_all crate = "::cosmwasm_schema::serde")]
+            #[schemars(crate = "::cosmwasm_schema::schemars" #schemars_title)]
             #input
-        },
-        syn::Data::Union(_) => panic!("unions are not supported"),
+        }),
+        syn::Data::Union(_) => Err(Error::new(input.span(), "unions are not supported")),
     }
 }
 
+#[derive(Default)]
+struct SchemaOptions {
+    /// Overrides the title of the generated schema
+    title: Option<String>,
+    /// The serde `rename_all` value used for fields (structs) or variants (enums)
+    casing: Option<String>,
+}
+
+/// Removes all `#[schema(...)]` attributes from the input and collects their options.
+fn take_schema_options(input: &mut DeriveInput) -> Result<SchemaOptions> {
+    let mut options = SchemaOptions::default();
+    let (schema_attrs, other_attrs) = std::mem::take(&mut input.attrs)
+        .into_iter()
+        .partition::<Vec<_>, _>(|attr| attr.path.is_ident(ATTR_PATH));
+    input.attrs = other_attrs;
+
+    for attr in schema_attrs {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(Error::new(
+                    meta.span(),
+                    format!("{} attribute must contain a meta list", ATTR_PATH),
+                ))
+            }
+        };
+        for nested in list.nested {
+            let (path, value) = match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) => match nv.lit {
+                    Lit::Str(value) => (nv.path, value),
+                    lit => return Err(Error::new(lit.span(), "expected a string literal")),
+                },
+                other => {
+                    return Err(Error::new(
+                        other.span(),
+                        "expected `title = \"...\"` or `casing = \"...\"`",
+                    ))
+                }
+            };
+            if path.is_ident("title") {
+                options.title = Some(value.value());
+            } else if path.is_ident("casing") {
+                let casing =
+                    match value.value().as_str() {
+                        "snake" => "snake_case",
+                        "camel" => "camelCase",
+                        _ => return Err(Error::new(
+                            value.span(),
+                            "unsupported casing\nhint: supported values are `snake` and `camel`",
+                        )),
+                    };
+                options.casing = Some(casing.to_string());
+            } else {
+                return Err(Error::new(
+                    path.span(),
+                    format!(
+                        "unrecognized schema param: {}\nhint: supported params are `title` and `casing`",
+                        quote!(#path)
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(options)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,7 +110,8 @@ mod tests {
                 pub verifier: String,
                 pub beneficiary: String,
             }
-        });
+        })
+        .unwrap();
 
         let expected = parse_quote! {
             #[derive(
@@ -72,7 +138,8 @@ mod tests {
     fn empty_struct() {
         let expanded = cw_serde_impl(parse_quote! {
             pub struct InstantiateMsg {}
-        });
+        })
+        .unwrap();
 
         let expected = parse_quote! {
             #[derive(
@@ -101,7 +168,8 @@ mod tests {
                     amount: Vec<Coin>,
                 },
             }
-        });
+        })
+        .unwrap();
 
         let expected = parse_quote! {
             #[derive(
@@ -127,13 +195,63 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "unions are not supported")]
+    fn schema_attributes() {
+        let expanded = cw_serde_impl(parse_quote! {
+            #[schema(title = "NftQuery", casing = "camel")]
+            pub enum QueryNFTInfo {
+                TokenInfo {},
+            }
+        })
+        .unwrap();
+
+        let expected = parse_quote! {
+            #[derive(
+                ::cosmwasm_schema::serde::Serialize,
+                ::cosmwasm_schema::serde::Deserialize,
+                ::std::clone::Clone,
+                ::std::fmt::Debug,
+                ::std::cmp::PartialEq,
+                ::cosmwasm_schema::schemars::JsonSchema
+            )]
+            #[allow(clippy::derive_partial_eq_without_eq)]
+            #[serde(deny_unknown_fields, rename_all = "camelCase", crate = "::cosmwasm_schema::serde")]
+            #[schemars(crate = "::cosmwasm_schema::schemars", title = "NftQuery")]
+            pub enum QueryNFTInfo {
+                TokenInfo {},
+            }
+        };
+
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    fn schema_attribute_errors() {
+        let err = cw_serde_impl(parse_quote! {
+            #[schema(casing = "kebab")]
+            pub struct Foo {}
+        })
+        .unwrap_err();
+        assert!(err.to_string().starts_with("unsupported casing"));
+
+        let err = cw_serde_impl(parse_quote! {
+            #[schema(name = "Bar")]
+            pub struct Foo {}
+        })
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("unrecognized schema param: name"));
+    }
+
+    #[test]
     fn unions() {
-        cw_serde_impl(parse_quote! {
+        let err = cw_serde_impl(parse_quote! {
             pub union SudoMsg {
                 x: u32,
                 y: u32,
             }
-        });
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "unions are not supported");
     }
 }
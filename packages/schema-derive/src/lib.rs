@@ -9,7 +9,10 @@ use syn::{parse_macro_input, DeriveInput, ItemEnum};
 pub fn query_responses_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as ItemEnum);
 
-    let expanded = query_responses::query_responses_derive_impl(input).into_token_stream();
+    let expanded = match query_responses::query_responses_derive_impl(input) {
+        Ok(expanded) => expanded.into_token_stream(),
+        Err(err) => err.to_compile_error(),
+    };
 
     proc_macro::TokenStream::from(expanded)
 }
@@ -39,7 +42,10 @@ pub fn cw_serde(
 ) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    let expanded = cw_serde::cw_serde_impl(input).into_token_stream();
+    let expanded = match cw_serde::cw_serde_impl(input) {
+        Ok(expanded) => expanded.into_token_stream(),
+        Err(err) => err.to_compile_error(),
+    };
 
     proc_macro::TokenStream::from(expanded)
 }
@@ -1,17 +1,22 @@
 mod context;
 
 use syn::{
-    parse_quote, Expr, ExprTuple, Generics, ItemEnum, ItemImpl, Type, TypeParamBound, Variant,
+    parse_quote, spanned::Spanned, Attribute, Error, Expr, ExprTuple, Fields, Generics, ItemEnum,
+    ItemImpl, Lit, Meta, MetaNameValue, Result, Type, TypeParamBound, Variant,
 };
 
 use self::context::Context;
 
-pub fn query_responses_derive_impl(input: ItemEnum) -> ItemImpl {
-    let ctx = context::get_context(&input);
+pub fn query_responses_derive_impl(input: ItemEnum) -> Result<ItemImpl> {
+    let ctx = context::get_context(&input)?;
 
     if ctx.is_nested {
         let ident = input.ident;
-        let subquery_calls = input.variants.into_iter().map(parse_subquery);
+        let subquery_calls = input
+            .variants
+            .into_iter()
+            .map(parse_subquery)
+            .collect::<Result<Vec<_>>>()?;
 
         // Handle generics if the type has any
         let (_, type_generics, where_clause) = input.generics.split_for_impl();
@@ -22,7 +27,7 @@ pub fn query_responses_derive_impl(input: ItemEnum) -> ItemImpl {
         );
 
         let subquery_len = subquery_calls.len();
-        parse_quote! {
+        Ok(parse_quote! {
             #[automatically_derived]
             #[cfg(not(target_arch = "wasm32"))]
             impl #impl_generics ::cosmwasm_schema::QueryResponses for #ident #type_generics #where_clause {
@@ -33,19 +38,21 @@ pub fn query_responses_derive_impl(input: ItemEnum) -> ItemImpl {
                     ::cosmwasm_schema::combine_subqueries::<#subquery_len, #ident #type_generics>(subqueries)
                 }
             }
-        }
+        })
     } else {
         let ident = input.ident;
-        let mappings = input.variants.into_iter().map(parse_query);
-        let mut queries: Vec<_> = mappings.clone().map(|(q, _)| q).collect();
-        queries.sort();
-        let mappings = mappings.map(parse_tuple);
+        let mappings = input
+            .variants
+            .into_iter()
+            .map(|variant| parse_query(&ctx, variant))
+            .collect::<Result<Vec<_>>>()?;
+        let mappings = mappings.into_iter().map(parse_tuple);
 
         // Handle generics if the type has any
         let (_, type_generics, where_clause) = input.generics.split_for_impl();
         let impl_generics = impl_generics(&ctx, &input.generics, &[]);
 
-        parse_quote! {
+        Ok(parse_quote! {
             #[automatically_derived]
             #[cfg(not(target_arch = "wasm32"))]
             impl #impl_generics ::cosmwasm_schema::QueryResponses for #ident #type_generics #where_clause {
@@ -55,7 +62,7 @@ pub fn query_responses_derive_impl(input: ItemEnum) -> ItemImpl {
                     ])
                 }
             }
-        }
+        })
     }
 }
 
@@ -80,36 +87,120 @@ fn impl_generics(ctx: &Context, generics: &Generics, bounds: &[TypeParamBound])
 }
 
 /// Extract the query -> response mapping out of an enum variant.
-fn parse_query(v: Variant) -> (String, Expr) {
-    let query = to_snake_case(&v.ident.to_string());
-    let response_ty: Type = v
-        .attrs
+fn parse_query(ctx: &Context, v: Variant) -> Result<(String, Expr)> {
+    let query = query_name(ctx, &v)?;
+
+    let mut returns = v.attrs.iter().filter(|a| a.path.is_ident("returns"));
+    let attr = returns.next().ok_or_else(|| {
+        Error::new(
+            v.ident.span(),
+            format!(
+                "missing return type for query: {}\nhint: add `#[returns(ResponseType)]` to the variant",
+                v.ident
+            ),
+        )
+    })?;
+    if let Some(duplicate) = returns.next() {
+        return Err(Error::new(
+            duplicate.span(),
+            format!("multiple return types for query: {}", v.ident),
+        ));
+    }
+    let response_ty: Type = attr.parse_args().map_err(|_| {
+        Error::new(
+            attr.span(),
+            format!(
+                "return for {} must be a type, e.g. `#[returns(ResponseType)]`",
+                v.ident
+            ),
+        )
+    })?;
+
+    let schema: Expr = parse_quote!(::cosmwasm_schema::schema_for!(#response_ty));
+    match doc_comment(&v.attrs) {
+        // Responses without a description of their own (e.g. primitives) get the query's docs
+        Some(doc) => Ok((
+            query,
+            parse_quote! {
+                {
+                    let mut schema = #schema;
+                    let metadata = schema.schema.metadata();
+                    if metadata.description.is_none() {
+                        metadata.description = Some(#doc.to_string());
+                    }
+                    schema
+                }
+            },
+        )),
+        None => Ok((query, schema)),
+    }
+}
+
+/// Converts the doc comment of an item into a description the same way schemars does,
+/// such that the response gets the same description as the query variant.
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let docs: Vec<String> = attrs
         .iter()
-        .find(|a| a.path.get_ident().unwrap() == "returns")
-        .unwrap_or_else(|| panic!("missing return type for query: {}", v.ident))
-        .parse_args()
-        .unwrap_or_else(|_| panic!("return for {} must be a type", v.ident));
-
-    (
-        query,
-        parse_quote!(::cosmwasm_schema::schema_for!(#response_ty)),
-    )
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                lit: Lit::Str(doc), ..
+            })) => Some(doc.value()),
+            _ => None,
+        })
+        .collect();
+    let lines: Vec<&str> = docs
+        .iter()
+        .flat_map(|doc| doc.split('\n'))
+        .map(str::trim)
+        .collect();
+    // lines are merged into paragraphs, paragraphs are separated by an empty line
+    let description = lines
+        .join("\n")
+        .trim()
+        .split("\n\n")
+        .map(|paragraph| paragraph.trim().replace('\n', " "))
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if description.is_empty() {
+        None
+    } else {
+        Some(description)
+    }
 }
 
 /// Extract the nested query  -> response mapping out of an enum variant.
-fn parse_subquery(v: Variant) -> Expr {
+fn parse_subquery(v: Variant) -> Result<Expr> {
+    const HINT: &str = "hint: nested queries must be tuple variants wrapping a single QueryMsg type, e.g. `Cw1(cw1::QueryMsg)`";
+
     let submsg = match v.fields {
-        syn::Fields::Named(_) => panic!("a struct variant is not a valid subquery"),
-        syn::Fields::Unnamed(fields) => {
+        Fields::Named(fields) => {
+            return Err(Error::new(
+                fields.span(),
+                format!("a struct variant is not a valid subquery\n{}", HINT),
+            ))
+        }
+        Fields::Unnamed(fields) => {
             if fields.unnamed.len() != 1 {
-                panic!("invalid number of subquery parameters");
+                return Err(Error::new(
+                    fields.span(),
+                    format!("invalid number of subquery parameters\n{}", HINT),
+                ));
             }
 
             fields.unnamed[0].ty.clone()
         }
-        syn::Fields::Unit => panic!("a unit variant is not a valid subquery"),
+        Fields::Unit => {
+            return Err(Error::new(
+                v.ident.span(),
+                format!("a unit variant is not a valid subquery\n{}", HINT),
+            ))
+        }
     };
-    parse_quote!(<#submsg as ::cosmwasm_schema::QueryResponses>::response_schemas_impl())
+    Ok(parse_quote!(
+        <#submsg as ::cosmwasm_schema::QueryResponses>::response_schemas_impl()
+    ))
 }
 
 fn parse_tuple((q, r): (String, Expr)) -> ExprTuple {
@@ -118,6 +209,27 @@ fn parse_tuple((q, r): (String, Expr)) -> ExprTuple {
     }
 }
 
+/// The name of the query in JSON, following serde's `rename` and `rename_all` attributes.
+fn query_name(ctx: &Context, v: &Variant) -> Result<String> {
+    if let Some(rename) = context::serde_name_value(&v.attrs, "rename") {
+        return Ok(rename);
+    }
+    let ident = v.ident.to_string();
+    match ctx.rename_all.as_deref() {
+        None | Some("snake_case") => Ok(to_snake_case(&ident)),
+        Some("camelCase") => Ok(ident[..1].to_ascii_lowercase() + &ident[1..]),
+        Some("lowercase") => Ok(ident.to_ascii_lowercase()),
+        Some("PascalCase") => Ok(ident),
+        Some(other) => Err(Error::new(
+            v.ident.span(),
+            format!(
+                "unsupported rename_all value for QueryResponses: {}\nhint: use `snake_case`, `camelCase`, `lowercase` or `PascalCase`",
+                other
+            ),
+        )),
+    }
+}
+
 fn to_snake_case(input: &str) -> String {
     // this was stolen from serde for consistent behavior
     let mut snake = String::new();
@@ -150,7 +262,7 @@ mod tests {
         };
 
         assert_eq!(
-            query_responses_derive_impl(input),
+            query_responses_derive_impl(input).unwrap(),
             parse_quote! {
                 #[automatically_derived]
                 #[cfg(not(target_arch = "wasm32"))]
@@ -175,7 +287,7 @@ mod tests {
         };
 
         assert_eq!(
-            query_responses_derive_impl(input),
+            query_responses_derive_impl(input).unwrap(),
             parse_quote! {
                 #[automatically_derived]
                 #[cfg(not(target_arch = "wasm32"))]
@@ -225,7 +337,7 @@ mod tests {
             }
         };
 
-        let result = query_responses_derive_impl(input);
+        let result = query_responses_derive_impl(input).unwrap();
 
         assert_eq!(
             result,
@@ -243,7 +355,7 @@ mod tests {
             }
         );
         assert_eq!(
-            query_responses_derive_impl(input2),
+            query_responses_derive_impl(input2).unwrap(),
             parse_quote! {
                 #[automatically_derived]
                 #[cfg(not(target_arch = "wasm32"))]
@@ -257,7 +369,7 @@ mod tests {
                 }
             }
         );
-        let a = query_responses_derive_impl(input3);
+        let a = query_responses_derive_impl(input3).unwrap();
         assert_eq!(
             a,
             parse_quote! {
@@ -278,7 +390,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "missing return type for query: Supply")]
     fn missing_return() {
         let input: ItemEnum = parse_quote! {
             #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
@@ -290,11 +401,13 @@ mod tests {
             }
         };
 
-        query_responses_derive_impl(input);
+        let err = query_responses_derive_impl(input).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("missing return type for query: Supply"));
     }
 
     #[test]
-    #[should_panic(expected = "return for Supply must be a type")]
     fn invalid_return() {
         let input: ItemEnum = parse_quote! {
             #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
@@ -307,18 +420,70 @@ mod tests {
             }
         };
 
-        query_responses_derive_impl(input);
+        let err = query_responses_derive_impl(input).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("return for Supply must be a type"));
+    }
+
+    #[test]
+    fn duplicate_return() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+            #[serde(rename_all = "snake_case")]
+            pub enum QueryMsg {
+                #[returns(SomeType)]
+                #[returns(OtherType)]
+                Supply {},
+            }
+        };
+
+        let err = query_responses_derive_impl(input).unwrap_err();
+        assert_eq!(err.to_string(), "multiple return types for query: Supply");
+    }
+
+    #[test]
+    fn unrecognized_param() {
+        let input: ItemEnum = parse_quote! {
+            #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+            #[query_responses(flat)]
+            pub enum QueryMsg {
+                #[returns(SomeType)]
+                Supply {},
+            }
+        };
+
+        let err = query_responses_derive_impl(input).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("unrecognized QueryResponses param: flat"));
+
+        let input: ItemEnum = parse_quote! {
+            #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+            #[query_responses(no_bounds_for(T::Assoc))]
+            pub enum QueryMsg<T> {
+                #[returns(SomeType)]
+                Supply { data: T },
+            }
+        };
+
+        let err = query_responses_derive_impl(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`no_bounds_for` only accepts a list of type params"
+        );
     }
 
     #[test]
     fn parse_query_works() {
+        let ctx = context::get_context(&parse_quote! { enum QueryMsg {} }).unwrap();
         let variant = parse_quote! {
             #[returns(Foo)]
             GetFoo {}
         };
 
         assert_eq!(
-            parse_tuple(parse_query(variant)),
+            parse_tuple(parse_query(&ctx, variant).unwrap()),
             parse_quote! {
                 ("get_foo".to_string(), ::cosmwasm_schema::schema_for!(Foo))
             }
@@ -330,9 +495,77 @@ mod tests {
         };
 
         assert_eq!(
-            parse_tuple(parse_query(variant)),
+            parse_tuple(parse_query(&ctx, variant).unwrap()),
             parse_quote! { ("get_foo".to_string(), ::cosmwasm_schema::schema_for!(some_crate::Foo)) }
         );
+
+        let variant = parse_quote! {
+            #[returns(PageResult<some_crate::Foo>)]
+            GetFoos {}
+        };
+
+        assert_eq!(
+            parse_tuple(parse_query(&ctx, variant).unwrap()),
+            parse_quote! { ("get_foos".to_string(), ::cosmwasm_schema::schema_for!(PageResult<some_crate::Foo>)) }
+        );
+    }
+
+    #[test]
+    fn parse_query_forwards_docs() {
+        let ctx = context::get_context(&parse_quote! { enum QueryMsg {} }).unwrap();
+        let variant = parse_quote! {
+            /// Returns the balance
+            /// of an account.
+            #[returns(u128)]
+            Balance {}
+        };
+
+        assert_eq!(
+            parse_tuple(parse_query(&ctx, variant).unwrap()),
+            parse_quote! {
+                ("balance".to_string(), {
+                    let mut schema = ::cosmwasm_schema::schema_for!(u128);
+                    let metadata = schema.schema.metadata();
+                    if metadata.description.is_none() {
+                        metadata.description = Some("Returns the balance of an account.".to_string());
+                    }
+                    schema
+                })
+            }
+        );
+    }
+
+    #[test]
+    fn query_name_follows_serde() {
+        let variant: Variant = parse_quote! { QueryNFTInfo {} };
+
+        let ctx = context::get_context(&parse_quote! { enum QueryMsg {} }).unwrap();
+        assert_eq!(query_name(&ctx, &variant).unwrap(), "query_n_f_t_info");
+
+        let ctx = context::get_context(&parse_quote! {
+            #[serde(deny_unknown_fields, rename_all = "camelCase")]
+            enum QueryMsg {}
+        })
+        .unwrap();
+        assert_eq!(query_name(&ctx, &variant).unwrap(), "queryNFTInfo");
+
+        let variant: Variant = parse_quote! {
+            #[serde(rename = "query_nft_info")]
+            QueryNFTInfo {}
+        };
+        assert_eq!(query_name(&ctx, &variant).unwrap(), "query_nft_info");
+
+        let ctx = context::get_context(&parse_quote! {
+            #[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+            enum QueryMsg {}
+        })
+        .unwrap();
+        assert!(query_name(&ctx, &variant).is_ok());
+        let variant: Variant = parse_quote! { QueryNFTInfo {} };
+        assert!(query_name(&ctx, &variant)
+            .unwrap_err()
+            .to_string()
+            .starts_with("unsupported rename_all value"));
     }
 
     #[test]
@@ -353,7 +586,7 @@ mod tests {
                 Cw1WhitelistContract(QueryMsg),
             }
         };
-        let result = query_responses_derive_impl(input);
+        let result = query_responses_derive_impl(input).unwrap();
         assert_eq!(
             result,
             parse_quote! {
@@ -381,7 +614,7 @@ mod tests {
             #[query_responses(nested)]
             pub enum EmptyMsg {}
         };
-        let result = query_responses_derive_impl(input);
+        let result = query_responses_derive_impl(input).unwrap();
         assert_eq!(
             result,
             parse_quote! {
@@ -398,7 +631,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "invalid number of subquery parameters")]
     fn nested_too_many_params() {
         let input: ItemEnum = parse_quote! {
             #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
@@ -409,11 +641,13 @@ mod tests {
                 Whitelist(whitelist::QueryMsg),
             }
         };
-        query_responses_derive_impl(input);
+        let err = query_responses_derive_impl(input).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid number of subquery parameters"));
     }
 
     #[test]
-    #[should_panic(expected = "a struct variant is not a valid subquery")]
     fn nested_mixed() {
         let input: ItemEnum = parse_quote! {
             #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
@@ -426,11 +660,13 @@ mod tests {
                 }
             }
         };
-        query_responses_derive_impl(input);
+        let err = query_responses_derive_impl(input).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("a struct variant is not a valid subquery"));
     }
 
     #[test]
-    #[should_panic(expected = "a unit variant is not a valid subquery")]
     fn nested_unit_variant() {
         let input: ItemEnum = parse_quote! {
             #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
@@ -441,6 +677,9 @@ mod tests {
                 Whitelist,
             }
         };
-        query_responses_derive_impl(input);
+        let err = query_responses_derive_impl(input).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("a unit variant is not a valid subquery"));
     }
 }
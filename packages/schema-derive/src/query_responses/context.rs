@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use syn::{Ident, ItemEnum, Meta, NestedMeta};
+use syn::{spanned::Spanned, Attribute, Error, Ident, ItemEnum, Lit, Meta, NestedMeta, Result};
 
 const ATTR_PATH: &str = "query_responses";
 
@@ -11,53 +11,99 @@ pub struct Context {
     pub is_nested: bool,
     /// Disable infering the `JsonSchema` trait bound for chosen type parameters.
     pub no_bounds_for: HashSet<Ident>,
+    /// The `#[serde(rename_all = "...")]` casing of the enum, used to name the queries
+    /// the same way serde does.
+    pub rename_all: Option<String>,
 }
 
-pub fn get_context(input: &ItemEnum) -> Context {
-    let params = input
+pub fn get_context(input: &ItemEnum) -> Result<Context> {
+    let mut params = Vec::new();
+    for attr in input
         .attrs
         .iter()
-        .filter(|attr| matches!(attr.path.get_ident(), Some(id) if *id == ATTR_PATH))
-        .flat_map(|attr| {
-            if let Meta::List(l) = attr.parse_meta().unwrap() {
-                l.nested
-            } else {
-                panic!("{} attribute must contain a meta list", ATTR_PATH);
-            }
-        })
-        .map(|nested_meta| {
-            if let NestedMeta::Meta(m) = nested_meta {
-                m
-            } else {
-                panic!("no literals allowed in QueryResponses params")
+        .filter(|attr| attr.path.is_ident(ATTR_PATH))
+    {
+        match attr.parse_meta()? {
+            Meta::List(l) => params.extend(l.nested),
+            meta => {
+                return Err(Error::new(
+                    meta.span(),
+                    format!("{} attribute must contain a meta list", ATTR_PATH),
+                ))
             }
-        });
+        }
+    }
 
     let mut ctx = Context {
         is_nested: false,
         no_bounds_for: HashSet::new(),
+        rename_all: serde_name_value(&input.attrs, "rename_all"),
     };
 
     for param in params {
-        match param.path().get_ident().unwrap().to_string().as_str() {
-            "no_bounds_for" => {
-                if let Meta::List(l) = param {
-                    for item in l.nested {
-                        match item {
-                            NestedMeta::Meta(Meta::Path(p)) => {
-                                ctx.no_bounds_for.insert(p.get_ident().unwrap().clone());
-                            }
-                            _ => panic!("`no_bounds_for` only accepts a list of type params"),
+        let param = match param {
+            NestedMeta::Meta(m) => m,
+            NestedMeta::Lit(lit) => {
+                return Err(Error::new(
+                    lit.span(),
+                    "no literals allowed in QueryResponses params",
+                ))
+            }
+        };
+
+        let path = param.path();
+        if path.is_ident("no_bounds_for") {
+            if let Meta::List(l) = param {
+                for item in l.nested {
+                    match item {
+                        NestedMeta::Meta(Meta::Path(p)) if p.get_ident().is_some() => {
+                            ctx.no_bounds_for.insert(p.get_ident().unwrap().clone());
+                        }
+                        item => {
+                            return Err(Error::new(
+                                item.span(),
+                                "`no_bounds_for` only accepts a list of type params",
+                            ))
                         }
                     }
-                } else {
-                    panic!("expected a list for `no_bounds_for`")
                 }
+            } else {
+                return Err(Error::new(
+                    param.span(),
+                    "expected a list for `no_bounds_for`",
+                ));
             }
-            "nested" => ctx.is_nested = true,
-            path => panic!("unrecognized QueryResponses param: {}", path),
+        } else if path.is_ident("nested") {
+            ctx.is_nested = true;
+        } else {
+            return Err(Error::new(
+                path.span(),
+                format!(
+                    "unrecognized QueryResponses param: {}\nhint: supported params are `nested` and `no_bounds_for(...)`",
+                    quote::quote!(#path)
+                ),
+            ));
         }
     }
 
-    ctx
+    Ok(ctx)
+}
+
+/// Returns the string value of a `#[serde(key = "value")]` attribute, if present.
+pub fn serde_name_value(attrs: &[Attribute], key: &str) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("serde"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident(key) => match nv.lit {
+                Lit::Str(value) => Some(value.value()),
+                _ => None,
+            },
+            _ => None,
+        })
 }
@@ -1,14 +1,66 @@
+/// The casing used to derive schema file names from titles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Casing {
+    /// `QueryNFTInfo` becomes `query_nft_info`
+    Snake,
+    /// `QueryNFTInfo` becomes `queryNftInfo`
+    Camel,
+}
+
+impl Casing {
+    pub fn apply(self, name: &str) -> String {
+        match self {
+            Casing::Snake => to_snake_case(name),
+            Casing::Camel => to_camel_case(name),
+        }
+    }
+}
+
+/// Converts a name to snake_case, keeping acronyms together (`QueryNFTInfo` becomes `query_nft_info`).
 pub fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
     let mut out = String::new();
-    for (index, ch) in name.char_indices() {
+    for (index, &ch) in chars.iter().enumerate() {
         if index != 0 && ch.is_uppercase() {
-            out.push('_');
+            let prev = chars[index - 1];
+            let next_is_lower = matches!(chars.get(index + 1), Some(c) if c.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                out.push('_');
+            }
         }
         out.push(ch.to_ascii_lowercase());
     }
     out
 }
 
+/// Converts a name to camelCase, keeping acronyms together (`QueryNFTInfo` becomes `queryNftInfo`).
+pub fn to_camel_case(name: &str) -> String {
+    let pascal = to_pascal_case(&to_snake_case(name));
+    let mut chars = pascal.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase())
+        .into_iter()
+        .chain(chars)
+        .collect()
+}
+
+pub fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -25,6 +77,32 @@ mod tests {
     fn to_snake_case_works_for_camel_case() {
         assert_eq!(to_snake_case("Foobar"), "foobar");
         assert_eq!(to_snake_case("FooBar"), "foo_bar");
-        assert_eq!(to_snake_case("ABC"), "a_b_c");
+        assert_eq!(to_snake_case("ABC"), "abc");
+    }
+
+    #[test]
+    fn to_snake_case_keeps_acronyms_together() {
+        assert_eq!(to_snake_case("QueryNFTInfo"), "query_nft_info");
+        assert_eq!(to_snake_case("NFTInfo"), "nft_info");
+        assert_eq!(to_snake_case("QueryNFT"), "query_nft");
+        assert_eq!(to_snake_case("Cw721QueryMsg"), "cw721_query_msg");
+    }
+
+    #[test]
+    fn to_camel_case_works() {
+        assert_eq!(to_camel_case(""), "");
+        assert_eq!(to_camel_case("foo"), "foo");
+        assert_eq!(to_camel_case("FooBar"), "fooBar");
+        assert_eq!(to_camel_case("QueryNFTInfo"), "queryNftInfo");
+        assert_eq!(Casing::Snake.apply("QueryNFTInfo"), "query_nft_info");
+        assert_eq!(Casing::Camel.apply("QueryNFTInfo"), "queryNftInfo");
+    }
+
+    #[test]
+    fn to_pascal_case_works() {
+        assert_eq!(to_pascal_case(""), "");
+        assert_eq!(to_pascal_case("balance"), "Balance");
+        assert_eq!(to_pascal_case("token_info"), "TokenInfo");
+        assert_eq!(to_pascal_case("AlreadyPascal"), "AlreadyPascal");
     }
 }
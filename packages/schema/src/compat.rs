@@ -0,0 +1,611 @@
+//! Compatibility checks between two versions of a schema
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+
+/// A change between two schema versions that can break existing clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompatIssue {
+    /// An enum variant that existed in the old schema is gone
+    RemovedVariant { path: String, variant: String },
+    /// A field that existed in the old schema is gone
+    RemovedField { path: String, field: String },
+    /// A field was removed and a field of the same type was added in its place
+    RenamedField {
+        path: String,
+        old_name: String,
+        new_name: String,
+    },
+    /// A field is required now that was optional or unknown before
+    AddedRequiredField { path: String, field: String },
+    /// The type at the given location does not accept all values it accepted before
+    TypeChanged {
+        path: String,
+        old_type: String,
+        new_type: String,
+    },
+}
+
+impl fmt::Display for CompatIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompatIssue::RemovedVariant { path, variant } => {
+                write!(f, "{}: variant `{}` was removed", path, variant)
+            }
+            CompatIssue::RemovedField { path, field } => {
+                write!(f, "{}: field `{}` was removed", path, field)
+            }
+            CompatIssue::RenamedField {
+                path,
+                old_name,
+                new_name,
+            } => write!(
+                f,
+                "{}: field `{}` was renamed to `{}`",
+                path, old_name, new_name
+            ),
+            CompatIssue::AddedRequiredField { path, field } => {
+                write!(f, "{}: field `{}` is now required", path, field)
+            }
+            CompatIssue::TypeChanged {
+                path,
+                old_type,
+                new_type,
+            } => write!(
+                f,
+                "{}: type changed from `{}` to `{}`",
+                path, old_type, new_type
+            ),
+        }
+    }
+}
+
+/// The result of [`compare_schemas`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompatReport {
+    pub issues: Vec<CompatIssue>,
+}
+
+impl CompatReport {
+    /// Returns true if no breaking changes were found
+    pub fn is_compatible(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for CompatReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two versions of a message schema and reports changes that break clients
+/// built against the old version, i.e. JSON that was valid for `old` and is not valid
+/// for `new` anymore.
+///
+/// Adding variants, adding optional fields and widening types is considered compatible.
+///
+/// # Example
+/// ```
+/// use cosmwasm_schema::{compare_schemas, cw_serde, schema_for};
+///
+/// #[cw_serde]
+/// enum ExecuteMsgV1 {
+///     Mint { amount: u128 },
+/// }
+///
+/// #[cw_serde]
+/// enum ExecuteMsgV2 {
+///     Mint { amount: u128 },
+///     Burn { amount: u128 },
+/// }
+///
+/// let report = compare_schemas(&schema_for!(ExecuteMsgV1), &schema_for!(ExecuteMsgV2));
+/// assert!(report.is_compatible());
+///
+/// let report = compare_schemas(&schema_for!(ExecuteMsgV2), &schema_for!(ExecuteMsgV1));
+/// assert_eq!(report.to_string(), "ExecuteMsgV2: variant `burn` was removed\n");
+/// ```
+pub fn compare_schemas(old: &RootSchema, new: &RootSchema) -> CompatReport {
+    let mut comparator = Comparator {
+        old,
+        new,
+        visited: HashSet::new(),
+        issues: vec![],
+    };
+    let path = old
+        .schema
+        .metadata
+        .as_ref()
+        .and_then(|m| m.title.clone())
+        .unwrap_or_else(|| "root".to_string());
+    comparator.compare_objects(&path, &old.schema, &new.schema);
+    CompatReport {
+        issues: comparator.issues,
+    }
+}
+
+struct Comparator<'a> {
+    old: &'a RootSchema,
+    new: &'a RootSchema,
+    /// Pairs of (old, new) references already compared, to terminate on recursive types
+    visited: HashSet<(String, String)>,
+    issues: Vec<CompatIssue>,
+}
+
+impl<'a> Comparator<'a> {
+    fn compare(&mut self, path: &str, old: &'a Schema, new: &'a Schema) {
+        match (old, new) {
+            (_, Schema::Bool(true)) | (Schema::Bool(false), _) => {}
+            (Schema::Object(old), Schema::Object(new)) => self.compare_objects(path, old, new),
+            _ => self.type_changed(path, old, new),
+        }
+    }
+
+    fn compare_objects(&mut self, path: &str, old: &'a SchemaObject, new: &'a SchemaObject) {
+        if let Some(old) = single_all_of(old) {
+            return self.compare_objects(path, old, new);
+        }
+        if let Some(new) = single_all_of(new) {
+            return self.compare_objects(path, old, new);
+        }
+        match (nullable_inner(old), nullable_inner(new)) {
+            (Some(old), Some(new)) => return self.compare_objects(path, old, new),
+            // Making a value optional accepts everything that was accepted before
+            (None, Some(new)) => return self.compare_objects(path, old, new),
+            (Some(_), None) => return self.type_changed_objects(path, old, new),
+            (None, None) => {}
+        }
+
+        if old.reference.is_some() || new.reference.is_some() {
+            let key = (
+                old.reference.clone().unwrap_or_default(),
+                new.reference.clone().unwrap_or_default(),
+            );
+            if !self.visited.insert(key) {
+                return;
+            }
+            let old = resolve(self.old, old);
+            let new = resolve(self.new, new);
+            return self.compare_objects(path, old, new);
+        }
+
+        let old_variants = variants(self.old, old);
+        let new_variants = variants(self.new, new);
+        if let (Some(old_variants), Some(new_variants)) = (&old_variants, &new_variants) {
+            for (name, old_variant) in old_variants {
+                match new_variants.get(name) {
+                    Some(new_variant) => {
+                        let path = format!("{}.{}", path, name);
+                        if let (Some(old_variant), Some(new_variant)) = (old_variant, new_variant) {
+                            self.compare(&path, old_variant, new_variant);
+                        } else if old_variant.is_some() != new_variant.is_some() {
+                            self.issues.push(CompatIssue::TypeChanged {
+                                path,
+                                old_type: variant_kind(old_variant).to_string(),
+                                new_type: variant_kind(new_variant).to_string(),
+                            });
+                        }
+                    }
+                    None => self.issues.push(CompatIssue::RemovedVariant {
+                        path: path.to_string(),
+                        variant: name.clone(),
+                    }),
+                }
+            }
+            return;
+        }
+        if old_variants.is_some() || new_variants.is_some() {
+            return self.type_changed_objects(path, old, new);
+        }
+
+        let old_types = instance_types(old);
+        let new_types = instance_types(new);
+        if !new_types.is_empty() && !old_types.is_subset(&new_types) {
+            return self.type_changed_objects(path, old, new);
+        }
+
+        if let (Some(old_obj), Some(new_obj)) = (&old.object, &new.object) {
+            let mut removed = vec![];
+            for (field, old_field) in &old_obj.properties {
+                match new_obj.properties.get(field) {
+                    Some(new_field) => {
+                        self.compare(&format!("{}.{}", path, field), old_field, new_field)
+                    }
+                    None => removed.push((field, old_field)),
+                }
+            }
+            let mut added: Vec<_> = new_obj
+                .properties
+                .iter()
+                .filter(|(field, _)| !old_obj.properties.contains_key(*field))
+                .collect();
+
+            for (field, old_field) in removed {
+                match added
+                    .iter()
+                    .position(|(_, new_field)| *new_field == old_field)
+                {
+                    Some(index) => {
+                        let (new_name, _) = added.remove(index);
+                        self.issues.push(CompatIssue::RenamedField {
+                            path: path.to_string(),
+                            old_name: field.clone(),
+                            new_name: new_name.clone(),
+                        });
+                    }
+                    None => self.issues.push(CompatIssue::RemovedField {
+                        path: path.to_string(),
+                        field: field.clone(),
+                    }),
+                }
+            }
+
+            let renamed: BTreeSet<_> = self
+                .issues
+                .iter()
+                .filter_map(|issue| match issue {
+                    CompatIssue::RenamedField {
+                        path: p, new_name, ..
+                    } if p == path => Some(new_name.clone()),
+                    _ => None,
+                })
+                .collect();
+            for field in &new_obj.required {
+                if !old_obj.required.contains(field) && !renamed.contains(field) {
+                    self.issues.push(CompatIssue::AddedRequiredField {
+                        path: path.to_string(),
+                        field: field.clone(),
+                    });
+                }
+            }
+        }
+
+        if let (Some(old_arr), Some(new_arr)) = (&old.array, &new.array) {
+            if let (Some(SingleOrVec::Single(old_items)), Some(SingleOrVec::Single(new_items))) =
+                (&old_arr.items, &new_arr.items)
+            {
+                self.compare(&format!("{}[]", path), old_items, new_items);
+            }
+        }
+    }
+
+    fn type_changed(&mut self, path: &str, old: &Schema, new: &Schema) {
+        self.issues.push(CompatIssue::TypeChanged {
+            path: path.to_string(),
+            old_type: describe(old),
+            new_type: describe(new),
+        });
+    }
+
+    fn type_changed_objects(&mut self, path: &str, old: &SchemaObject, new: &SchemaObject) {
+        self.issues.push(CompatIssue::TypeChanged {
+            path: path.to_string(),
+            old_type: describe_object(old),
+            new_type: describe_object(new),
+        });
+    }
+}
+
+fn resolve<'a>(root: &'a RootSchema, schema: &'a SchemaObject) -> &'a SchemaObject {
+    let target = schema
+        .reference
+        .as_deref()
+        .and_then(|r| r.strip_prefix("#/definitions/"))
+        .and_then(|name| root.definitions.get(name));
+    match target {
+        Some(Schema::Object(obj)) => obj,
+        _ => schema,
+    }
+}
+
+/// The wrapped schema of `{"allOf": [T]}`, which is how schemars attaches a description
+/// to a field of a referenced type. A single `anyOf` or `oneOf` alternative is not unwrapped,
+/// since this is an enum with one variant.
+fn single_all_of(schema: &SchemaObject) -> Option<&SchemaObject> {
+    match schema.subschemas.as_ref()?.all_of.as_deref()? {
+        [Schema::Object(inner)] => Some(inner),
+        _ => None,
+    }
+}
+
+/// The wrapped schema of `{"anyOf": [T, {"type": "null"}]}`, which is how schemars
+/// represents an `Option<T>` of a referenced type. `oneOf` is treated the same.
+fn nullable_inner(schema: &SchemaObject) -> Option<&SchemaObject> {
+    let is_null = |schema: &Schema| match schema {
+        Schema::Object(obj) => instance_types(obj) == BTreeSet::from([InstanceType::Null]),
+        Schema::Bool(_) => false,
+    };
+    let subschemas = schema.subschemas.as_ref()?;
+    let alternatives = subschemas.any_of.as_ref().or(subschemas.one_of.as_ref())?;
+    match alternatives.as_slice() {
+        [Schema::Object(inner), null] | [null, Schema::Object(inner)] if is_null(null) => {
+            Some(inner)
+        }
+        _ => None,
+    }
+}
+
+/// Variants of an externally tagged enum by name. Unit variants have no schema.
+///
+/// Returns `None` if the schema does not describe an enum.
+fn variants<'a>(
+    root: &'a RootSchema,
+    schema: &'a SchemaObject,
+) -> Option<BTreeMap<String, Option<&'a Schema>>> {
+    let mut result = BTreeMap::new();
+    if let Some(values) = &schema.enum_values {
+        for value in values {
+            result.insert(value.as_str()?.to_string(), None);
+        }
+        return Some(result);
+    }
+
+    let subschemas = schema.subschemas.as_ref()?;
+    let alternatives = subschemas.one_of.as_ref().or(subschemas.any_of.as_ref())?;
+    for alternative in alternatives {
+        let alternative = match alternative {
+            Schema::Object(obj) => resolve(root, obj),
+            Schema::Bool(_) => return None,
+        };
+        if let Some(values) = &alternative.enum_values {
+            for value in values {
+                result.insert(value.as_str()?.to_string(), None);
+            }
+            continue;
+        }
+        let object = alternative.object.as_ref()?;
+        if object.properties.len() != 1 || object.required.len() != 1 {
+            return None;
+        }
+        let (name, variant) = object.properties.iter().next()?;
+        result.insert(name.clone(), Some(variant));
+    }
+    Some(result)
+}
+
+fn variant_kind(variant: &Option<&Schema>) -> &'static str {
+    match variant {
+        Some(_) => "struct variant",
+        None => "unit variant",
+    }
+}
+
+fn instance_types(schema: &SchemaObject) -> BTreeSet<InstanceType> {
+    match &schema.instance_type {
+        Some(SingleOrVec::Single(ty)) => BTreeSet::from([**ty]),
+        Some(SingleOrVec::Vec(types)) => types.iter().copied().collect(),
+        None => BTreeSet::new(),
+    }
+}
+
+fn describe(schema: &Schema) -> String {
+    match schema {
+        Schema::Bool(true) => "any".to_string(),
+        Schema::Bool(false) => "never".to_string(),
+        Schema::Object(obj) => describe_object(obj),
+    }
+}
+
+fn describe_object(schema: &SchemaObject) -> String {
+    if let Some(inner) = single_all_of(schema) {
+        return describe_object(inner);
+    }
+    if let Some(inner) = nullable_inner(schema) {
+        return format!("{} | null", describe_object(inner));
+    }
+    if let Some(reference) = &schema.reference {
+        return reference.trim_start_matches("#/definitions/").to_string();
+    }
+    if schema.enum_values.is_some() || schema.subschemas.is_some() {
+        return "enum".to_string();
+    }
+    let types: Vec<_> = instance_types(schema)
+        .into_iter()
+        .map(|ty| {
+            serde_json::to_value(ty)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default()
+        })
+        .collect();
+    if types.is_empty() {
+        "any".to_string()
+    } else {
+        types.join(" | ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schema_for;
+    use schemars::JsonSchema;
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum MsgV1 {
+        Mint { amount: u32, recipient: String },
+        Burn { amount: u32 },
+        Pause,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum MsgV2 {
+        Mint {
+            amount: u32,
+            recipient: String,
+            memo: Option<String>,
+        },
+        Burn {
+            amount: u32,
+        },
+        Pause,
+        Unpause,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum MsgV3 {
+        Mint { amount: String, to: String },
+        Burn { amount: u32, reason: String },
+        Pause {},
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct Node {
+        value: u32,
+        children: Vec<Node>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct OwnerV1 {
+        address: String,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct OwnerV2 {
+        address: u32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct DocumentedV1 {
+        /// The owner of the contract
+        owner: OwnerV1,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct DocumentedV2 {
+        /// The owner of the contract
+        owner: OwnerV2,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct OptionalV1 {
+        owner: Option<OwnerV1>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct OptionalV2 {
+        owner: Option<OwnerV2>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct RequiredV1 {
+        owner: OwnerV1,
+    }
+
+    #[test]
+    fn compare_schemas_accepts_compatible_changes() {
+        let report = compare_schemas(&schema_for!(MsgV1), &schema_for!(MsgV2));
+        assert_eq!(report, CompatReport::default());
+        assert!(report.is_compatible());
+    }
+
+    #[test]
+    fn compare_schemas_detects_breaking_changes() {
+        let report = compare_schemas(&schema_for!(MsgV2), &schema_for!(MsgV3));
+        assert!(!report.is_compatible());
+        assert_eq!(
+            report.issues,
+            vec![
+                CompatIssue::AddedRequiredField {
+                    path: "MsgV2.burn".to_string(),
+                    field: "reason".to_string(),
+                },
+                CompatIssue::TypeChanged {
+                    path: "MsgV2.mint.amount".to_string(),
+                    old_type: "integer".to_string(),
+                    new_type: "string".to_string(),
+                },
+                CompatIssue::RemovedField {
+                    path: "MsgV2.mint".to_string(),
+                    field: "memo".to_string(),
+                },
+                CompatIssue::RenamedField {
+                    path: "MsgV2.mint".to_string(),
+                    old_name: "recipient".to_string(),
+                    new_name: "to".to_string(),
+                },
+                CompatIssue::TypeChanged {
+                    path: "MsgV2.pause".to_string(),
+                    old_type: "unit variant".to_string(),
+                    new_type: "struct variant".to_string(),
+                },
+                CompatIssue::RemovedVariant {
+                    path: "MsgV2".to_string(),
+                    variant: "unpause".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn compare_schemas_follows_references_of_documented_fields() {
+        let report = compare_schemas(&schema_for!(DocumentedV1), &schema_for!(DocumentedV2));
+        assert_eq!(
+            report.issues,
+            vec![CompatIssue::TypeChanged {
+                path: "DocumentedV1.owner.address".to_string(),
+                old_type: "string".to_string(),
+                new_type: "integer".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn compare_schemas_follows_references_of_optional_fields() {
+        let report = compare_schemas(&schema_for!(OptionalV1), &schema_for!(OptionalV2));
+        assert_eq!(
+            report.issues,
+            vec![CompatIssue::TypeChanged {
+                path: "OptionalV1.owner.address".to_string(),
+                old_type: "string".to_string(),
+                new_type: "integer".to_string(),
+            }]
+        );
+
+        // making a field optional is fine, the other way around is not
+        let report = compare_schemas(&schema_for!(RequiredV1), &schema_for!(OptionalV1));
+        assert_eq!(report, CompatReport::default());
+        let report = compare_schemas(&schema_for!(OptionalV1), &schema_for!(RequiredV1));
+        assert_eq!(
+            report.issues,
+            vec![
+                CompatIssue::TypeChanged {
+                    path: "OptionalV1.owner".to_string(),
+                    old_type: "OwnerV1 | null".to_string(),
+                    new_type: "OwnerV1".to_string(),
+                },
+                CompatIssue::AddedRequiredField {
+                    path: "OptionalV1".to_string(),
+                    field: "owner".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn compare_schemas_handles_recursive_types() {
+        let report = compare_schemas(&schema_for!(Node), &schema_for!(Node));
+        assert!(report.is_compatible());
+    }
+}
@@ -1,11 +1,12 @@
 //! Export schema to file
 
-use std::fs::write;
+use std::fs::{create_dir_all, write};
 use std::path::Path;
 
 use schemars::schema::RootSchema;
 
-use crate::casing::to_snake_case;
+use crate::casing::Casing;
+use crate::{remove_schemas, Api};
 
 // Exports a schema, auto-generating filename based on the metadata title of the generated schema.
 pub fn export_schema(schema: &RootSchema, out_dir: &Path) {
@@ -15,25 +16,57 @@ pub fn export_schema(schema: &RootSchema, out_dir: &Path) {
         .as_ref()
         .map(|b| b.title.clone().unwrap_or_else(|| "untitled".to_string()))
         .unwrap_or_else(|| "unknown".to_string());
-    write_schema(schema, out_dir, &title);
+    write_schema(schema, out_dir, &title, Casing::Snake);
 }
 
 // use this if you want to override the auto-detected name of the object.
 // very useful when creating an alias for a type-alias.
 pub fn export_schema_with_title(schema: &RootSchema, out_dir: &Path, title: &str) {
+    export_schema_with_casing(schema, out_dir, title, Casing::Snake);
+}
+
+// Like `export_schema_with_title` but allows choosing how the file name is derived from the title.
+pub fn export_schema_with_casing(schema: &RootSchema, out_dir: &Path, title: &str, casing: Casing) {
     let mut schema = schema.clone();
     // set the title explicitly on the schema's metadata
     if let Some(metadata) = &mut schema.schema.metadata {
         metadata.title = Some(title.to_string());
     }
-    write_schema(&schema, out_dir, title);
+    write_schema(&schema, out_dir, title, casing);
+}
+
+/// Exports the full API of a contract into `out_dir`.
+///
+/// Existing schemas in `out_dir` and its `raw` subdirectory are removed first. Then the
+/// combined interface description is written to `<contract_name>.json` and the schemas of the
+/// individual messages and query responses are written to `raw/`.
+///
+/// Panics on any error writing out the schemas.
+pub fn export_api(out_dir: &Path, api: &Api) {
+    let raw_dir = out_dir.join("raw");
+    create_dir_all(&raw_dir).unwrap();
+    remove_schemas(out_dir).unwrap();
+    remove_schemas(&raw_dir).unwrap();
+
+    let api = api.clone().render();
+
+    let path = out_dir.join(format!("{}.json", api.contract_name()));
+    let json = api.to_string().unwrap();
+    write(&path, json + "\n").unwrap();
+    println!("Exported the full API as {}", path.to_str().unwrap());
+
+    for (filename, json) in api.to_schema_files().unwrap() {
+        let path = raw_dir.join(filename);
+        write(&path, json + "\n").unwrap();
+        println!("Exported {}", path.to_str().unwrap());
+    }
 }
 
 /// Writes schema to file. Overwrites existing file.
 /// Panics on any error writing out the schema.
-fn write_schema(schema: &RootSchema, out_dir: &Path, title: &str) {
+fn write_schema(schema: &RootSchema, out_dir: &Path, title: &str, casing: Casing) {
     // first, we set the title as we wish
-    let path = out_dir.join(format!("{}.json", to_snake_case(title)));
+    let path = out_dir.join(format!("{}.json", casing.apply(title)));
     let json = serde_json::to_string_pretty(schema).unwrap();
     write(&path, json + "\n").unwrap();
     println!("Created {}", path.to_str().unwrap());
@@ -5,6 +5,9 @@ use std::collections::BTreeMap;
 use schemars::schema::RootSchema;
 use thiserror::Error;
 
+use crate::casing::to_pascal_case;
+use crate::typescript::TypeScriptWriter;
+
 /// The version of the CosmWasm IDL.
 ///
 /// Follows Semantic Versioning 2.0.0: <https://semver.org/>
@@ -12,6 +15,7 @@ use thiserror::Error;
 pub const IDL_VERSION: &str = "1.0.0";
 
 /// Rust representation of a contract's API.
+#[derive(Clone)]
 pub struct Api {
     pub contract_name: String,
     pub contract_version: String,
@@ -81,6 +85,10 @@ pub struct JsonApi {
 }
 
 impl JsonApi {
+    pub fn contract_name(&self) -> &str {
+        &self.contract_name
+    }
+
     pub fn to_string(&self) -> Result<String, EncodeError> {
         serde_json::to_string_pretty(&self).map_err(Into::into)
     }
@@ -127,6 +135,33 @@ impl JsonApi {
         Ok(result)
     }
 
+    /// Renders TypeScript definitions (`.d.ts`) for all messages and query responses.
+    ///
+    /// Query responses are named after the query variant, e.g. the response to
+    /// `balance` becomes `BalanceResponse`.
+    pub fn to_typescript(&self) -> String {
+        let mut writer = TypeScriptWriter::new();
+        writer.add("InstantiateMsg", &self.instantiate);
+        if let Some(execute) = &self.execute {
+            writer.add("ExecuteMsg", execute);
+        }
+        if let Some(query) = &self.query {
+            writer.add("QueryMsg", query);
+        }
+        if let Some(migrate) = &self.migrate {
+            writer.add("MigrateMsg", migrate);
+        }
+        if let Some(sudo) = &self.sudo {
+            writer.add("SudoMsg", sudo);
+        }
+        if let Some(responses) = &self.responses {
+            for (name, response) in responses {
+                writer.add(format!("{}Response", to_pascal_case(name)), response);
+            }
+        }
+        writer.render()
+    }
+
     pub fn to_writer(&self, writer: impl std::io::Write) -> Result<(), EncodeError> {
         serde_json::to_writer_pretty(writer, self).map_err(Into::into)
     }
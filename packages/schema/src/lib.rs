@@ -1,14 +1,21 @@
 mod casing;
+mod compat;
 mod export;
 mod idl;
 mod query_response;
 mod remove;
 mod schema_for;
+mod typescript;
+mod validate;
 
-pub use export::{export_schema, export_schema_with_title};
+pub use casing::Casing;
+pub use compat::{compare_schemas, CompatIssue, CompatReport};
+pub use export::{export_api, export_schema, export_schema_with_casing, export_schema_with_title};
 pub use idl::{Api, IDL_VERSION};
 pub use query_response::{combine_subqueries, IntegrityError, QueryResponses};
-pub use remove::remove_schemas;
+pub use remove::{remove_schemas, remove_schemas_matching};
+pub use typescript::{to_typescript, TypeScriptWriter};
+pub use validate::{assert_fixtures_valid, validate_instance, ValidationError};
 
 // Re-exports
 /// An attribute macro that annotates types with things they need to be properly (de)serialized
@@ -17,6 +24,11 @@ pub use remove::remove_schemas;
 /// This derives things like `serde::Serialize` or `schemars::JsonSchema`, makes sure
 /// variants are `snake_case` in the resulting JSON, and so forth.
 ///
+/// An optional `#[schema(...)]` attribute customizes the output:
+/// - `title = "..."` - overrides the title of the generated schema
+/// - `casing = "snake" | "camel"` - casing of enum variants or struct fields in the JSON
+///   (enum variants are `snake` by default, struct fields are kept as they are)
+///
 /// # Example
 /// ```
 /// use cosmwasm_schema::{cw_serde, QueryResponses};
@@ -34,6 +46,12 @@ pub use remove::remove_schemas;
 ///     #[returns(String)]
 ///     AccountName { account: String },
 /// }
+///
+/// #[cw_serde]
+/// #[schema(title = "NftInfoQuery", casing = "camel")]
+/// pub enum QueryNFTInfo {
+///     TokenUri {},
+/// }
 /// ```
 pub use cosmwasm_schema_derive::cw_serde;
 /// Generates an [`Api`](crate::Api) for the contract. The body describes the message
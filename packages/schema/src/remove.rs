@@ -11,27 +11,76 @@ fn is_hidden(path: &path::Path) -> bool {
     }
 }
 
-fn is_json(path: &path::Path) -> bool {
+/// Matches a file name against a glob pattern supporting `*` (any sequence of characters)
+/// and `?` (exactly one character).
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // position of the last `*` in the pattern and the name position it matched up to
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn matches_any(path: &path::Path, patterns: &[&str]) -> bool {
     match path.file_name() {
-        Some(name) => name.to_os_string().to_string_lossy().ends_with(".json"),
-        None => false, // a path without filename is no *.json
+        Some(name) => {
+            let name = name.to_string_lossy();
+            patterns.iter().any(|pattern| glob_match(pattern, &name))
+        }
+        None => false,
     }
 }
 
-pub fn remove_schemas(schemas_dir: &path::Path) -> Result<(), io::Error> {
-    let file_paths = fs::read_dir(schemas_dir)?
+/// Removes all JSON files from the given directory. Hidden files, directories
+/// and symlinks are left untouched.
+///
+/// Returns the paths of the removed files.
+pub fn remove_schemas(schemas_dir: &path::Path) -> Result<Vec<path::PathBuf>, io::Error> {
+    remove_schemas_matching(schemas_dir, &["*.json"])
+}
+
+/// Like [`remove_schemas`] but only removes files whose name matches one of the given
+/// glob patterns (e.g. `"response_to_*.json"`). Patterns support `*` and `?`.
+///
+/// Returns the paths of the removed files.
+pub fn remove_schemas_matching(
+    schemas_dir: &path::Path,
+    patterns: &[&str],
+) -> Result<Vec<path::PathBuf>, io::Error> {
+    let file_paths: Vec<_> = fs::read_dir(schemas_dir)?
         .filter_map(Result::ok) // skip read errors on entries
         .map(|entry| entry.path())
         .filter(|path| is_regular_file(path).unwrap_or(false)) // skip directories and symlinks
         .filter(|path| !is_hidden(path)) // skip hidden
-        .filter(|path| is_json(path)) // skip non JSON
-        ;
+        .filter(|path| matches_any(path, patterns))
+        .collect();
 
-    for file_path in file_paths {
+    for file_path in &file_paths {
         println!("Removing {:?} …", file_path);
         fs::remove_file(file_path)?;
     }
-    Ok(())
+    Ok(file_paths)
 }
 
 #[cfg(test)]
@@ -69,31 +118,71 @@ mod tests {
     }
 
     #[test]
-    fn is_json_works() {
-        assert!(!is_json(Path::new("/foo")));
-        assert!(!is_json(Path::new("/foo/bar")));
-        assert!(!is_json(Path::new("/foo/bar.txt")));
-        assert!(!is_json(Path::new("~foo")));
-        assert!(!is_json(Path::new("foo")));
-        assert!(!is_json(Path::new("foo.json5")));
-
-        assert!(is_json(Path::new("/.json")));
-        assert!(is_json(Path::new("/foo/.bar.json")));
-        assert!(is_json(Path::new("/foo/bar.json")));
-        assert!(is_json(Path::new("foo.json")));
+    fn matches_any_works_for_json() {
+        assert!(!matches_any(Path::new("/foo"), &["*.json"]));
+        assert!(!matches_any(Path::new("/foo/bar"), &["*.json"]));
+        assert!(!matches_any(Path::new("/foo/bar.txt"), &["*.json"]));
+        assert!(!matches_any(Path::new("~foo"), &["*.json"]));
+        assert!(!matches_any(Path::new("foo"), &["*.json"]));
+        assert!(!matches_any(Path::new("foo.json5"), &["*.json"]));
+
+        assert!(matches_any(Path::new("/.json"), &["*.json"]));
+        assert!(matches_any(Path::new("/foo/.bar.json"), &["*.json"]));
+        assert!(matches_any(Path::new("/foo/bar.json"), &["*.json"]));
+        assert!(matches_any(Path::new("foo.json"), &["*.json"]));
 
         // no filename
-        assert!(!is_json(Path::new("/")));
-        assert!(!is_json(Path::new("")));
+        assert!(!matches_any(Path::new("/"), &["*.json"]));
+        assert!(!matches_any(Path::new(""), &["*.json"]));
 
         // invalid UTF-8
         #[cfg(any(unix, target_os = "redox"))]
         {
             use std::os::unix::ffi::OsStrExt;
             let non_hidden = OsStr::from_bytes(&[0x66, 0x6f, 0x80, 0x6f]); // fo�o
-            assert!(!is_json(Path::new(non_hidden)));
+            assert!(!matches_any(Path::new(non_hidden), &["*.json"]));
             let hidden = OsStr::from_bytes(&[0x66, 0x6f, 0x80, 0x6f, 0x2e, 0x6a, 0x73, 0x6f, 0x6e]); // fo�o.json
-            assert!(is_json(Path::new(hidden)));
+            assert!(matches_any(Path::new(hidden), &["*.json"]));
         }
     }
+
+    #[test]
+    fn glob_match_works() {
+        assert!(glob_match("*.json", "foo.json"));
+        assert!(glob_match("*.json", ".json"));
+        assert!(!glob_match("*.json", "foo.json5"));
+        assert!(glob_match("response_to_*.json", "response_to_balance.json"));
+        assert!(!glob_match("response_to_*.json", "query.json"));
+        assert!(glob_match("?uery.json", "query.json"));
+        assert!(!glob_match("?query.json", "query.json"));
+        assert!(glob_match("*a*b*", "xxaxxbxx"));
+        assert!(!glob_match("*a*b", "xxbxxa"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn remove_schemas_matching_works() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "query.json",
+            "response_to_a.json",
+            "response_to_b.json",
+            "notes.txt",
+        ] {
+            fs::write(dir.path().join(name), "{}").unwrap();
+        }
+
+        let removed = remove_schemas_matching(dir.path(), &["response_to_*.json"]).unwrap();
+        let mut removed: Vec<_> = removed
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        removed.sort();
+        assert_eq!(removed, ["response_to_a.json", "response_to_b.json"]);
+
+        let removed = remove_schemas(dir.path()).unwrap();
+        assert_eq!(removed, [dir.path().join("query.json")]);
+        assert!(dir.path().join("notes.txt").exists());
+    }
 }
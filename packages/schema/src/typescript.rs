@@ -0,0 +1,316 @@
+//! Generation of TypeScript definitions from JSON schemas
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use schemars::schema::{
+    InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec, SubschemaValidation,
+};
+
+const INDENT: &str = "  ";
+
+/// Collects named types and renders them as a TypeScript declaration file (`.d.ts`).
+///
+/// Definitions shared between multiple schemas (e.g. `Uint128` or `Addr`) are emitted once.
+#[derive(Default)]
+pub struct TypeScriptWriter {
+    definitions: BTreeMap<String, Schema>,
+    roots: Vec<(String, Schema)>,
+}
+
+impl TypeScriptWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the root schema as a type called `name` along with all its definitions.
+    pub fn add(&mut self, name: impl Into<String>, root: &RootSchema) -> &mut Self {
+        for (def_name, def) in &root.definitions {
+            self.definitions
+                .entry(def_name.clone())
+                .or_insert_with(|| def.clone());
+        }
+        self.roots
+            .push((name.into(), Schema::Object(root.schema.clone())));
+        self
+    }
+
+    /// Renders all collected types. Definitions come first in alphabetical order,
+    /// followed by the root types in the order they were added.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let roots = self.roots.iter().map(|(name, schema)| (name, schema));
+        let definitions = self
+            .definitions
+            .iter()
+            .filter(|(name, _)| !self.roots.iter().any(|(root, _)| root == *name));
+        for (name, schema) in definitions.chain(roots) {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            write_doc(&mut out, schema, "");
+            writeln!(out, "export type {} = {};", name, type_of(schema, "")).unwrap();
+        }
+        out
+    }
+}
+
+/// Converts a single root schema into TypeScript definitions, exporting the root type as `name`.
+pub fn to_typescript(name: &str, root: &RootSchema) -> String {
+    TypeScriptWriter::new().add(name, root).render()
+}
+
+fn write_doc(out: &mut String, schema: &Schema, indent: &str) {
+    let description = match schema {
+        Schema::Object(SchemaObject {
+            metadata: Some(metadata),
+            ..
+        }) => metadata.description.as_deref(),
+        _ => None,
+    };
+    if let Some(description) = description {
+        writeln!(out, "{}/**", indent).unwrap();
+        for line in description.lines() {
+            writeln!(
+                out,
+                "{} * {}",
+                indent,
+                line.replace("*/", "*\\/").trim_end()
+            )
+            .unwrap();
+        }
+        writeln!(out, "{} */", indent).unwrap();
+    }
+}
+
+fn type_of(schema: &Schema, indent: &str) -> String {
+    match schema {
+        Schema::Bool(true) => "unknown".to_string(),
+        Schema::Bool(false) => "never".to_string(),
+        Schema::Object(obj) => type_of_object(obj, indent),
+    }
+}
+
+fn type_of_object(obj: &SchemaObject, indent: &str) -> String {
+    if let Some(reference) = &obj.reference {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    if let Some(value) = &obj.const_value {
+        return value.to_string();
+    }
+    if let Some(values) = &obj.enum_values {
+        return union(values.iter().map(|v| v.to_string()).collect());
+    }
+    if let Some(subschemas) = &obj.subschemas {
+        if let Some(ty) = type_of_subschemas(subschemas, indent) {
+            return ty;
+        }
+    }
+
+    match &obj.instance_type {
+        Some(SingleOrVec::Single(ty)) => type_of_instance(**ty, obj, indent),
+        Some(SingleOrVec::Vec(types)) => union(
+            types
+                .iter()
+                .map(|ty| type_of_instance(*ty, obj, indent))
+                .collect(),
+        ),
+        None => "unknown".to_string(),
+    }
+}
+
+fn type_of_subschemas(subschemas: &SubschemaValidation, indent: &str) -> Option<String> {
+    let render = |schemas: &[Schema]| -> Vec<String> {
+        schemas.iter().map(|s| type_of(s, indent)).collect()
+    };
+    if let Some(all_of) = &subschemas.all_of {
+        return Some(render(all_of).join(" & "));
+    }
+    if let Some(one_of) = &subschemas.one_of {
+        return Some(union(render(one_of)));
+    }
+    if let Some(any_of) = &subschemas.any_of {
+        return Some(union(render(any_of)));
+    }
+    None
+}
+
+fn type_of_instance(ty: InstanceType, obj: &SchemaObject, indent: &str) -> String {
+    match ty {
+        InstanceType::Null => "null".to_string(),
+        InstanceType::Boolean => "boolean".to_string(),
+        InstanceType::Integer | InstanceType::Number => "number".to_string(),
+        InstanceType::String => "string".to_string(),
+        InstanceType::Array => match obj.array.as_ref().and_then(|a| a.items.as_ref()) {
+            Some(SingleOrVec::Single(item)) => {
+                let item = type_of(item, indent);
+                if item.contains(' ') {
+                    format!("({})[]", item)
+                } else {
+                    format!("{}[]", item)
+                }
+            }
+            Some(SingleOrVec::Vec(items)) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|item| type_of(item, indent))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => "unknown[]".to_string(),
+        },
+        InstanceType::Object => type_of_object_literal(obj, indent),
+    }
+}
+
+fn type_of_object_literal(obj: &SchemaObject, indent: &str) -> String {
+    let validation = match &obj.object {
+        Some(validation) => validation,
+        None => return "Record<string, unknown>".to_string(),
+    };
+
+    let inner = format!("{}{}", indent, INDENT);
+    let mut out = String::new();
+    for (name, property) in &validation.properties {
+        write_doc(&mut out, property, &inner);
+        let optional = if validation.required.contains(name) {
+            ""
+        } else {
+            "?"
+        };
+        writeln!(
+            out,
+            "{}{}{}: {};",
+            inner,
+            property_name(name),
+            optional,
+            type_of(property, &inner)
+        )
+        .unwrap();
+    }
+    match validation.additional_properties.as_deref() {
+        None | Some(Schema::Bool(false)) => {}
+        Some(additional) => writeln!(
+            out,
+            "{}[key: string]: {};",
+            inner,
+            type_of(additional, &inner)
+        )
+        .unwrap(),
+    }
+
+    if out.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{\n{}{}}}", out, indent)
+    }
+}
+
+fn union(mut types: Vec<String>) -> String {
+    types.dedup();
+    match types.len() {
+        0 => "never".to_string(),
+        _ => types.join(" | "),
+    }
+}
+
+fn property_name(name: &str) -> String {
+    let is_identifier = matches!(name.chars().next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schema_for;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    struct InstantiateMsg {
+        /// The admin address
+        admin: String,
+        cap: Option<u64>,
+        tags: Vec<String>,
+        pair: (u8, bool),
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum ExecuteMsg {
+        Freeze {},
+        Mint { amount: Amount },
+        Reset,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Amount(String);
+
+    #[test]
+    fn to_typescript_works_for_structs() {
+        let ts = to_typescript("InstantiateMsg", &schema_for!(InstantiateMsg));
+        assert_eq!(
+            ts,
+            r#"export type InstantiateMsg = {
+  /**
+   * The admin address
+   */
+  admin: string;
+  cap?: number | null;
+  pair: [number, boolean];
+  tags: string[];
+};
+"#
+        );
+    }
+
+    #[test]
+    fn to_typescript_works_for_enums() {
+        let ts = to_typescript("ExecuteMsg", &schema_for!(ExecuteMsg));
+        assert_eq!(
+            ts,
+            r#"export type Amount = string;
+
+export type ExecuteMsg = "reset" | {
+  freeze: {};
+} | {
+  mint: {
+    amount: Amount;
+  };
+};
+"#
+        );
+    }
+
+    #[test]
+    fn writer_deduplicates_definitions() {
+        let ts = TypeScriptWriter::new()
+            .add("ExecuteMsg", &schema_for!(ExecuteMsg))
+            .add("OtherMsg", &schema_for!(ExecuteMsg))
+            .render();
+        assert_eq!(ts.matches("export type Amount =").count(), 1);
+        assert_eq!(ts.matches("export type OtherMsg =").count(), 1);
+    }
+
+    #[test]
+    fn property_name_quotes_invalid_identifiers() {
+        assert_eq!(property_name("admin"), "admin");
+        assert_eq!(property_name("_a1"), "_a1");
+        assert_eq!(property_name("1a"), "\"1a\"");
+        assert_eq!(property_name("foo-bar"), "\"foo-bar\"");
+    }
+}
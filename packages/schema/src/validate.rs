@@ -0,0 +1,430 @@
+//! Validation of JSON instances against generated schemas
+
+use std::fs;
+use std::path::Path;
+
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ValidationError {
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+    #[error("{path}: {msg}")]
+    Mismatch { path: String, msg: String },
+}
+
+impl ValidationError {
+    fn mismatch(path: &str, msg: impl Into<String>) -> Self {
+        ValidationError::Mismatch {
+            path: path.to_string(),
+            msg: msg.into(),
+        }
+    }
+}
+
+/// Checks that the JSON document conforms to the schema.
+///
+/// This supports the subset of JSON Schema (draft-07) generated by schemars for
+/// CosmWasm message types: types, properties, required fields, additional properties,
+/// enums, constants, `oneOf`/`anyOf`/`allOf`, array items and numeric/length bounds.
+///
+/// # Example
+/// ```
+/// use cosmwasm_schema::{cw_serde, schema_for, validate_instance};
+///
+/// #[cw_serde]
+/// enum ExecuteMsg {
+///     Mint { amount: u64 },
+/// }
+///
+/// let schema = schema_for!(ExecuteMsg);
+/// validate_instance(&schema, r#"{"mint":{"amount":5}}"#).unwrap();
+/// validate_instance(&schema, r#"{"mint":{"amount":"5"}}"#).unwrap_err();
+/// ```
+pub fn validate_instance(schema: &RootSchema, json: &str) -> Result<(), ValidationError> {
+    let instance: Value = serde_json::from_str(json)?;
+    Validator { root: schema }.validate_object("$", &schema.schema, &instance)
+}
+
+/// Validates every `*.json` file in `dir` against the schema and panics with the file
+/// name and the reason if one of them does not conform.
+///
+/// Use this in a contract's tests to make sure the example messages in `testdata/`
+/// don't drift from the actual message types.
+pub fn assert_fixtures_valid(schema: &RootSchema, dir: impl AsRef<Path>) {
+    let dir = dir.as_ref();
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("Error reading fixture directory {:?}: {}", dir, e))
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension() == Some("json".as_ref()))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let json = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Error reading fixture {:?}: {}", path, e));
+        if let Err(err) = validate_instance(schema, &json) {
+            panic!("Fixture {:?} does not match the schema: {}", path, err);
+        }
+    }
+}
+
+struct Validator<'a> {
+    root: &'a RootSchema,
+}
+
+impl<'a> Validator<'a> {
+    fn validate(
+        &self,
+        path: &str,
+        schema: &Schema,
+        instance: &Value,
+    ) -> Result<(), ValidationError> {
+        match schema {
+            Schema::Bool(true) => Ok(()),
+            Schema::Bool(false) => Err(ValidationError::mismatch(path, "no value allowed")),
+            Schema::Object(obj) => self.validate_object(path, obj, instance),
+        }
+    }
+
+    fn validate_object(
+        &self,
+        path: &str,
+        schema: &SchemaObject,
+        instance: &Value,
+    ) -> Result<(), ValidationError> {
+        if let Some(reference) = &schema.reference {
+            let name = reference.trim_start_matches("#/definitions/");
+            let target = self.root.definitions.get(name).ok_or_else(|| {
+                ValidationError::mismatch(path, format!("unknown reference {}", reference))
+            })?;
+            return self.validate(path, target, instance);
+        }
+
+        if let Some(value) = &schema.const_value {
+            if value != instance {
+                return Err(ValidationError::mismatch(
+                    path,
+                    format!("expected {}", value),
+                ));
+            }
+        }
+        if let Some(values) = &schema.enum_values {
+            if !values.contains(instance) {
+                let values: Vec<_> = values.iter().map(Value::to_string).collect();
+                return Err(ValidationError::mismatch(
+                    path,
+                    format!("expected one of {}", values.join(", ")),
+                ));
+            }
+        }
+
+        if let Some(subschemas) = &schema.subschemas {
+            if let Some(all_of) = &subschemas.all_of {
+                for subschema in all_of {
+                    self.validate(path, subschema, instance)?;
+                }
+            }
+            if let Some(any_of) = &subschemas.any_of {
+                let errors: Vec<_> = any_of
+                    .iter()
+                    .filter_map(|s| self.validate(path, s, instance).err())
+                    .collect();
+                if errors.len() == any_of.len() {
+                    return Err(deepest_error(path, errors, "anyOf"));
+                }
+            }
+            if let Some(one_of) = &subschemas.one_of {
+                let errors: Vec<_> = one_of
+                    .iter()
+                    .filter_map(|s| self.validate(path, s, instance).err())
+                    .collect();
+                match one_of.len() - errors.len() {
+                    1 => {}
+                    0 => return Err(deepest_error(path, errors, "oneOf")),
+                    matches => {
+                        return Err(ValidationError::mismatch(
+                            path,
+                            format!(
+                                "must match exactly one schema in oneOf, but matches {}",
+                                matches
+                            ),
+                        ))
+                    }
+                }
+            }
+        }
+
+        if let Some(types) = &schema.instance_type {
+            let types: &[InstanceType] = match types {
+                SingleOrVec::Single(ty) => std::slice::from_ref(ty),
+                SingleOrVec::Vec(types) => types,
+            };
+            if !types.iter().any(|ty| has_type(instance, *ty)) {
+                let names: Vec<_> = types.iter().map(|ty| type_name(*ty)).collect();
+                return Err(ValidationError::mismatch(
+                    path,
+                    format!(
+                        "expected {}, got {}",
+                        names.join(" or "),
+                        value_type(instance)
+                    ),
+                ));
+            }
+        }
+
+        match instance {
+            Value::Object(map) => {
+                if let Some(validation) = &schema.object {
+                    for field in &validation.required {
+                        if !map.contains_key(field) {
+                            return Err(ValidationError::mismatch(
+                                path,
+                                format!("missing field `{}`", field),
+                            ));
+                        }
+                    }
+                    for (key, value) in map {
+                        let field_path = format!("{}.{}", path, key);
+                        match validation.properties.get(key) {
+                            Some(property) => self.validate(&field_path, property, value)?,
+                            None => match validation.additional_properties.as_deref() {
+                                Some(Schema::Bool(false)) => {
+                                    return Err(ValidationError::mismatch(
+                                        path,
+                                        format!("unknown field `{}`", key),
+                                    ))
+                                }
+                                Some(additional) => {
+                                    self.validate(&field_path, additional, value)?
+                                }
+                                None => {}
+                            },
+                        }
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(validation) = &schema.array {
+                    if let Some(min) = validation.min_items {
+                        if items.len() < min as usize {
+                            return Err(ValidationError::mismatch(
+                                path,
+                                format!("expected at least {} items", min),
+                            ));
+                        }
+                    }
+                    if let Some(max) = validation.max_items {
+                        if items.len() > max as usize {
+                            return Err(ValidationError::mismatch(
+                                path,
+                                format!("expected at most {} items", max),
+                            ));
+                        }
+                    }
+                    match &validation.items {
+                        Some(SingleOrVec::Single(item_schema)) => {
+                            for (index, item) in items.iter().enumerate() {
+                                self.validate(&format!("{}[{}]", path, index), item_schema, item)?;
+                            }
+                        }
+                        Some(SingleOrVec::Vec(item_schemas)) => {
+                            for (index, (item_schema, item)) in
+                                item_schemas.iter().zip(items).enumerate()
+                            {
+                                self.validate(&format!("{}[{}]", path, index), item_schema, item)?;
+                            }
+                        }
+                        None => {}
+                    }
+                }
+            }
+            Value::Number(number) => {
+                if let (Some(validation), Some(value)) = (&schema.number, number.as_f64()) {
+                    if matches!(validation.minimum, Some(min) if value < min) {
+                        return Err(ValidationError::mismatch(
+                            path,
+                            format!("{} is below the minimum", number),
+                        ));
+                    }
+                    if matches!(validation.maximum, Some(max) if value > max) {
+                        return Err(ValidationError::mismatch(
+                            path,
+                            format!("{} is above the maximum", number),
+                        ));
+                    }
+                }
+            }
+            Value::String(string) => {
+                if let Some(validation) = &schema.string {
+                    let len = string.chars().count();
+                    if matches!(validation.min_length, Some(min) if len < min as usize) {
+                        return Err(ValidationError::mismatch(path, "string is too short"));
+                    }
+                    if matches!(validation.max_length, Some(max) if len > max as usize) {
+                        return Err(ValidationError::mismatch(path, "string is too long"));
+                    }
+                }
+            }
+            Value::Null | Value::Bool(_) => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// If no alternative matches, the error that got furthest into the instance is the most
+/// helpful one (e.g. the error inside the enum variant that was intended).
+fn deepest_error(path: &str, errors: Vec<ValidationError>, keyword: &str) -> ValidationError {
+    let depth = |err: &ValidationError| match err {
+        ValidationError::Mismatch { path, .. } => path.len(),
+        ValidationError::InvalidJson(_) => 0,
+    };
+    match errors.into_iter().max_by_key(depth) {
+        Some(err) if depth(&err) > path.len() => err,
+        _ => ValidationError::mismatch(
+            path,
+            format!("does not match any of the allowed schemas in {}", keyword),
+        ),
+    }
+}
+
+fn has_type(instance: &Value, ty: InstanceType) -> bool {
+    match ty {
+        InstanceType::Null => instance.is_null(),
+        InstanceType::Boolean => instance.is_boolean(),
+        InstanceType::Object => instance.is_object(),
+        InstanceType::Array => instance.is_array(),
+        InstanceType::Number => instance.is_number(),
+        InstanceType::String => instance.is_string(),
+        InstanceType::Integer => match instance {
+            Value::Number(number) => {
+                number.is_i64()
+                    || number.is_u64()
+                    || matches!(number.as_f64(), Some(f) if f.fract() == 0.0)
+            }
+            _ => false,
+        },
+    }
+}
+
+fn type_name(ty: InstanceType) -> &'static str {
+    match ty {
+        InstanceType::Null => "null",
+        InstanceType::Boolean => "boolean",
+        InstanceType::Object => "object",
+        InstanceType::Array => "array",
+        InstanceType::Number => "number",
+        InstanceType::String => "string",
+        InstanceType::Integer => "integer",
+    }
+}
+
+fn value_type(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schema_for;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    #[allow(dead_code)]
+    #[derive(Deserialize, JsonSchema)]
+    #[serde(deny_unknown_fields, rename_all = "snake_case")]
+    enum ExecuteMsg {
+        Transfer {
+            recipient: String,
+            amount: u32,
+            memo: Option<String>,
+        },
+        Batch {
+            pairs: Vec<(String, u8)>,
+        },
+        Freeze,
+    }
+
+    fn mismatch(json: &str) -> String {
+        match validate_instance(&schema_for!(ExecuteMsg), json).unwrap_err() {
+            ValidationError::Mismatch { path, msg } => format!("{}: {}", path, msg),
+            err => panic!("Unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn validate_instance_accepts_valid_messages() {
+        let schema = schema_for!(ExecuteMsg);
+        for json in [
+            r#"{"transfer":{"recipient":"alice","amount":5}}"#,
+            r#"{"transfer":{"recipient":"alice","amount":5,"memo":null}}"#,
+            r#"{"transfer":{"recipient":"alice","amount":5,"memo":"hi"}}"#,
+            r#"{"batch":{"pairs":[["a",1],["b",255]]}}"#,
+            r#""freeze""#,
+        ] {
+            validate_instance(&schema, json).unwrap();
+        }
+    }
+
+    #[test]
+    fn validate_instance_rejects_invalid_messages() {
+        assert_eq!(
+            mismatch(r#""thaw""#),
+            "$: does not match any of the allowed schemas in oneOf"
+        );
+        assert_eq!(
+            mismatch(r#"{"transfer":{"recipient":"alice"}}"#),
+            "$.transfer: missing field `amount`"
+        );
+        assert_eq!(
+            mismatch(r#"{"transfer":{"recipient":"alice","amount":-1}}"#),
+            "$.transfer.amount: -1 is below the minimum"
+        );
+        assert_eq!(
+            mismatch(r#"{"transfer":{"recipient":"alice","amount":"1"}}"#),
+            "$.transfer.amount: expected integer, got string"
+        );
+        assert_eq!(
+            mismatch(r#"{"transfer":{"recipient":"alice","amount":1,"foo":1}}"#),
+            "$.transfer: unknown field `foo`"
+        );
+        assert_eq!(
+            mismatch(r#"{"batch":{"pairs":[["a",-1]]}}"#),
+            "$.batch.pairs[0][1]: -1 is below the minimum"
+        );
+    }
+
+    #[test]
+    fn validate_instance_rejects_invalid_json() {
+        let err = validate_instance(&schema_for!(ExecuteMsg), "{").unwrap_err();
+        assert!(matches!(err, ValidationError::InvalidJson(_)));
+    }
+
+    #[test]
+    fn assert_fixtures_valid_works() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("freeze.json"), r#""freeze""#).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not json").unwrap();
+        assert_fixtures_valid(&schema_for!(ExecuteMsg), dir.path());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match the schema")]
+    fn assert_fixtures_valid_panics_for_invalid_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("thaw.json"), r#""thaw""#).unwrap();
+        assert_fixtures_valid(&schema_for!(ExecuteMsg), dir.path());
+    }
+}
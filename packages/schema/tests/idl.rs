@@ -284,3 +284,209 @@ fn nested_name_collision_caught() {
         query: NestedNameCollision,
     };
 }
+
+#[test]
+fn test_typescript() {
+    let ts = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+        execute: ExecuteMsg,
+        sudo: SudoMsg,
+        migrate: MigrateMsg,
+    }
+    .render()
+    .to_typescript();
+
+    for name in [
+        "InstantiateMsg",
+        "ExecuteMsg",
+        "QueryMsg",
+        "MigrateMsg",
+        "SudoMsg",
+        "BalanceResponse",
+    ] {
+        assert!(
+            ts.contains(&format!("export type {} = ", name)),
+            "missing {}",
+            name
+        );
+    }
+    assert!(ts.contains("export type BalanceResponse = number;"));
+}
+
+#[test]
+fn test_export_api() {
+    let out_dir = tempfile::tempdir().unwrap();
+    // stale files from a previous export
+    std::fs::create_dir(out_dir.path().join("raw")).unwrap();
+    std::fs::write(out_dir.path().join("raw/response_to_gone.json"), "{}").unwrap();
+    std::fs::write(out_dir.path().join("README.md"), "keep me").unwrap();
+
+    let api = generate_api! {
+        name: "test",
+        version: "0.1.0",
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+        execute: ExecuteMsg,
+    };
+    cosmwasm_schema::export_api(out_dir.path(), &api);
+
+    let mut files: Vec<_> = walk(out_dir.path());
+    files.sort();
+    assert_eq!(
+        files,
+        [
+            "README.md",
+            "raw/execute.json",
+            "raw/instantiate.json",
+            "raw/query.json",
+            "raw/response_to_balance.json",
+            "test.json",
+        ]
+    );
+}
+
+fn walk(dir: &std::path::Path) -> Vec<String> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        if path.is_dir() {
+            files.extend(walk(&path).into_iter().map(|f| format!("{}/{}", name, f)));
+        } else {
+            files.push(name);
+        }
+    }
+    files
+}
+
+#[test]
+fn test_schema_attribute() {
+    #[cw_serde]
+    #[schema(title = "NftQuery", casing = "camel")]
+    pub enum QueryNFTInfo {
+        TokenUri {},
+    }
+
+    let schema = cosmwasm_schema::schema_for!(QueryNFTInfo);
+    let json = serde_json::to_value(&schema).unwrap();
+    assert_eq!(json["title"], "NftQuery");
+    assert_eq!(json["oneOf"][0]["required"][0], "tokenUri");
+
+    let out_dir = tempfile::tempdir().unwrap();
+    cosmwasm_schema::export_schema_with_title(&schema, out_dir.path(), "QueryNFTInfo");
+    assert!(out_dir.path().join("query_nft_info.json").exists());
+    cosmwasm_schema::export_schema_with_casing(
+        &schema,
+        out_dir.path(),
+        "QueryNFTInfo",
+        cosmwasm_schema::Casing::Camel,
+    );
+    assert!(out_dir.path().join("queryNftInfo.json").exists());
+}
+
+#[test]
+fn test_query_responses_follow_casing() {
+    #[cw_serde]
+    #[schema(casing = "camel")]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(String)]
+        TokenUri {},
+        #[returns(u64)]
+        #[serde(rename = "nft_info")]
+        NFTInfo {},
+    }
+
+    let api = generate_api! {
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+    }
+    .render();
+    let files: Vec<_> = api
+        .to_schema_files()
+        .unwrap()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert!(files.contains(&"response_to_tokenUri.json".to_string()));
+    assert!(files.contains(&"response_to_nft_info.json".to_string()));
+}
+
+#[test]
+fn test_query_responses_docs() {
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        /// Returns the balance
+        #[returns(BalanceResponse)]
+        Balance {},
+        /// Returns the number of holders
+        #[returns(u64)]
+        Holders {},
+    }
+
+    /// The balance of an account
+    #[cw_serde]
+    pub struct BalanceResponse {
+        pub amount: u64,
+    }
+
+    let responses = QueryMsg::response_schemas().unwrap();
+    let description = |name: &str| {
+        responses[name]
+            .schema
+            .metadata
+            .as_ref()
+            .unwrap()
+            .description
+            .clone()
+    };
+    // the response's own docs take precedence
+    assert_eq!(
+        description("balance").as_deref(),
+        Some("The balance of an account")
+    );
+    assert_eq!(
+        description("holders").as_deref(),
+        Some("Returns the number of holders")
+    );
+}
+
+#[test]
+fn test_query_responses_generic_response_types() {
+    #[cw_serde]
+    pub struct PageResult<T> {
+        pub items: Vec<T>,
+        pub next: Option<String>,
+    }
+
+    #[cw_serde]
+    pub struct TokenInfo {
+        pub id: String,
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum QueryMsg {
+        #[returns(PageResult<TokenInfo>)]
+        Tokens {},
+        #[returns(std::collections::BTreeMap<String, Vec<TokenInfo>>)]
+        TokensByOwner {},
+    }
+
+    let responses = QueryMsg::response_schemas().unwrap();
+    let tokens = &responses["tokens"];
+    assert_eq!(
+        tokens.schema.metadata.as_ref().unwrap().title.as_deref(),
+        Some("PageResult_for_TokenInfo")
+    );
+    assert!(tokens.definitions.contains_key("TokenInfo"));
+    let by_owner = &responses["tokens_by_owner"];
+    assert_eq!(
+        by_owner.schema.metadata.as_ref().unwrap().title.as_deref(),
+        Some("Map_of_Array_of_TokenInfo")
+    );
+}
@@ -0,0 +1,228 @@
+//! Implementations of [`arbitrary::Arbitrary`] for property-based testing and fuzzing.
+//!
+//! The generated values are valid in the sense that they can be serialized and
+//! deserialized again, e.g. denoms and attribute keys follow the rules of the chain.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::addresses::Addr;
+use crate::binary::Binary;
+use crate::coin::Coin;
+use crate::math::Uint128;
+use crate::results::{
+    Attribute, BankMsg, CosmosMsg, Empty, Event, ReplyOn, Response, SubMsg, WasmMsg,
+};
+use crate::timestamp::Timestamp;
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const ALPHANUMERIC: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Creates a string starting with a lowercase letter followed by lowercase letters and digits.
+fn identifier(u: &mut Unstructured, min_len: usize, max_len: usize) -> Result<String> {
+    let len = u.int_in_range(min_len..=max_len)?;
+    let mut out = String::with_capacity(len);
+    out.push(*u.choose(LOWERCASE)? as char);
+    for _ in 1..len {
+        out.push(*u.choose(ALPHANUMERIC)? as char);
+    }
+    Ok(out)
+}
+
+fn short_vec<'a, T>(
+    u: &mut Unstructured<'a>,
+    max_len: usize,
+    mut item: impl FnMut(&mut Unstructured<'a>) -> Result<T>,
+) -> Result<Vec<T>> {
+    let len = u.int_in_range(0..=max_len)?;
+    (0..len).map(|_| item(u)).collect()
+}
+
+impl<'a> Arbitrary<'a> for Uint128 {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Uint128::new(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Binary {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Binary(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Addr {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Addr::unchecked(identifier(u, 3, 64)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Timestamp {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Timestamp::from_nanos(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Coin {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Coin {
+            denom: identifier(u, 3, 16)?,
+            amount: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Attribute {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // keys starting with "_" are reserved
+        Ok(Attribute {
+            key: identifier(u, 1, 16)?,
+            value: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Event {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // types must be at least 2 characters long
+        Ok(Event {
+            ty: identifier(u, 2, 16)?,
+            attributes: short_vec(u, 5, Attribute::arbitrary)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for BankMsg {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let amount = short_vec(u, 3, Coin::arbitrary)?;
+        Ok(if u.arbitrary()? {
+            BankMsg::Send {
+                to_address: Addr::arbitrary(u)?.into(),
+                amount,
+            }
+        } else {
+            BankMsg::Burn { amount }
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for WasmMsg {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let contract_addr = Addr::arbitrary(u)?.into();
+        Ok(match u.int_in_range(0..=4)? {
+            0 => WasmMsg::Execute {
+                contract_addr,
+                msg: u.arbitrary()?,
+                funds: short_vec(u, 3, Coin::arbitrary)?,
+            },
+            1 => WasmMsg::Instantiate {
+                admin: if u.arbitrary()? {
+                    Some(contract_addr)
+                } else {
+                    None
+                },
+                code_id: u.arbitrary()?,
+                msg: u.arbitrary()?,
+                funds: short_vec(u, 3, Coin::arbitrary)?,
+                label: u.arbitrary()?,
+            },
+            2 => WasmMsg::Migrate {
+                contract_addr,
+                new_code_id: u.arbitrary()?,
+                msg: u.arbitrary()?,
+            },
+            3 => WasmMsg::UpdateAdmin {
+                contract_addr,
+                admin: Addr::arbitrary(u)?.into(),
+            },
+            _ => WasmMsg::ClearAdmin { contract_addr },
+        })
+    }
+}
+
+/// Only bank and Wasm messages are generated, which are available on all chains.
+impl<'a> Arbitrary<'a> for CosmosMsg<Empty> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(if u.arbitrary()? {
+            CosmosMsg::Bank(u.arbitrary()?)
+        } else {
+            CosmosMsg::Wasm(u.arbitrary()?)
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for ReplyOn {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(u.choose(&[
+            ReplyOn::Always,
+            ReplyOn::Error,
+            ReplyOn::Success,
+            ReplyOn::Never,
+        ])?
+        .clone())
+    }
+}
+
+impl<'a> Arbitrary<'a> for SubMsg<Empty> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(SubMsg {
+            id: u.arbitrary()?,
+            msg: u.arbitrary()?,
+            gas_limit: u.arbitrary()?,
+            reply_on: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Response<Empty> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Response {
+            messages: short_vec(u, 3, SubMsg::arbitrary)?,
+            attributes: short_vec(u, 5, Attribute::arbitrary)?,
+            events: short_vec(u, 3, Event::arbitrary)?,
+            data: u.arbitrary()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_slice, to_vec};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use std::fmt::Debug;
+
+    fn assert_roundtrips<T>()
+    where
+        T: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned + PartialEq + Debug,
+    {
+        for seed in 0u8..64 {
+            let data: Vec<u8> = (0..1024u32)
+                .map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed))
+                .collect();
+            let mut u = Unstructured::new(&data);
+            let value = T::arbitrary(&mut u).unwrap();
+            let serialized = to_vec(&value).unwrap();
+            assert_eq!(from_slice::<T>(&serialized).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn arbitrary_values_roundtrip() {
+        assert_roundtrips::<Coin>();
+        assert_roundtrips::<Binary>();
+        assert_roundtrips::<Addr>();
+        assert_roundtrips::<Timestamp>();
+        assert_roundtrips::<CosmosMsg<Empty>>();
+        assert_roundtrips::<Response<Empty>>();
+    }
+
+    #[test]
+    fn arbitrary_attributes_are_valid() {
+        let data = [7u8; 256];
+        let mut u = Unstructured::new(&data);
+        let attr = Attribute::arbitrary(&mut u).unwrap();
+        assert!(!attr.key.starts_with('_'));
+        // Attribute::new panics for invalid keys
+        Attribute::new(attr.key, attr.value);
+    }
+}
@@ -0,0 +1,68 @@
+/// Embeds information about the contract build into the custom Wasm section
+/// `cosmwasm_build_info`, which can be read by hosts via `Cache::analyze` in cosmwasm-vm.
+///
+/// The section contains a JSON object with the crate name and version as well
+/// as an optional free-form description of the build environment (e.g. the optimizer
+/// image used), which allows verifying which source a deployed code ID was built from.
+///
+/// Call this once at the top level of your contract crate:
+///
+/// ```
+/// cosmwasm_std::embed_build_info!("cosmwasm/rust-optimizer:0.12.9");
+/// ```
+///
+/// Only Wasm builds are affected. The section is empty in native builds.
+#[macro_export]
+macro_rules! embed_build_info {
+    () => {
+        $crate::embed_build_info!("");
+    };
+    ($builder:expr) => {
+        #[cfg(target_arch = "wasm32")]
+        const _: () = {
+            const BUILD_INFO: &str = concat!(
+                "{\"name\":\"",
+                env!("CARGO_PKG_NAME"),
+                "\",\"version\":\"",
+                env!("CARGO_PKG_VERSION"),
+                "\",\"builder\":\"",
+                $builder,
+                "\"}"
+            );
+            #[used]
+            #[link_section = "cosmwasm_build_info"]
+            static BUILD_INFO_SECTION: [u8; BUILD_INFO.len()] =
+                $crate::build_info_bytes(BUILD_INFO);
+        };
+    };
+}
+
+/// Copies a string into a fixed size array at compile time. Used by [`embed_build_info`].
+#[doc(hidden)]
+pub const fn build_info_bytes<const N: usize>(info: &str) -> [u8; N] {
+    let bytes = info.as_bytes();
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = bytes[i];
+        i += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::embed_build_info!();
+
+    #[test]
+    fn build_info_bytes_works() {
+        const INFO: &str = "{\"name\":\"foo\"}";
+        const BYTES: [u8; INFO.len()] = build_info_bytes(INFO);
+        assert_eq!(&BYTES, INFO.as_bytes());
+
+        let empty: [u8; 0] = build_info_bytes("");
+        assert_eq!(empty.len(), 0);
+    }
+}
@@ -69,7 +69,9 @@ impl From<CryptoError> for RecoverPubkeyError {
             }
             CryptoError::GenericErr { .. } => RecoverPubkeyError::unknown_err(original.code()),
             CryptoError::InvalidRecoveryParam { .. } => RecoverPubkeyError::InvalidRecoveryParam,
-            CryptoError::BatchErr { .. } => panic!("Conversion not supported"),
+            CryptoError::BatchErr { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::AggregationErr { .. } => panic!("Conversion not supported"),
         }
     }
 }
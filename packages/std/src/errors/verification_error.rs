@@ -80,6 +80,9 @@ impl From<CryptoError> for VerificationError {
             CryptoError::GenericErr { .. } => VerificationError::GenericErr,
             CryptoError::InvalidRecoveryParam { .. } => VerificationError::InvalidRecoveryParam,
             CryptoError::BatchErr { .. } => VerificationError::BatchErr,
+            CryptoError::InvalidPoint { .. } | CryptoError::AggregationErr { .. } => {
+                VerificationError::unknown_err(original.code())
+            }
         }
     }
 }
@@ -49,6 +49,14 @@ extern "C" fn requires_cosmwasm_1_1() -> () {}
 #[no_mangle]
 extern "C" fn requires_cosmwasm_1_2() -> () {}
 
+#[cfg(feature = "raw_range")]
+#[no_mangle]
+extern "C" fn requires_raw_range() -> () {}
+
+#[cfg(all(feature = "iterator", feature = "db_next_batch"))]
+#[no_mangle]
+extern "C" fn requires_db_next_batch() -> () {}
+
 /// interface_version_* exports mark which Wasm VM interface level this contract is compiled for.
 /// They can be checked by cosmwasm_vm.
 /// Update this whenever the Wasm VM interface breaks.
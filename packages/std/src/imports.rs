@@ -5,7 +5,9 @@ use crate::errors::{RecoverPubkeyError, StdError, StdResult, SystemError, Verifi
 use crate::import_helpers::{from_high_half, from_low_half};
 use crate::memory::{alloc, build_region, consume_region, Region};
 use crate::results::SystemResult;
-#[cfg(feature = "iterator")]
+#[cfg(all(feature = "iterator", feature = "db_next_batch"))]
+use crate::sections::decode_sections;
+#[cfg(all(feature = "iterator", not(feature = "db_next_batch")))]
 use crate::sections::decode_sections2;
 use crate::sections::encode_sections;
 use crate::serde::from_slice;
@@ -20,6 +22,9 @@ use crate::{
 const CANONICAL_ADDRESS_BUFFER_LENGTH: usize = 64;
 /// An upper bound for typical human readable address formats (e.g. 42 for Ethereum hex addresses or 90 for bech32)
 const HUMAN_ADDRESS_BUFFER_LENGTH: usize = 90;
+/// Number of records requested per db_next_batch call
+#[cfg(all(feature = "iterator", feature = "db_next_batch"))]
+const ITERATOR_BATCH_SIZE: u32 = 30;
 
 // This interface will compile into required Wasm imports.
 // A complete documentation those functions is available in the VM that provides them:
@@ -37,6 +42,9 @@ extern "C" {
     fn db_scan(start_ptr: u32, end_ptr: u32, order: i32) -> u32;
     #[cfg(feature = "iterator")]
     fn db_next(iterator_id: u32) -> u32;
+    // batched version of next(), returning up to max_items records at once
+    #[cfg(all(feature = "iterator", feature = "db_next_batch"))]
+    fn db_next_batch(iterator_id: u32, max_items: u32) -> u32;
 
     fn addr_validate(source_ptr: u32) -> u32;
     fn addr_canonicalize(source_ptr: u32, destination_ptr: u32) -> u32;
@@ -136,7 +144,11 @@ impl Storage for ExternalStorage {
         let start_region_addr = get_optional_region_address(&start_region.as_ref());
         let end_region_addr = get_optional_region_address(&end_region.as_ref());
         let iterator_id = unsafe { db_scan(start_region_addr, end_region_addr, order as i32) };
-        let iter = ExternalIterator { iterator_id };
+        let iter = ExternalIterator {
+            iterator_id,
+            #[cfg(feature = "db_next_batch")]
+            buffer: Vec::new(),
+        };
         Box::new(iter)
     }
 }
@@ -146,9 +158,12 @@ impl Storage for ExternalStorage {
 /// We use the pointer to differentiate between multiple open iterators.
 struct ExternalIterator {
     iterator_id: u32,
+    /// Records fetched via db_next_batch which were not yet returned, in reverse order
+    #[cfg(feature = "db_next_batch")]
+    buffer: Vec<Record>,
 }
 
-#[cfg(feature = "iterator")]
+#[cfg(all(feature = "iterator", not(feature = "db_next_batch")))]
 impl Iterator for ExternalIterator {
     type Item = Record;
 
@@ -165,6 +180,26 @@ impl Iterator for ExternalIterator {
     }
 }
 
+/// With the `db_next_batch` capability records are fetched in batches, which saves a host call per record.
+#[cfg(all(feature = "iterator", feature = "db_next_batch"))]
+impl Iterator for ExternalIterator {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            let batch_ptr = unsafe { db_next_batch(self.iterator_id, ITERATOR_BATCH_SIZE) };
+            let batch = unsafe { consume_region(batch_ptr as *mut Region) };
+            // An empty batch means no more elements
+            self.buffer = decode_sections(&batch)
+                .chunks_exact(2)
+                .rev()
+                .map(|kv| (kv[0].to_vec(), kv[1].to_vec()))
+                .collect();
+        }
+        self.buffer.pop()
+    }
+}
+
 /// A stateless convenience wrapper around imports provided by the VM
 #[derive(Copy, Clone)]
 pub struct ExternalApi {}
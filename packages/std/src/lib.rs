@@ -3,8 +3,11 @@
 // Exposed on all platforms
 
 mod addresses;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
 mod assertions;
 mod binary;
+mod build_info;
 mod coin;
 mod conversion;
 mod deps;
@@ -12,12 +15,13 @@ mod errors;
 mod hex_binary;
 mod ibc;
 mod import_helpers;
-#[cfg(feature = "iterator")]
+#[cfg(any(feature = "iterator", feature = "raw_range"))]
 mod iterator;
 mod math;
 mod panic;
 mod query;
 mod results;
+mod schemas;
 mod sections;
 mod serde;
 mod storage;
@@ -27,6 +31,8 @@ mod types;
 
 pub use crate::addresses::{instantiate2_address, Addr, CanonicalAddr};
 pub use crate::binary::Binary;
+#[doc(hidden)]
+pub use crate::build_info::build_info_bytes;
 pub use crate::coin::{coin, coins, has_coins, Coin};
 pub use crate::deps::{Deps, DepsMut, OwnedDeps};
 pub use crate::errors::{
@@ -42,12 +48,14 @@ pub use crate::ibc::{
     IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse,
     IbcTimeout, IbcTimeoutBlock,
 };
-#[cfg(feature = "iterator")]
+#[cfg(any(feature = "iterator", feature = "raw_range"))]
 pub use crate::iterator::{Order, Record};
 pub use crate::math::{
     Decimal, Decimal256, Decimal256RangeExceeded, DecimalRangeExceeded, Fraction, Isqrt, Uint128,
     Uint256, Uint512, Uint64,
 };
+#[cfg(feature = "raw_range")]
+pub use crate::query::RawRangeResponse;
 #[cfg(feature = "cosmwasm_1_1")]
 pub use crate::query::SupplyResponse;
 pub use crate::query::{
@@ -74,6 +82,7 @@ pub use crate::results::{
 pub use crate::results::{DistributionMsg, StakingMsg};
 #[cfg(feature = "stargate")]
 pub use crate::results::{GovMsg, VoteOption};
+pub use crate::schemas::core_schemas;
 pub use crate::serde::{from_binary, from_slice, to_binary, to_vec};
 pub use crate::storage::MemoryStorage;
 pub use crate::timestamp::Timestamp;
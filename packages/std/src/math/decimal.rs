@@ -216,6 +216,58 @@ impl Decimal {
         }
     }
 
+    /// Converts this decimal to an unsigned integer by truncating
+    /// the fractional part, e.g. 22.5 becomes 22.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use cosmwasm_std::{Decimal, Uint128};
+    ///
+    /// let d = Decimal::from_str("12.345").unwrap();
+    /// assert_eq!(d.to_uint_floor(), Uint128::from(12u64));
+    ///
+    /// let d = Decimal::from_str("12.999").unwrap();
+    /// assert_eq!(d.to_uint_floor(), Uint128::from(12u64));
+    ///
+    /// let d = Decimal::from_str("75.0").unwrap();
+    /// assert_eq!(d.to_uint_floor(), Uint128::from(75u64));
+    /// ```
+    pub fn to_uint_floor(self) -> Uint128 {
+        self.0 / Self::DECIMAL_FRACTIONAL
+    }
+
+    /// Converts this decimal to an unsigned integer by rounding up
+    /// to the next integer, e.g. 22.3 becomes 23.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use cosmwasm_std::{Decimal, Uint128};
+    ///
+    /// let d = Decimal::from_str("12.345").unwrap();
+    /// assert_eq!(d.to_uint_ceil(), Uint128::from(13u64));
+    ///
+    /// let d = Decimal::from_str("12.999").unwrap();
+    /// assert_eq!(d.to_uint_ceil(), Uint128::from(13u64));
+    ///
+    /// let d = Decimal::from_str("75.0").unwrap();
+    /// assert_eq!(d.to_uint_ceil(), Uint128::from(75u64));
+    /// ```
+    pub fn to_uint_ceil(self) -> Uint128 {
+        // Using `q = 1 + ((x - 1) / y); if x != 0` with unsigned integers x, y, q
+        // from https://stackoverflow.com/a/2745086/2013738. We know `x + y` CAN overflow.
+        let x = self.0;
+        let y = Self::DECIMAL_FRACTIONAL;
+        if x.is_zero() {
+            Uint128::zero()
+        } else {
+            Uint128::one() + ((x - Uint128::one()) / y)
+        }
+    }
+
     pub fn checked_add(self, other: Self) -> Result<Self, OverflowError> {
         self.0
             .checked_add(other.0)
@@ -1996,6 +2048,30 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn decimal_to_uint_floor_works() {
+        assert_eq!(dec("0").to_uint_floor(), Uint128::zero());
+        assert_eq!(dec("0.999").to_uint_floor(), Uint128::zero());
+        assert_eq!(dec("1").to_uint_floor(), Uint128::one());
+        assert_eq!(dec("22.5").to_uint_floor(), Uint128::from(22u32));
+        assert_eq!(
+            Decimal::MAX.to_uint_floor(),
+            Decimal::MAX.atomics() / Decimal::one().atomics()
+        );
+    }
+
+    #[test]
+    fn decimal_to_uint_ceil_works() {
+        assert_eq!(dec("0").to_uint_ceil(), Uint128::zero());
+        assert_eq!(dec("0.001").to_uint_ceil(), Uint128::one());
+        assert_eq!(dec("1").to_uint_ceil(), Uint128::one());
+        assert_eq!(dec("22.3").to_uint_ceil(), Uint128::from(23u32));
+        assert_eq!(
+            Decimal::MAX.to_uint_ceil(),
+            Decimal::MAX.atomics() / Decimal::one().atomics() + Uint128::one()
+        );
+    }
+
     #[test]
     fn decimal_partial_eq() {
         let test_cases = [
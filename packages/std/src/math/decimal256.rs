@@ -229,6 +229,58 @@ impl Decimal256 {
         }
     }
 
+    /// Converts this decimal to an unsigned integer by truncating
+    /// the fractional part, e.g. 22.5 becomes 22.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use cosmwasm_std::{Decimal256, Uint256};
+    ///
+    /// let d = Decimal256::from_str("12.345").unwrap();
+    /// assert_eq!(d.to_uint_floor(), Uint256::from(12u64));
+    ///
+    /// let d = Decimal256::from_str("12.999").unwrap();
+    /// assert_eq!(d.to_uint_floor(), Uint256::from(12u64));
+    ///
+    /// let d = Decimal256::from_str("75.0").unwrap();
+    /// assert_eq!(d.to_uint_floor(), Uint256::from(75u64));
+    /// ```
+    pub fn to_uint_floor(self) -> Uint256 {
+        self.0 / Self::DECIMAL_FRACTIONAL
+    }
+
+    /// Converts this decimal to an unsigned integer by rounding up
+    /// to the next integer, e.g. 22.3 becomes 23.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use cosmwasm_std::{Decimal256, Uint256};
+    ///
+    /// let d = Decimal256::from_str("12.345").unwrap();
+    /// assert_eq!(d.to_uint_ceil(), Uint256::from(13u64));
+    ///
+    /// let d = Decimal256::from_str("12.999").unwrap();
+    /// assert_eq!(d.to_uint_ceil(), Uint256::from(13u64));
+    ///
+    /// let d = Decimal256::from_str("75.0").unwrap();
+    /// assert_eq!(d.to_uint_ceil(), Uint256::from(75u64));
+    /// ```
+    pub fn to_uint_ceil(self) -> Uint256 {
+        // Using `q = 1 + ((x - 1) / y); if x != 0` with unsigned integers x, y, q
+        // from https://stackoverflow.com/a/2745086/2013738. We know `x + y` CAN overflow.
+        let x = self.0;
+        let y = Self::DECIMAL_FRACTIONAL;
+        if x.is_zero() {
+            Uint256::zero()
+        } else {
+            Uint256::one() + ((x - Uint256::one()) / y)
+        }
+    }
+
     pub fn checked_add(self, other: Self) -> Result<Self, OverflowError> {
         self.0
             .checked_add(other.0)
@@ -2143,6 +2195,30 @@ mod tests {
         assert_eq!(Decimal256::MAX.checked_ceil(), Err(RoundUpOverflowError));
     }
 
+    #[test]
+    fn decimal256_to_uint_floor_works() {
+        assert_eq!(dec("0").to_uint_floor(), Uint256::zero());
+        assert_eq!(dec("0.999").to_uint_floor(), Uint256::zero());
+        assert_eq!(dec("1").to_uint_floor(), Uint256::one());
+        assert_eq!(dec("22.5").to_uint_floor(), Uint256::from(22u32));
+        assert_eq!(
+            Decimal256::MAX.to_uint_floor(),
+            Decimal256::MAX.atomics() / Decimal256::one().atomics()
+        );
+    }
+
+    #[test]
+    fn decimal256_to_uint_ceil_works() {
+        assert_eq!(dec("0").to_uint_ceil(), Uint256::zero());
+        assert_eq!(dec("0.001").to_uint_ceil(), Uint256::one());
+        assert_eq!(dec("1").to_uint_ceil(), Uint256::one());
+        assert_eq!(dec("22.3").to_uint_ceil(), Uint256::from(23u32));
+        assert_eq!(
+            Decimal256::MAX.to_uint_ceil(),
+            Decimal256::MAX.atomics() / Decimal256::one().atomics() + Uint256::one()
+        );
+    }
+
     #[test]
     fn decimal256_partial_eq() {
         let test_cases = [
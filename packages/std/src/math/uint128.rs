@@ -10,7 +10,7 @@ use std::str::FromStr;
 use crate::errors::{
     CheckedMultiplyRatioError, DivideByZeroError, OverflowError, OverflowOperation, StdError,
 };
-use crate::{ConversionOverflowError, Uint256, Uint64};
+use crate::{ConversionOverflowError, Fraction, Uint256, Uint64};
 
 /// A thin wrapper around u128 that is using strings for JSON encoding/decoding,
 /// such that the full u128 range can be used for clients that convert JSON numbers to floats,
@@ -136,6 +136,75 @@ impl Uint128 {
             .unwrap()
     }
 
+    /// Multiplies `self` with a fraction like [`Decimal`](crate::Decimal) and rounds the result
+    /// down, e.g. 7 * 0.5 = 3.
+    ///
+    /// Panics if the denominator is zero or the result exceeds the value range of [`Uint128`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cosmwasm_std::{Decimal, Uint128};
+    ///
+    /// let fee = Uint128::from(7u32).mul_floor(Decimal::percent(50));
+    /// assert_eq!(fee, Uint128::from(3u32));
+    /// ```
+    pub fn mul_floor<F: Fraction<N>, N: Into<u128>>(self, rhs: F) -> Self {
+        self.multiply_ratio(rhs.numerator(), rhs.denominator())
+    }
+
+    /// Like [`Uint128::mul_floor`] but returns an error instead of panicking.
+    pub fn checked_mul_floor<F: Fraction<N>, N: Into<u128>>(
+        self,
+        rhs: F,
+    ) -> Result<Self, CheckedMultiplyRatioError> {
+        self.checked_multiply_ratio(rhs.numerator(), rhs.denominator())
+    }
+
+    /// Multiplies `self` with a fraction like [`Decimal`](crate::Decimal) and rounds the result
+    /// up, e.g. 7 * 0.5 = 4.
+    ///
+    /// Panics if the denominator is zero or the result exceeds the value range of [`Uint128`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cosmwasm_std::{Decimal, Uint128};
+    ///
+    /// let fee = Uint128::from(7u32).mul_ceil(Decimal::percent(50));
+    /// assert_eq!(fee, Uint128::from(4u32));
+    /// ```
+    pub fn mul_ceil<F: Fraction<N>, N: Into<u128>>(self, rhs: F) -> Self {
+        match self.checked_mul_ceil(rhs) {
+            Ok(value) => value,
+            Err(CheckedMultiplyRatioError::DivideByZero) => {
+                panic!("Denominator must not be zero")
+            }
+            Err(CheckedMultiplyRatioError::Overflow) => panic!("Multiplication overflow"),
+        }
+    }
+
+    /// Like [`Uint128::mul_ceil`] but returns an error instead of panicking.
+    pub fn checked_mul_ceil<F: Fraction<N>, N: Into<u128>>(
+        self,
+        rhs: F,
+    ) -> Result<Self, CheckedMultiplyRatioError> {
+        let numerator: u128 = rhs.numerator().into();
+        let denominator: u128 = rhs.denominator().into();
+        if denominator == 0 {
+            return Err(CheckedMultiplyRatioError::DivideByZero);
+        }
+        let product = self.full_mul(numerator);
+        let denominator = Uint256::from(denominator);
+        let mut result = product / denominator;
+        if !(product % denominator).is_zero() {
+            result += Uint256::one();
+        }
+        result
+            .try_into()
+            .map_err(|_| CheckedMultiplyRatioError::Overflow)
+    }
+
     pub fn checked_add(self, other: Self) -> Result<Self, OverflowError> {
         self.0
             .checked_add(other.0)
@@ -538,7 +607,7 @@ impl PartialEq<Uint128> for &Uint128 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{from_slice, to_vec};
+    use crate::{from_slice, to_vec, Decimal};
 
     #[test]
     fn uint128_zero_works() {
@@ -859,6 +928,38 @@ mod tests {
         Uint128(500).multiply_ratio(1u128, 0u128);
     }
 
+    #[test]
+    fn uint128_mul_floor_works() {
+        let fraction = Decimal::percent(50);
+        assert_eq!(Uint128::from(7u32).mul_floor(fraction), Uint128::from(3u32));
+        assert_eq!(Uint128::from(8u32).mul_floor(fraction), Uint128::from(4u32));
+        assert_eq!(Uint128::zero().mul_floor(fraction), Uint128::zero());
+        assert_eq!(Uint128::MAX.mul_floor(Decimal::one()), Uint128::MAX);
+        assert_eq!(
+            Uint128::MAX.checked_mul_floor(Decimal::percent(200)),
+            Err(CheckedMultiplyRatioError::Overflow),
+        );
+    }
+
+    #[test]
+    fn uint128_mul_ceil_works() {
+        let fraction = Decimal::percent(50);
+        assert_eq!(Uint128::from(7u32).mul_ceil(fraction), Uint128::from(4u32));
+        assert_eq!(Uint128::from(8u32).mul_ceil(fraction), Uint128::from(4u32));
+        assert_eq!(Uint128::zero().mul_ceil(fraction), Uint128::zero());
+        assert_eq!(Uint128::MAX.mul_ceil(Decimal::one()), Uint128::MAX);
+        assert_eq!(
+            Uint128::MAX.checked_mul_ceil(Decimal::percent(101)),
+            Err(CheckedMultiplyRatioError::Overflow),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Multiplication overflow")]
+    fn uint128_mul_ceil_panics_on_overflow() {
+        Uint128::MAX.mul_ceil(Decimal::percent(101));
+    }
+
     #[test]
     fn uint128_checked_multiply_ratio_does_not_panic() {
         assert_eq!(
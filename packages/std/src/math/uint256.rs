@@ -12,7 +12,7 @@ use crate::errors::{
     CheckedMultiplyRatioError, ConversionOverflowError, DivideByZeroError, OverflowError,
     OverflowOperation, StdError,
 };
-use crate::{Uint128, Uint512, Uint64};
+use crate::{Fraction, Uint128, Uint512, Uint64};
 
 /// This module is purely a workaround that lets us ignore lints for all the code
 /// the `construct_uint!` macro generates.
@@ -222,6 +222,75 @@ impl Uint256 {
             .unwrap()
     }
 
+    /// Multiplies `self` with a fraction like [`Decimal256`](crate::Decimal256) and rounds the result
+    /// down, e.g. 7 * 0.5 = 3.
+    ///
+    /// Panics if the denominator is zero or the result exceeds the value range of [`Uint256`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cosmwasm_std::{Decimal256, Uint256};
+    ///
+    /// let fee = Uint256::from(7u32).mul_floor(Decimal256::percent(50));
+    /// assert_eq!(fee, Uint256::from(3u32));
+    /// ```
+    pub fn mul_floor<F: Fraction<N>, N: Into<Uint256>>(self, rhs: F) -> Self {
+        self.multiply_ratio(rhs.numerator(), rhs.denominator())
+    }
+
+    /// Like [`Uint256::mul_floor`] but returns an error instead of panicking.
+    pub fn checked_mul_floor<F: Fraction<N>, N: Into<Uint256>>(
+        self,
+        rhs: F,
+    ) -> Result<Self, CheckedMultiplyRatioError> {
+        self.checked_multiply_ratio(rhs.numerator(), rhs.denominator())
+    }
+
+    /// Multiplies `self` with a fraction like [`Decimal256`](crate::Decimal256) and rounds the result
+    /// up, e.g. 7 * 0.5 = 4.
+    ///
+    /// Panics if the denominator is zero or the result exceeds the value range of [`Uint256`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cosmwasm_std::{Decimal256, Uint256};
+    ///
+    /// let fee = Uint256::from(7u32).mul_ceil(Decimal256::percent(50));
+    /// assert_eq!(fee, Uint256::from(4u32));
+    /// ```
+    pub fn mul_ceil<F: Fraction<N>, N: Into<Uint256>>(self, rhs: F) -> Self {
+        match self.checked_mul_ceil(rhs) {
+            Ok(value) => value,
+            Err(CheckedMultiplyRatioError::DivideByZero) => {
+                panic!("Denominator must not be zero")
+            }
+            Err(CheckedMultiplyRatioError::Overflow) => panic!("Multiplication overflow"),
+        }
+    }
+
+    /// Like [`Uint256::mul_ceil`] but returns an error instead of panicking.
+    pub fn checked_mul_ceil<F: Fraction<N>, N: Into<Uint256>>(
+        self,
+        rhs: F,
+    ) -> Result<Self, CheckedMultiplyRatioError> {
+        let numerator: Uint256 = rhs.numerator().into();
+        let denominator: Uint256 = rhs.denominator().into();
+        if denominator.is_zero() {
+            return Err(CheckedMultiplyRatioError::DivideByZero);
+        }
+        let product = self.full_mul(numerator);
+        let denominator = Uint512::from(denominator);
+        let mut result = product / denominator;
+        if !(product % denominator).is_zero() {
+            result += Uint512::one();
+        }
+        result
+            .try_into()
+            .map_err(|_| CheckedMultiplyRatioError::Overflow)
+    }
+
     pub fn checked_add(self, other: Self) -> Result<Self, OverflowError> {
         self.0
             .checked_add(other.0)
@@ -666,7 +735,7 @@ impl PartialEq<Uint256> for &Uint256 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{from_slice, to_vec};
+    use crate::{from_slice, to_vec, Decimal256};
 
     #[test]
     fn uint256_new_works() {
@@ -1446,6 +1515,38 @@ mod tests {
         Uint256::from(500u32).multiply_ratio(1u128, 0u128);
     }
 
+    #[test]
+    fn uint256_mul_floor_works() {
+        let fraction = Decimal256::percent(50);
+        assert_eq!(Uint256::from(7u32).mul_floor(fraction), Uint256::from(3u32));
+        assert_eq!(Uint256::from(8u32).mul_floor(fraction), Uint256::from(4u32));
+        assert_eq!(Uint256::zero().mul_floor(fraction), Uint256::zero());
+        assert_eq!(Uint256::MAX.mul_floor(Decimal256::one()), Uint256::MAX);
+        assert_eq!(
+            Uint256::MAX.checked_mul_floor(Decimal256::percent(200)),
+            Err(CheckedMultiplyRatioError::Overflow),
+        );
+    }
+
+    #[test]
+    fn uint256_mul_ceil_works() {
+        let fraction = Decimal256::percent(50);
+        assert_eq!(Uint256::from(7u32).mul_ceil(fraction), Uint256::from(4u32));
+        assert_eq!(Uint256::from(8u32).mul_ceil(fraction), Uint256::from(4u32));
+        assert_eq!(Uint256::zero().mul_ceil(fraction), Uint256::zero());
+        assert_eq!(Uint256::MAX.mul_ceil(Decimal256::one()), Uint256::MAX);
+        assert_eq!(
+            Uint256::MAX.checked_mul_ceil(Decimal256::percent(101)),
+            Err(CheckedMultiplyRatioError::Overflow),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Multiplication overflow")]
+    fn uint256_mul_ceil_panics_on_overflow() {
+        Uint256::MAX.mul_ceil(Decimal256::percent(101));
+    }
+
     #[test]
     fn uint256_checked_multiply_ratio_does_not_panic() {
         assert_eq!(
@@ -2,6 +2,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::Binary;
+#[cfg(feature = "raw_range")]
+use crate::Order;
 
 #[non_exhaustive]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -24,6 +26,20 @@ pub enum WasmQuery {
     },
     /// returns a ContractInfoResponse with metadata on the contract from the runtime
     ContractInfo { contract_addr: String },
+    /// Queries a range of keys from the raw kv-store of the contract.
+    /// Returns a [`RawRangeResponse`].
+    #[cfg(feature = "raw_range")]
+    RawRange {
+        contract_addr: String,
+        /// Inclusive start bound. This is the first key you would like to get data for.
+        start: Option<Binary>,
+        /// Exclusive end bound. This is the key after the last key you would like to get data for.
+        end: Option<Binary>,
+        /// Maximum number of elements to return. The chain may enforce a lower limit.
+        limit: u16,
+        /// The order in which you want to receive the key-value pairs.
+        order: Order,
+    },
 }
 
 #[non_exhaustive]
@@ -53,3 +69,24 @@ impl ContractInfoResponse {
         }
     }
 }
+
+#[cfg(feature = "raw_range")]
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RawRangeResponse {
+    /// The key-value pairs
+    pub data: Vec<(Binary, Binary)>,
+    /// `None` if there are no more key-value pairs within the given key range.
+    /// Otherwise, this is the first key of the next page. Pass it as `start` to continue an
+    /// ascending query. For a descending query, pass the key with a 0 byte appended as `end`.
+    pub next_key: Option<Binary>,
+}
+
+#[cfg(feature = "raw_range")]
+impl RawRangeResponse {
+    /// Convenience constructor for tests / mocks
+    #[doc(hidden)]
+    pub fn new(data: Vec<(Binary, Binary)>, next_key: Option<Binary>) -> Self {
+        Self { data, next_key }
+    }
+}
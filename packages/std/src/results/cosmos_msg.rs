@@ -206,8 +206,8 @@ pub enum VoteOption {
 #[cfg(all(feature = "stargate", feature = "cosmwasm_1_2"))]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct WeightedVoteOption {
-    option: VoteOption,
-    weight: Decimal,
+    pub option: VoteOption,
+    pub weight: Decimal,
 }
 
 /// Shortcut helper as the construction of WasmMsg::Instantiate can be quite verbose in contract code.
@@ -0,0 +1,47 @@
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+
+use crate::{CosmosMsg, Empty, Env, MessageInfo, QueryRequest, Response};
+
+/// Returns the JSON schemas of the envelope types which are passed between chain and contracts,
+/// together with their type names.
+///
+/// Contract specific messages are exported via cosmwasm-schema. This covers the parts
+/// that are the same for all contracts, such that non-Rust tooling (indexers, relayers,
+/// client SDKs) can generate bindings for them. Custom messages and queries are set to
+/// [`Empty`].
+pub fn core_schemas() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        ("Env", schema_for!(Env)),
+        ("MessageInfo", schema_for!(MessageInfo)),
+        ("Response", schema_for!(Response<Empty>)),
+        ("CosmosMsg", schema_for!(CosmosMsg<Empty>)),
+        ("QueryRequest", schema_for!(QueryRequest<Empty>)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn core_schemas_works() {
+        let schemas = core_schemas();
+        let names: Vec<_> = schemas.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            [
+                "Env",
+                "MessageInfo",
+                "Response",
+                "CosmosMsg",
+                "QueryRequest"
+            ]
+        );
+
+        let (_, env) = &schemas[0];
+        let title = env.schema.metadata.as_ref().unwrap().title.as_deref();
+        assert_eq!(title, Some("Env"));
+        assert!(env.definitions.contains_key("BlockInfo"));
+    }
+}
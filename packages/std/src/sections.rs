@@ -8,6 +8,27 @@ pub fn decode_sections2(data: Vec<u8>) -> (Vec<u8>, Vec<u8>) {
     (first, second)
 }
 
+/// A sections decoder for an arbitrary number of elements.
+///
+/// Trailing data which is too short to contain a section length is ignored.
+#[allow(dead_code)] // used in Wasm and tests only
+pub fn decode_sections(data: &[u8]) -> Vec<&[u8]> {
+    let mut result: Vec<&[u8]> = vec![];
+    let mut remaining_len = data.len();
+    while remaining_len >= 4 {
+        let tail_len = u32::from_be_bytes([
+            data[remaining_len - 4],
+            data[remaining_len - 3],
+            data[remaining_len - 2],
+            data[remaining_len - 1],
+        ]) as usize;
+        result.push(&data[remaining_len - 4 - tail_len..remaining_len - 4]);
+        remaining_len -= 4 + tail_len;
+    }
+    result.reverse();
+    result
+}
+
 /// Encodes multiple sections of data into one vector.
 ///
 /// Each section is suffixed by a section length encoded as big endian uint32.
@@ -106,6 +127,18 @@ mod tests {
         assert_ne!(second.as_ptr(), original_ptr);
     }
 
+    #[test]
+    fn decode_sections_works() {
+        let dec = decode_sections(b"");
+        assert_eq!(dec.len(), 0);
+        let dec = decode_sections(b"\0\0\0\0\0\0\0\0");
+        assert_eq!(dec, &[&[0u8; 0]; 2]);
+        let dec = decode_sections(b"\xAA\0\0\0\x01");
+        assert_eq!(dec, &[vec![0xAA]]);
+        let dec = decode_sections(b"\xAA\0\0\0\x01\xDE\xDE\0\0\0\x02\0\0\0\0");
+        assert_eq!(dec, &[vec![0xAA], vec![0xDE, 0xDE], vec![]]);
+    }
+
     #[test]
     fn encode_sections_works_for_empty_sections() {
         let enc = encode_sections(&[]);
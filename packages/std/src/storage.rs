@@ -9,7 +9,7 @@ use std::ops::{Bound, RangeBounds};
 use crate::iterator::{Order, Record};
 use crate::traits::Storage;
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct MemoryStorage {
     data: BTreeMap<Vec<u8>, Vec<u8>>,
 }
@@ -0,0 +1,1513 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::addresses::Addr;
+use crate::binary::Binary;
+use crate::coin::Coin;
+use crate::deps::{Deps, DepsMut};
+use crate::errors::{StdError, StdResult, SystemError};
+#[cfg(all(feature = "raw_range", feature = "iterator"))]
+use crate::iterator::Order;
+#[cfg(all(feature = "raw_range", feature = "iterator"))]
+use crate::query::RawRangeResponse;
+use crate::query::{BankQuery, ContractInfoResponse, CustomQuery, QueryRequest, WasmQuery};
+use crate::results::{
+    BankMsg, ContractResult, CosmosMsg, Empty, Event, Reply, ReplyOn, Response, SubMsg,
+    SubMsgResponse, SubMsgResult, SystemResult, WasmMsg,
+};
+use crate::serde::{from_slice, to_binary, to_vec};
+use crate::traits::{Querier, QuerierResult, QuerierWrapper, Storage};
+use crate::types::{BlockInfo, Env, MessageInfo};
+
+use super::mock::{mock_env, BankQuerier, MockApi};
+use super::storage::MockStorage;
+
+/// The block time assumed by [`App::advance_height`]
+const BLOCK_TIME_SECONDS: u64 = 5;
+
+/// A contract that can be registered in an [`App`]. Messages are passed as JSON.
+///
+/// Use [`ContractWrapper`] to create one from the entry points of a contract.
+pub trait Contract<C = Empty, Q: CustomQuery = Empty> {
+    fn instantiate(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> Result<Response<C>, String>;
+
+    fn execute(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> Result<Response<C>, String>;
+
+    fn query(&self, deps: Deps<Q>, env: Env, msg: &[u8]) -> Result<Binary, String>;
+
+    fn reply(&self, deps: DepsMut<Q>, env: Env, reply: Reply) -> Result<Response<C>, String>;
+
+    fn migrate(&self, deps: DepsMut<Q>, env: Env, msg: &[u8]) -> Result<Response<C>, String>;
+}
+
+type ExecuteFn<C, Q> =
+    Box<dyn Fn(DepsMut<Q>, Env, MessageInfo, &[u8]) -> Result<Response<C>, String>>;
+type QueryFn<Q> = Box<dyn Fn(Deps<Q>, Env, &[u8]) -> Result<Binary, String>>;
+type ReplyFn<C, Q> = Box<dyn Fn(DepsMut<Q>, Env, Reply) -> Result<Response<C>, String>>;
+type MigrateFn<C, Q> = Box<dyn Fn(DepsMut<Q>, Env, &[u8]) -> Result<Response<C>, String>>;
+type ExecuteEntryPoint<T, C, Q, E> = fn(DepsMut<Q>, Env, MessageInfo, T) -> Result<Response<C>, E>;
+type QueryEntryPoint<T, Q, E> = fn(Deps<Q>, Env, T) -> Result<Binary, E>;
+type ReplyEntryPoint<C, Q, E> = fn(DepsMut<Q>, Env, Reply) -> Result<Response<C>, E>;
+type MigrateEntryPoint<T, C, Q, E> = fn(DepsMut<Q>, Env, T) -> Result<Response<C>, E>;
+
+/// Implements [`Contract`] for the entry points of a contract.
+///
+/// ## Examples
+///
+/// ```ignore
+/// let code = ContractWrapper::new(contract::instantiate, contract::execute, contract::query);
+/// let code_id = app.store_code(Box::new(code));
+/// ```
+pub struct ContractWrapper<C = Empty, Q: CustomQuery = Empty> {
+    instantiate: ExecuteFn<C, Q>,
+    execute: ExecuteFn<C, Q>,
+    query: QueryFn<Q>,
+    reply: Option<ReplyFn<C, Q>>,
+    migrate: Option<MigrateFn<C, Q>>,
+}
+
+impl<C: 'static, Q: CustomQuery + 'static> ContractWrapper<C, Q> {
+    pub fn new<T1, T2, T3, E1, E2, E3>(
+        instantiate: ExecuteEntryPoint<T1, C, Q, E1>,
+        execute: ExecuteEntryPoint<T2, C, Q, E2>,
+        query: QueryEntryPoint<T3, Q, E3>,
+    ) -> Self
+    where
+        T1: DeserializeOwned + 'static,
+        T2: DeserializeOwned + 'static,
+        T3: DeserializeOwned + 'static,
+        E1: ToString + 'static,
+        E2: ToString + 'static,
+        E3: ToString + 'static,
+    {
+        ContractWrapper {
+            instantiate: wrap_execute(instantiate),
+            execute: wrap_execute(execute),
+            query: Box::new(move |deps: Deps<Q>, env: Env, msg: &[u8]| {
+                let msg: T3 = from_slice(msg).map_err(|e| e.to_string())?;
+                query(deps, env, msg).map_err(|e| e.to_string())
+            }),
+            reply: None,
+            migrate: None,
+        }
+    }
+
+    /// Sets the reply entry point, which is required for submessages with `ReplyOn` other than `Never`.
+    pub fn with_reply<E>(mut self, reply: ReplyEntryPoint<C, Q, E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.reply = Some(Box::new(move |deps: DepsMut<Q>, env: Env, msg: Reply| {
+            reply(deps, env, msg).map_err(|e| e.to_string())
+        }));
+        self
+    }
+
+    /// Sets the migrate entry point, which is required for `WasmMsg::Migrate`.
+    pub fn with_migrate<T, E>(mut self, migrate: MigrateEntryPoint<T, C, Q, E>) -> Self
+    where
+        T: DeserializeOwned + 'static,
+        E: ToString + 'static,
+    {
+        self.migrate = Some(Box::new(move |deps: DepsMut<Q>, env: Env, msg: &[u8]| {
+            let msg: T = from_slice(msg).map_err(|e| e.to_string())?;
+            migrate(deps, env, msg).map_err(|e| e.to_string())
+        }));
+        self
+    }
+}
+
+fn wrap_execute<C: 'static, Q: CustomQuery + 'static, T, E>(
+    entry_point: ExecuteEntryPoint<T, C, Q, E>,
+) -> ExecuteFn<C, Q>
+where
+    T: DeserializeOwned + 'static,
+    E: ToString + 'static,
+{
+    Box::new(
+        move |deps: DepsMut<Q>, env: Env, info: MessageInfo, msg: &[u8]| {
+            let msg: T = from_slice(msg).map_err(|e| e.to_string())?;
+            entry_point(deps, env, info, msg).map_err(|e| e.to_string())
+        },
+    )
+}
+
+impl<C, Q: CustomQuery> Contract<C, Q> for ContractWrapper<C, Q> {
+    fn instantiate(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> Result<Response<C>, String> {
+        (self.instantiate)(deps, env, info, msg)
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut<Q>,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> Result<Response<C>, String> {
+        (self.execute)(deps, env, info, msg)
+    }
+
+    fn query(&self, deps: Deps<Q>, env: Env, msg: &[u8]) -> Result<Binary, String> {
+        (self.query)(deps, env, msg)
+    }
+
+    fn reply(&self, deps: DepsMut<Q>, env: Env, reply: Reply) -> Result<Response<C>, String> {
+        match &self.reply {
+            Some(reply_fn) => reply_fn(deps, env, reply),
+            None => Err("Contract does not implement reply".to_string()),
+        }
+    }
+
+    fn migrate(&self, deps: DepsMut<Q>, env: Env, msg: &[u8]) -> Result<Response<C>, String> {
+        match &self.migrate {
+            Some(migrate_fn) => migrate_fn(deps, env, msg),
+            None => Err("Contract does not implement migrate".to_string()),
+        }
+    }
+}
+
+/// The result of executing a message in an [`App`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppResponse {
+    /// All events emitted, in the order wasmd emits them
+    pub events: Vec<Event>,
+    /// The data returned by the executed message
+    pub data: Option<Binary>,
+}
+
+#[derive(Clone)]
+struct ContractData {
+    code_id: u64,
+    creator: Addr,
+    admin: Option<Addr>,
+    storage: RefCell<MockStorage>,
+}
+
+#[derive(Clone, Default)]
+struct AppState {
+    balances: BTreeMap<String, Vec<Coin>>,
+    contracts: BTreeMap<Addr, ContractData>,
+}
+
+/// A test harness running multiple contracts in one simulated chain.
+///
+/// Contracts are registered via [`App::store_code`] and each instance gets its own storage.
+/// Messages returned by contracts are executed like wasmd does, such that interactions
+/// between contracts and the bank module can be tested end to end. If a message fails,
+/// all state changes of the transaction are reverted.
+///
+/// Supported are `BankMsg` and the `WasmMsg::Instantiate`/`WasmMsg::Execute`/`WasmMsg::Migrate`
+/// messages as well as bank and Wasm queries. Submessages call the `reply` entry point according
+/// to their `ReplyOn` setting, and a reply returning data overrides the data of the response.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{coins, to_binary, Addr, BankMsg, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+/// # use cosmwasm_std::testing::{App, ContractWrapper};
+/// fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+///     Ok(Response::new())
+/// }
+/// fn execute(_: DepsMut, _: Env, info: MessageInfo, _: Empty) -> StdResult<Response> {
+///     // forward all funds to the owner
+///     Ok(Response::new().add_message(BankMsg::Send {
+///         to_address: "owner".to_string(),
+///         amount: info.funds,
+///     }))
+/// }
+/// fn query(_: Deps, _: Env, _: Empty) -> StdResult<Binary> {
+///     to_binary("nothing")
+/// }
+///
+/// let mut app = App::new();
+/// app.init_balance("alice", coins(100, "ucosm"));
+/// let code_id = app.store_code(Box::new(ContractWrapper::new(instantiate, execute, query)));
+/// let contract = app
+///     .instantiate_contract(code_id, Addr::unchecked("alice"), &Empty {}, &[], "forwarder")
+///     .unwrap();
+/// app.execute_contract(Addr::unchecked("alice"), contract, &Empty {}, &coins(30, "ucosm"))
+///     .unwrap();
+///
+/// let balance = app.wrap().query_balance("owner", "ucosm").unwrap();
+/// assert_eq!(balance.amount.u128(), 30);
+/// ```
+pub struct App<C = Empty, Q: CustomQuery = Empty> {
+    api: MockApi,
+    block: BlockInfo,
+    codes: Vec<Box<dyn Contract<C, Q>>>,
+    state: AppState,
+}
+
+impl<C, Q> Default for App<C, Q>
+where
+    C: Clone + fmt::Debug + 'static,
+    Q: CustomQuery + DeserializeOwned + 'static,
+{
+    fn default() -> Self {
+        App {
+            api: MockApi::default(),
+            block: mock_env().block,
+            codes: vec![],
+            state: AppState::default(),
+        }
+    }
+}
+
+impl<C, Q> App<C, Q>
+where
+    C: Clone + fmt::Debug + 'static,
+    Q: CustomQuery + DeserializeOwned + 'static,
+{
+    pub fn new() -> Self {
+        App::default()
+    }
+
+    pub fn api(&self) -> &MockApi {
+        &self.api
+    }
+
+    pub fn block_info(&self) -> BlockInfo {
+        self.block.clone()
+    }
+
+    pub fn set_block(&mut self, block: BlockInfo) {
+        self.block = block;
+    }
+
+    /// Modifies the block info used for all subsequent calls.
+    pub fn update_block(&mut self, action: impl FnOnce(&mut BlockInfo)) {
+        action(&mut self.block);
+    }
+
+    /// Moves the chain forward by `blocks` blocks of 5 seconds each.
+    pub fn advance_height(&mut self, blocks: u64) {
+        self.block.height += blocks;
+        self.block.time = self.block.time.plus_seconds(blocks * BLOCK_TIME_SECONDS);
+    }
+
+    /// Moves the block time forward by `seconds` without changing the height.
+    pub fn advance_seconds(&mut self, seconds: u64) {
+        self.block.time = self.block.time.plus_seconds(seconds);
+    }
+
+    /// Sets the balance of the given address, replacing the previous balance.
+    pub fn init_balance(&mut self, addr: impl Into<String>, balance: Vec<Coin>) {
+        self.state.balances.insert(addr.into(), balance);
+    }
+
+    /// Registers a contract and returns its code ID.
+    pub fn store_code(&mut self, code: Box<dyn Contract<C, Q>>) -> u64 {
+        self.codes.push(code);
+        self.codes.len() as u64
+    }
+
+    /// Returns a querier to query the bank balances and contracts in this app.
+    pub fn wrap(&self) -> QuerierWrapper<'_, Q> {
+        QuerierWrapper::new(self)
+    }
+
+    /// Returns the storage of the given contract for inspection.
+    pub fn contract_storage(&self, contract_addr: &Addr) -> Option<MockStorage> {
+        self.state
+            .contracts
+            .get(contract_addr)
+            .map(|contract| contract.storage.borrow().clone())
+    }
+
+    pub fn instantiate_contract(
+        &mut self,
+        code_id: u64,
+        sender: Addr,
+        msg: &impl Serialize,
+        funds: &[Coin],
+        label: impl Into<String>,
+    ) -> StdResult<Addr> {
+        self.instantiate_contract_with_admin(code_id, sender, msg, funds, label, None)
+    }
+
+    /// Like [`App::instantiate_contract`] but sets an admin, who is allowed to migrate the contract.
+    pub fn instantiate_contract_with_admin(
+        &mut self,
+        code_id: u64,
+        sender: Addr,
+        msg: &impl Serialize,
+        funds: &[Coin],
+        label: impl Into<String>,
+        admin: Option<Addr>,
+    ) -> StdResult<Addr> {
+        let mut state = self.state.clone();
+        let (contract_addr, _) = self.instantiate(
+            &mut state,
+            &sender,
+            code_id,
+            admin,
+            to_vec(msg)?,
+            funds,
+            label.into(),
+        )?;
+        self.state = state;
+        Ok(contract_addr)
+    }
+
+    pub fn execute_contract(
+        &mut self,
+        sender: Addr,
+        contract_addr: Addr,
+        msg: &impl Serialize,
+        funds: &[Coin],
+    ) -> StdResult<AppResponse> {
+        self.execute(
+            sender,
+            WasmMsg::Execute {
+                contract_addr: contract_addr.into(),
+                msg: to_binary(msg)?,
+                funds: funds.to_vec(),
+            }
+            .into(),
+        )
+    }
+
+    /// Migrates the contract to `new_code_id`. This fails unless `sender` is the admin of the contract.
+    pub fn migrate_contract(
+        &mut self,
+        sender: Addr,
+        contract_addr: Addr,
+        new_code_id: u64,
+        msg: &impl Serialize,
+    ) -> StdResult<AppResponse> {
+        self.execute(
+            sender,
+            WasmMsg::Migrate {
+                contract_addr: contract_addr.into(),
+                new_code_id,
+                msg: to_binary(msg)?,
+            }
+            .into(),
+        )
+    }
+
+    /// Executes the message as if it was sent by `sender` in a transaction.
+    /// All state changes are reverted if this fails.
+    pub fn execute(&mut self, sender: Addr, msg: CosmosMsg<C>) -> StdResult<AppResponse> {
+        let mut state = self.state.clone();
+        let response = self.execute_msg(&mut state, &sender, msg)?;
+        self.state = state;
+        Ok(response)
+    }
+
+    fn execute_msg(
+        &self,
+        state: &mut AppState,
+        sender: &Addr,
+        msg: CosmosMsg<C>,
+    ) -> StdResult<AppResponse> {
+        match msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                transfer(state, sender.as_str(), &to_address, &amount)?;
+                let event = Event::new("transfer")
+                    .add_attribute("recipient", to_address)
+                    .add_attribute("sender", sender)
+                    .add_attribute("amount", coins_to_string(&amount));
+                Ok(AppResponse {
+                    events: vec![event],
+                    data: None,
+                })
+            }
+            CosmosMsg::Bank(BankMsg::Burn { amount }) => {
+                let balance = state.balances.entry(sender.to_string()).or_default();
+                sub_coins(balance, &amount)?;
+                let event = Event::new("burn")
+                    .add_attribute("burner", sender)
+                    .add_attribute("amount", coins_to_string(&amount));
+                Ok(AppResponse {
+                    events: vec![event],
+                    data: None,
+                })
+            }
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                funds,
+            }) => {
+                let contract_addr = Addr::unchecked(contract_addr);
+                if !state.contracts.contains_key(&contract_addr) {
+                    return Err(StdError::generic_err(format!(
+                        "Unknown contract: {}",
+                        contract_addr
+                    )));
+                }
+                transfer(state, sender.as_str(), contract_addr.as_str(), &funds)?;
+                let info = MessageInfo {
+                    sender: sender.clone(),
+                    funds,
+                };
+                let response =
+                    self.call_contract(state, &contract_addr, |contract, deps, env| {
+                        contract.execute(deps, env, info, msg.as_slice())
+                    })?;
+                self.process_response(state, &contract_addr, Event::new("execute"), response)
+            }
+            CosmosMsg::Wasm(WasmMsg::Instantiate {
+                admin,
+                code_id,
+                msg,
+                funds,
+                label,
+            }) => {
+                let admin = admin.map(Addr::unchecked);
+                let (contract_addr, mut response) =
+                    self.instantiate(state, sender, code_id, admin, msg.into(), &funds, label)?;
+                response.data = Some(instantiate_response_data(
+                    &contract_addr,
+                    response.data.as_ref(),
+                ));
+                Ok(response)
+            }
+            CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr,
+                new_code_id,
+                msg,
+            }) => {
+                let contract_addr = Addr::unchecked(contract_addr);
+                let contract = state.contracts.get_mut(&contract_addr).ok_or_else(|| {
+                    StdError::generic_err(format!("Unknown contract: {}", contract_addr))
+                })?;
+                if contract.admin.as_ref() != Some(sender) {
+                    return Err(StdError::generic_err(format!(
+                        "Only the admin can migrate {}",
+                        contract_addr
+                    )));
+                }
+                self.check_code_id(new_code_id)?;
+                contract.code_id = new_code_id;
+
+                let response =
+                    self.call_contract(state, &contract_addr, |contract, deps, env| {
+                        contract.migrate(deps, env, msg.as_slice())
+                    })?;
+                let event = Event::new("migrate").add_attribute("code_id", new_code_id.to_string());
+                self.process_response(state, &contract_addr, event, response)
+            }
+            msg => Err(StdError::generic_err(format!(
+                "Unsupported message: {:?}",
+                msg
+            ))),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn instantiate(
+        &self,
+        state: &mut AppState,
+        sender: &Addr,
+        code_id: u64,
+        admin: Option<Addr>,
+        msg: Vec<u8>,
+        funds: &[Coin],
+        label: String,
+    ) -> StdResult<(Addr, AppResponse)> {
+        self.check_code_id(code_id)?;
+        if label.is_empty() {
+            return Err(StdError::generic_err("Label must not be empty"));
+        }
+
+        let contract_addr = Addr::unchecked(format!("contract{}", state.contracts.len()));
+        state.contracts.insert(
+            contract_addr.clone(),
+            ContractData {
+                code_id,
+                creator: sender.clone(),
+                admin,
+                storage: RefCell::new(MockStorage::new()),
+            },
+        );
+        transfer(state, sender.as_str(), contract_addr.as_str(), funds)?;
+
+        let info = MessageInfo {
+            sender: sender.clone(),
+            funds: funds.to_vec(),
+        };
+        let response = self.call_contract(state, &contract_addr, |contract, deps, env| {
+            contract.instantiate(deps, env, info, &msg)
+        })?;
+        let event = Event::new("instantiate").add_attribute("code_id", code_id.to_string());
+        let response = self.process_response(state, &contract_addr, event, response)?;
+        Ok((contract_addr, response))
+    }
+
+    fn check_code_id(&self, code_id: u64) -> StdResult<()> {
+        if code_id == 0 || code_id as usize > self.codes.len() {
+            return Err(StdError::generic_err(format!(
+                "Unknown code ID: {}",
+                code_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Calls an entry point of the contract with the contract's storage.
+    fn call_contract<F>(
+        &self,
+        state: &AppState,
+        contract_addr: &Addr,
+        call: F,
+    ) -> StdResult<Response<C>>
+    where
+        F: FnOnce(&dyn Contract<C, Q>, DepsMut<Q>, Env) -> Result<Response<C>, String>,
+    {
+        let contract = &state.contracts[contract_addr];
+        let code = self.codes[contract.code_id as usize - 1].as_ref();
+        let querier = AppQuerier { app: self, state };
+        let mut storage = contract.storage.borrow_mut();
+        let deps = DepsMut {
+            storage: &mut *storage,
+            api: &self.api,
+            querier: QuerierWrapper::new(&querier),
+        };
+        call(code, deps, self.env(contract_addr)).map_err(StdError::generic_err)
+    }
+
+    /// Emits the events of the response like wasmd and executes the messages.
+    fn process_response(
+        &self,
+        state: &mut AppState,
+        contract_addr: &Addr,
+        event: Event,
+        response: Response<C>,
+    ) -> StdResult<AppResponse> {
+        let mut events = vec![event.add_attribute("_contract_address", contract_addr)];
+        if !response.attributes.is_empty() {
+            events.push(
+                Event::new("wasm")
+                    .add_attribute("_contract_address", contract_addr)
+                    .add_attributes(response.attributes),
+            );
+        }
+        events.extend(response.events.into_iter().map(|event| {
+            Event::new(format!("wasm-{}", event.ty))
+                .add_attribute("_contract_address", contract_addr)
+                .add_attributes(event.attributes)
+        }));
+
+        let mut data = response.data;
+        for sub_msg in response.messages {
+            if let Some(reply_data) =
+                self.execute_submsg(state, contract_addr, sub_msg, &mut events)?
+            {
+                data = Some(reply_data);
+            }
+        }
+
+        Ok(AppResponse { events, data })
+    }
+
+    /// Executes a submessage and calls the reply entry point according to `reply_on`.
+    /// Returns the data set by the reply, which overrides the data of the calling contract.
+    fn execute_submsg(
+        &self,
+        state: &mut AppState,
+        contract_addr: &Addr,
+        sub_msg: SubMsg<C>,
+        events: &mut Vec<Event>,
+    ) -> StdResult<Option<Binary>> {
+        let SubMsg {
+            id, msg, reply_on, ..
+        } = sub_msg;
+        let catches_error = matches!(reply_on, ReplyOn::Always | ReplyOn::Error);
+        // the submessage's state changes are reverted on error before the reply is called
+        let snapshot = if catches_error {
+            Some(state.clone())
+        } else {
+            None
+        };
+
+        let result = match self.execute_msg(state, contract_addr, msg) {
+            Ok(response) => {
+                events.extend(response.events.iter().cloned());
+                if !matches!(reply_on, ReplyOn::Always | ReplyOn::Success) {
+                    return Ok(None);
+                }
+                SubMsgResult::Ok(SubMsgResponse {
+                    events: response.events,
+                    data: response.data,
+                })
+            }
+            Err(err) => match snapshot {
+                Some(snapshot) => {
+                    *state = snapshot;
+                    SubMsgResult::Err(err.to_string())
+                }
+                None => return Err(err),
+            },
+        };
+
+        let reply = Reply { id, result };
+        let response = self.call_contract(state, contract_addr, |contract, deps, env| {
+            contract.reply(deps, env, reply)
+        })?;
+        let response =
+            self.process_response(state, contract_addr, Event::new("reply"), response)?;
+        events.extend(response.events);
+        Ok(response.data)
+    }
+
+    fn env(&self, contract_addr: &Addr) -> Env {
+        let mut env = mock_env();
+        env.block = self.block.clone();
+        env.contract.address = contract_addr.clone();
+        env
+    }
+}
+
+impl<C, Q> Querier for App<C, Q>
+where
+    C: Clone + fmt::Debug + 'static,
+    Q: CustomQuery + DeserializeOwned + 'static,
+{
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        AppQuerier {
+            app: self,
+            state: &self.state,
+        }
+        .raw_query(bin_request)
+    }
+}
+
+/// Answers queries from the state of an app during the execution of a message.
+struct AppQuerier<'a, C, Q: CustomQuery> {
+    app: &'a App<C, Q>,
+    state: &'a AppState,
+}
+
+impl<'a, C, Q> Querier for AppQuerier<'a, C, Q>
+where
+    C: Clone + fmt::Debug + 'static,
+    Q: CustomQuery + DeserializeOwned + 'static,
+{
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Q> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        match request {
+            QueryRequest::Bank(query) => self.query_bank(&query),
+            QueryRequest::Wasm(query) => self.query_wasm(query),
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "non-bank/wasm".to_string(),
+            }),
+        }
+    }
+}
+
+impl<'a, C, Q> AppQuerier<'a, C, Q>
+where
+    C: Clone + fmt::Debug + 'static,
+    Q: CustomQuery + DeserializeOwned + 'static,
+{
+    fn query_bank(&self, query: &BankQuery) -> QuerierResult {
+        let balances: Vec<(&str, &[Coin])> = self
+            .state
+            .balances
+            .iter()
+            .map(|(addr, balance)| (addr.as_str(), balance.as_slice()))
+            .collect();
+        BankQuerier::new(&balances).query(query)
+    }
+
+    fn query_wasm(&self, query: WasmQuery) -> QuerierResult {
+        let addr = match &query {
+            WasmQuery::Smart { contract_addr, .. }
+            | WasmQuery::Raw { contract_addr, .. }
+            | WasmQuery::ContractInfo { contract_addr } => Addr::unchecked(contract_addr),
+            #[cfg(feature = "raw_range")]
+            WasmQuery::RawRange { contract_addr, .. } => Addr::unchecked(contract_addr),
+        };
+        let contract = match self.state.contracts.get(&addr) {
+            Some(contract) => contract,
+            None => return SystemResult::Err(SystemError::NoSuchContract { addr: addr.into() }),
+        };
+        // The storage is borrowed mutably while the contract is executed
+        let storage = match contract.storage.try_borrow() {
+            Ok(storage) => storage,
+            Err(_) => {
+                return SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: format!("query to {} while it is executed", addr),
+                })
+            }
+        };
+
+        let result = match query {
+            WasmQuery::Smart { msg, .. } => {
+                let code = self.app.codes[contract.code_id as usize - 1].as_ref();
+                let deps = Deps {
+                    storage: &*storage,
+                    api: &self.app.api,
+                    querier: QuerierWrapper::new(self),
+                };
+                code.query(deps, self.app.env(&addr), msg.as_slice())
+            }
+            WasmQuery::Raw { key, .. } => Ok(Binary::from(
+                storage.get(key.as_slice()).unwrap_or_default(),
+            )),
+            WasmQuery::ContractInfo { .. } => {
+                let mut response = ContractInfoResponse::new(contract.code_id, &contract.creator);
+                response.admin = contract.admin.as_ref().map(|admin| admin.to_string());
+                to_binary(&response).map_err(|e| e.to_string())
+            }
+            #[cfg(all(feature = "raw_range", feature = "iterator"))]
+            WasmQuery::RawRange {
+                start,
+                end,
+                limit,
+                order,
+                ..
+            } => query_raw_range(&*storage, start, end, limit, order),
+            #[cfg(all(feature = "raw_range", not(feature = "iterator")))]
+            WasmQuery::RawRange { .. } => {
+                return SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "raw range query without iterator feature".to_string(),
+                })
+            }
+        };
+        SystemResult::Ok(ContractResult::from(result))
+    }
+}
+
+#[cfg(all(feature = "raw_range", feature = "iterator"))]
+fn query_raw_range(
+    storage: &dyn Storage,
+    start: Option<Binary>,
+    end: Option<Binary>,
+    limit: u16,
+    order: Order,
+) -> Result<Binary, String> {
+    // load one more item to find out if there is a next page
+    let mut data: Vec<(Binary, Binary)> = storage
+        .range(start.as_deref(), end.as_deref(), order)
+        .take(limit as usize + 1)
+        .map(|(key, value)| (Binary(key), Binary(value)))
+        .collect();
+    let next_key = if data.len() > limit as usize {
+        data.pop().map(|(key, _)| key)
+    } else {
+        None
+    };
+    to_binary(&RawRangeResponse::new(data, next_key)).map_err(|e| e.to_string())
+}
+
+fn transfer(state: &mut AppState, from: &str, to: &str, amount: &[Coin]) -> StdResult<()> {
+    if amount.is_empty() {
+        return Ok(());
+    }
+    sub_coins(state.balances.entry(from.to_string()).or_default(), amount)?;
+    add_coins(state.balances.entry(to.to_string()).or_default(), amount);
+    Ok(())
+}
+
+fn add_coins(balance: &mut Vec<Coin>, amount: &[Coin]) {
+    for coin in amount {
+        match balance.iter_mut().find(|c| c.denom == coin.denom) {
+            Some(existing) => existing.amount += coin.amount,
+            None => balance.push(coin.clone()),
+        }
+    }
+    balance.retain(|c| !c.amount.is_zero());
+}
+
+fn sub_coins(balance: &mut Vec<Coin>, amount: &[Coin]) -> StdResult<()> {
+    for coin in amount {
+        let existing = balance
+            .iter_mut()
+            .find(|c| c.denom == coin.denom)
+            .filter(|c| c.amount >= coin.amount)
+            .ok_or_else(|| {
+                StdError::generic_err(format!("Insufficient funds: {} required", coin))
+            })?;
+        existing.amount -= coin.amount;
+    }
+    balance.retain(|c| !c.amount.is_zero());
+    Ok(())
+}
+
+fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(|coin| coin.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Encodes a `MsgInstantiateContractResponse` as protobuf, which is the data wasmd returns
+/// for instantiate messages.
+fn instantiate_response_data(contract_addr: &Addr, data: Option<&Binary>) -> Binary {
+    let mut out = Vec::new();
+    encode_proto_bytes(&mut out, 1, contract_addr.as_bytes());
+    if let Some(data) = data {
+        encode_proto_bytes(&mut out, 2, data.as_slice());
+    }
+    out.into()
+}
+
+fn encode_proto_bytes(out: &mut Vec<u8>, field: u8, value: &[u8]) {
+    // wire type 2 (length-delimited)
+    out.push(field << 3 | 2);
+    let mut len = value.len();
+    while len >= 0x80 {
+        out.push((len as u8 & 0x7f) | 0x80);
+        len >>= 7;
+    }
+    out.push(len as u8);
+    out.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coins;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum ExecuteMsg {
+        /// Stores the value and forwards the message to the next contract, if any
+        Store {
+            value: String,
+            next: Option<String>,
+        },
+        Fail {},
+        /// Sends the message to the contract as a submessage with the given `reply_on`
+        Sub {
+            contract_addr: String,
+            msg: Box<ExecuteMsg>,
+            reply_on: ReplyOn,
+        },
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum QueryMsg {
+        Value {},
+        /// Queries the value of another contract
+        Other {
+            addr: String,
+        },
+    }
+
+    fn instantiate(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: String,
+    ) -> StdResult<Response> {
+        deps.storage.set(b"value", msg.as_bytes());
+        Ok(Response::new()
+            .add_attribute("creator", info.sender)
+            .set_data(b"hello"))
+    }
+
+    fn execute(
+        deps: DepsMut,
+        env: Env,
+        _info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Store { value, next } => {
+                deps.storage.set(b"value", value.as_bytes());
+                let mut response = Response::new()
+                    .add_attribute("action", "store")
+                    .add_event(Event::new("stored").add_attribute("value", &value));
+                if let Some(next) = next {
+                    response = response.add_message(WasmMsg::Execute {
+                        contract_addr: next,
+                        msg: to_binary(&ExecuteMsg::Store { value, next: None })?,
+                        funds: vec![],
+                    });
+                }
+                let balance = deps.querier.query_all_balances(env.contract.address)?;
+                if !balance.is_empty() {
+                    response = response.add_message(BankMsg::Burn { amount: balance });
+                }
+                Ok(response)
+            }
+            ExecuteMsg::Fail {} => Err(StdError::generic_err("Fail")),
+            ExecuteMsg::Sub {
+                contract_addr,
+                msg,
+                reply_on,
+            } => {
+                let msg = WasmMsg::Execute {
+                    contract_addr,
+                    msg: to_binary(&msg)?,
+                    funds: vec![],
+                };
+                let mut sub_msg = SubMsg::new(msg);
+                sub_msg.id = 7;
+                sub_msg.reply_on = reply_on;
+                Ok(Response::new().add_submessage(sub_msg).set_data(b"execute"))
+            }
+        }
+    }
+
+    fn reply(deps: DepsMut, _env: Env, reply: Reply) -> StdResult<Response> {
+        let result = match reply.result {
+            SubMsgResult::Ok(response) => format!("ok: {} events", response.events.len()),
+            SubMsgResult::Err(err) => format!("err: {}", err),
+        };
+        deps.storage.set(b"reply", result.as_bytes());
+        Ok(Response::new()
+            .add_attribute("reply_id", reply.id.to_string())
+            .set_data(b"reply"))
+    }
+
+    fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::Value {} => {
+                let value = deps.storage.get(b"value").unwrap_or_default();
+                to_binary(&String::from_utf8(value).unwrap())
+            }
+            QueryMsg::Other { addr } => {
+                let value: String = deps.querier.query_wasm_smart(addr, &QueryMsg::Value {})?;
+                to_binary(&value)
+            }
+        }
+    }
+
+    fn setup() -> (App, Addr, Addr) {
+        let mut app = App::new();
+        let code_id = app.store_code(Box::new(
+            ContractWrapper::new(instantiate, execute, query).with_reply(reply),
+        ));
+        let first = app
+            .instantiate_contract(code_id, Addr::unchecked("creator"), &"first", &[], "first")
+            .unwrap();
+        let second = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked("creator"),
+                &"second",
+                &[],
+                "second",
+            )
+            .unwrap();
+        (app, first, second)
+    }
+
+    fn query_value(app: &App, contract: &Addr) -> String {
+        app.wrap()
+            .query_wasm_smart(contract, &QueryMsg::Value {})
+            .unwrap()
+    }
+
+    #[test]
+    fn block_can_be_advanced() {
+        let mut app = App::<Empty, Empty>::new();
+        let start = app.block_info();
+
+        app.advance_height(3);
+        let block = app.block_info();
+        assert_eq!(block.height, start.height + 3);
+        assert_eq!(block.time, start.time.plus_seconds(15));
+
+        app.advance_seconds(100);
+        let block = app.block_info();
+        assert_eq!(block.height, start.height + 3);
+        assert_eq!(block.time, start.time.plus_seconds(115));
+
+        app.update_block(|block| {
+            block.height = 1;
+            block.chain_id = "other-chain".to_string();
+        });
+        let block = app.block_info();
+        assert_eq!(block.height, 1);
+        assert_eq!(block.chain_id, "other-chain");
+        assert_eq!(block.time, start.time.plus_seconds(115));
+    }
+
+    #[test]
+    fn instantiate_works() {
+        let (app, first, second) = setup();
+        assert_eq!(first, "contract0");
+        assert_eq!(second, "contract1");
+        assert_eq!(query_value(&app, &first), "first");
+        assert_eq!(query_value(&app, &second), "second");
+
+        let info = app.wrap().query_wasm_contract_info(&first).unwrap();
+        assert_eq!(info.code_id, 1);
+        assert_eq!(info.creator, "creator");
+        let raw = app
+            .wrap()
+            .query_wasm_raw(&second, b"value".as_ref())
+            .unwrap();
+        assert_eq!(raw, Some(b"second".to_vec()));
+
+        // unknown code
+        let err = App::<Empty>::new()
+            .instantiate_contract(1, Addr::unchecked("creator"), &"x", &[], "x")
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unknown code ID: 1"));
+    }
+
+    #[test]
+    fn execute_runs_returned_messages() {
+        let (mut app, first, second) = setup();
+        let res = app
+            .execute_contract(
+                Addr::unchecked("alice"),
+                first.clone(),
+                &ExecuteMsg::Store {
+                    value: "foo".to_string(),
+                    next: Some(second.to_string()),
+                },
+                &[],
+            )
+            .unwrap();
+        assert_eq!(query_value(&app, &first), "foo");
+        assert_eq!(query_value(&app, &second), "foo");
+
+        let types: Vec<_> = res.events.iter().map(|e| e.ty.as_str()).collect();
+        assert_eq!(
+            types,
+            [
+                "execute",
+                "wasm",
+                "wasm-stored",
+                "execute",
+                "wasm",
+                "wasm-stored"
+            ]
+        );
+        assert_eq!(
+            res.events[1],
+            Event::new("wasm")
+                .add_attribute("_contract_address", "contract0")
+                .add_attribute("action", "store")
+        );
+        assert_eq!(
+            res.events[3],
+            Event::new("execute").add_attribute("_contract_address", "contract1")
+        );
+
+        // queries between contracts
+        let value: String = app
+            .wrap()
+            .query_wasm_smart(
+                &first,
+                &QueryMsg::Other {
+                    addr: second.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(value, "foo");
+    }
+
+    #[test]
+    fn funds_are_transferred() {
+        let (mut app, first, _) = setup();
+        app.init_balance("alice", coins(100, "ucosm"));
+
+        let res = app
+            .execute(
+                Addr::unchecked("alice"),
+                BankMsg::Send {
+                    to_address: "bob".to_string(),
+                    amount: coins(40, "ucosm"),
+                }
+                .into(),
+            )
+            .unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("transfer")
+                .add_attribute("recipient", "bob")
+                .add_attribute("sender", "alice")
+                .add_attribute("amount", "40ucosm")]
+        );
+
+        // funds sent to the contract are burned by it
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            first.clone(),
+            &ExecuteMsg::Store {
+                value: "foo".to_string(),
+                next: None,
+            },
+            &coins(10, "ucosm"),
+        )
+        .unwrap();
+
+        let wrapper = app.wrap();
+        assert_eq!(
+            wrapper.query_all_balances("alice").unwrap(),
+            coins(50, "ucosm")
+        );
+        assert_eq!(
+            wrapper.query_all_balances("bob").unwrap(),
+            coins(40, "ucosm")
+        );
+        assert_eq!(wrapper.query_all_balances(&first).unwrap(), vec![]);
+
+        let err = app
+            .execute(
+                Addr::unchecked("bob"),
+                BankMsg::Send {
+                    to_address: "alice".to_string(),
+                    amount: coins(41, "ucosm"),
+                }
+                .into(),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Insufficient funds: 41ucosm required")
+        );
+    }
+
+    #[test]
+    fn failures_revert_state() {
+        let (mut app, first, _) = setup();
+        app.init_balance("alice", coins(100, "ucosm"));
+
+        // the first contract stores the value, the message to the unknown contract fails
+        let err = app
+            .execute_contract(
+                Addr::unchecked("alice"),
+                first.clone(),
+                &ExecuteMsg::Store {
+                    value: "foo".to_string(),
+                    next: Some("unknown".to_string()),
+                },
+                &coins(10, "ucosm"),
+            )
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unknown contract: unknown"));
+        assert_eq!(query_value(&app, &first), "first");
+        assert_eq!(
+            app.wrap().query_all_balances("alice").unwrap(),
+            coins(100, "ucosm")
+        );
+
+        let err = app
+            .execute_contract(Addr::unchecked("alice"), first, &ExecuteMsg::Fail {}, &[])
+            .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Generic error: Fail"));
+    }
+
+    #[test]
+    fn instantiate_message_returns_address_in_data() {
+        let (mut app, _, _) = setup();
+        let res = app
+            .execute(
+                Addr::unchecked("creator"),
+                WasmMsg::Instantiate {
+                    admin: Some("admin".to_string()),
+                    code_id: 1,
+                    msg: to_binary("third").unwrap(),
+                    funds: vec![],
+                    label: "third".to_string(),
+                }
+                .into(),
+            )
+            .unwrap();
+        let mut expected = vec![0x0a, 9];
+        expected.extend(b"contract2");
+        expected.extend([0x12, 5]);
+        expected.extend(b"hello");
+        assert_eq!(res.data.unwrap(), expected);
+        assert_eq!(res.events[0].ty, "instantiate");
+
+        let third = Addr::unchecked("contract2");
+        assert_eq!(query_value(&app, &third), "third");
+        let info = app.wrap().query_wasm_contract_info(&third).unwrap();
+        assert_eq!(info.admin, Some("admin".to_string()));
+    }
+
+    fn execute_sub(
+        app: &mut App,
+        contract: &Addr,
+        target: &Addr,
+        msg: ExecuteMsg,
+        reply_on: ReplyOn,
+    ) -> StdResult<AppResponse> {
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            contract.clone(),
+            &ExecuteMsg::Sub {
+                contract_addr: target.to_string(),
+                msg: Box::new(msg),
+                reply_on,
+            },
+            &[],
+        )
+    }
+
+    fn stored_reply(app: &App, contract: &Addr) -> Option<String> {
+        app.contract_storage(contract)
+            .unwrap()
+            .get(b"reply")
+            .map(|reply| String::from_utf8(reply).unwrap())
+    }
+
+    fn store_msg(value: &str) -> ExecuteMsg {
+        ExecuteMsg::Store {
+            value: value.to_string(),
+            next: None,
+        }
+    }
+
+    #[test]
+    fn reply_is_called_on_success() {
+        let (mut app, first, second) = setup();
+        let res = execute_sub(
+            &mut app,
+            &first,
+            &second,
+            store_msg("foo"),
+            ReplyOn::Success,
+        )
+        .unwrap();
+        assert_eq!(query_value(&app, &second), "foo");
+        assert_eq!(stored_reply(&app, &first).unwrap(), "ok: 3 events");
+
+        let types: Vec<_> = res.events.iter().map(|e| e.ty.as_str()).collect();
+        assert_eq!(
+            types,
+            ["execute", "execute", "wasm", "wasm-stored", "reply", "wasm"]
+        );
+        assert_eq!(
+            res.events[5],
+            Event::new("wasm")
+                .add_attribute("_contract_address", "contract0")
+                .add_attribute("reply_id", "7")
+        );
+        // the data of the reply overrides the data of execute
+        assert_eq!(res.data, Some(Binary::from(b"reply")));
+    }
+
+    #[test]
+    fn reply_is_not_called_for_other_results() {
+        let (mut app, first, second) = setup();
+        let res = execute_sub(&mut app, &first, &second, store_msg("foo"), ReplyOn::Error).unwrap();
+        assert_eq!(query_value(&app, &second), "foo");
+        assert_eq!(stored_reply(&app, &first), None);
+        assert_eq!(res.data, Some(Binary::from(b"execute")));
+
+        let err = execute_sub(
+            &mut app,
+            &first,
+            &second,
+            ExecuteMsg::Fail {},
+            ReplyOn::Success,
+        )
+        .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Generic error: Fail"));
+        let err = execute_sub(
+            &mut app,
+            &first,
+            &second,
+            ExecuteMsg::Fail {},
+            ReplyOn::Never,
+        )
+        .unwrap_err();
+        assert_eq!(err, StdError::generic_err("Generic error: Fail"));
+        assert_eq!(stored_reply(&app, &first), None);
+    }
+
+    #[test]
+    fn reply_is_called_on_error_and_reverts_submessage() {
+        let (mut app, first, second) = setup();
+        // the submessage stores "bar" in second, then fails forwarding to an unknown contract
+        let msg = ExecuteMsg::Store {
+            value: "bar".to_string(),
+            next: Some("unknown".to_string()),
+        };
+        let res = execute_sub(&mut app, &first, &second, msg, ReplyOn::Always).unwrap();
+        assert_eq!(query_value(&app, &second), "second");
+        assert_eq!(
+            stored_reply(&app, &first).unwrap(),
+            "err: Generic error: Unknown contract: unknown"
+        );
+
+        // no events of the failed submessage
+        let types: Vec<_> = res.events.iter().map(|e| e.ty.as_str()).collect();
+        assert_eq!(types, ["execute", "reply", "wasm"]);
+    }
+
+    #[test]
+    fn reply_fails_for_contracts_without_reply() {
+        let mut app = App::new();
+        let code_id = app.store_code(Box::new(ContractWrapper::new(instantiate, execute, query)));
+        let contract = app
+            .instantiate_contract(code_id, Addr::unchecked("creator"), &"first", &[], "first")
+            .unwrap();
+
+        let err = execute_sub(
+            &mut app,
+            &contract,
+            &contract.clone(),
+            ExecuteMsg::Fail {},
+            ReplyOn::Error,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Contract does not implement reply")
+        );
+    }
+
+    fn migrate(deps: DepsMut, _env: Env, msg: String) -> StdResult<Response> {
+        deps.storage.set(b"value", msg.as_bytes());
+        Ok(Response::new().add_attribute("action", "migrate"))
+    }
+
+    #[test]
+    fn migrate_works_for_admin() {
+        let mut app = App::new();
+        let old_code = app.store_code(Box::new(ContractWrapper::new(instantiate, execute, query)));
+        let new_code = app.store_code(Box::new(
+            ContractWrapper::new(instantiate, execute, query).with_migrate(migrate),
+        ));
+        let admin = Addr::unchecked("admin");
+        let contract = app
+            .instantiate_contract_with_admin(
+                old_code,
+                Addr::unchecked("creator"),
+                &"first",
+                &[],
+                "first",
+                Some(admin.clone()),
+            )
+            .unwrap();
+
+        let res = app
+            .migrate_contract(admin, contract.clone(), new_code, &"migrated")
+            .unwrap();
+        assert_eq!(
+            res.events[0],
+            Event::new("migrate")
+                .add_attribute("code_id", "2")
+                .add_attribute("_contract_address", &contract)
+        );
+        assert_eq!(query_value(&app, &contract), "migrated");
+        let info = app.wrap().query_wasm_contract_info(&contract).unwrap();
+        assert_eq!(info.code_id, new_code);
+    }
+
+    #[test]
+    fn migrate_fails_for_non_admin() {
+        let mut app = App::new();
+        let code_id = app.store_code(Box::new(
+            ContractWrapper::new(instantiate, execute, query).with_migrate(migrate),
+        ));
+        let contract = app
+            .instantiate_contract(code_id, Addr::unchecked("creator"), &"first", &[], "first")
+            .unwrap();
+
+        // contracts without admin cannot be migrated, not even by the creator
+        let err = app
+            .migrate_contract(Addr::unchecked("creator"), contract.clone(), code_id, &"x")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Only the admin can migrate contract0")
+        );
+        assert_eq!(query_value(&app, &contract), "first");
+    }
+
+    #[test]
+    fn migrate_fails_for_contracts_without_migrate() {
+        let mut app = App::new();
+        let code_id = app.store_code(Box::new(ContractWrapper::new(instantiate, execute, query)));
+        let admin = Addr::unchecked("admin");
+        let contract = app
+            .instantiate_contract_with_admin(
+                code_id,
+                Addr::unchecked("creator"),
+                &"first",
+                &[],
+                "first",
+                Some(admin.clone()),
+            )
+            .unwrap();
+
+        let err = app
+            .migrate_contract(admin.clone(), contract.clone(), code_id, &"x")
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("Contract does not implement migrate")
+        );
+        let err = app.migrate_contract(admin, contract, 7, &"x").unwrap_err();
+        assert_eq!(err, StdError::generic_err("Unknown code ID: 7"));
+    }
+
+    #[cfg(all(feature = "raw_range", feature = "iterator"))]
+    #[test]
+    fn raw_range_query_works() {
+        use crate::Order;
+
+        let (mut app, first, second) = setup();
+        // stores "reply" in the first contract
+        execute_sub(
+            &mut app,
+            &first,
+            &second,
+            store_msg("foo"),
+            ReplyOn::Success,
+        )
+        .unwrap();
+
+        let page = app
+            .wrap()
+            .query_wasm_raw_range(&first, None, None, 1, Order::Ascending)
+            .unwrap();
+        assert_eq!(
+            page.data,
+            vec![(Binary::from(b"reply"), Binary::from(b"ok: 3 events"))]
+        );
+        assert_eq!(page.next_key, Some(Binary::from(b"value")));
+
+        let page = app
+            .wrap()
+            .query_wasm_raw_range(&first, page.next_key, None, 1, Order::Ascending)
+            .unwrap();
+        assert_eq!(
+            page.data,
+            vec![(Binary::from(b"value"), Binary::from(b"first"))]
+        );
+        assert_eq!(page.next_key, None);
+
+        let page = app
+            .wrap()
+            .query_wasm_raw_range(&first, None, None, 10, Order::Descending)
+            .unwrap();
+        let keys: Vec<_> = page.data.into_iter().map(|(key, _)| key).collect();
+        assert_eq!(keys, [Binary::from(b"value"), Binary::from(b"reply")]);
+    }
+}
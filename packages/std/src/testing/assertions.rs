@@ -1,6 +1,8 @@
-use crate::{Decimal, Uint128};
+use crate::{Attribute, Decimal, Event, Response, Uint128};
 use std::str::FromStr as _;
 
+use super::AppResponse;
+
 /// Asserts that two expressions are approximately equal to each other.
 ///
 /// The `max_rel_diff` argument defines the maximum relative difference
@@ -52,8 +54,120 @@ pub fn assert_approx_eq_impl<U: Into<Uint128>>(
     }
 }
 
+/// Assertions on the attributes and events of a contract response for use in tests.
+///
+/// This is implemented for [`Response`], where the attributes are the ones of the "wasm" event,
+/// and for [`AppResponse`], where all attributes of all emitted events are considered.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{Event, Response};
+/// # use cosmwasm_std::testing::ResponseAssertions;
+/// let res: Response = Response::new()
+///     .add_attribute("action", "transfer")
+///     .add_event(Event::new("foo").add_attribute("amount", "12"));
+///
+/// res.assert_attribute("action", "transfer");
+/// let event = res.assert_event("wasm-foo");
+/// assert_eq!(event.attributes[0].value, "12");
+/// ```
+pub trait ResponseAssertions {
+    /// Returns the values of all attributes with the given key in order.
+    fn attribute_values(&self, key: &str) -> Vec<&str>;
+
+    /// Returns the value of the first attribute with the given key.
+    fn attribute(&self, key: &str) -> Option<&str> {
+        self.attribute_values(key).into_iter().next()
+    }
+
+    /// Returns the first event of the given type. Custom events of a [`Response`] can be
+    /// referred to with or without the "wasm-" prefix added by wasmd.
+    fn event(&self, ty: &str) -> Option<&Event>;
+
+    /// Panics if there is no attribute with the given key and value.
+    /// If multiple attributes have the given key, any of them can match.
+    #[track_caller]
+    fn assert_attribute(&self, key: &str, value: &str) -> &Self {
+        let values = self.attribute_values(key);
+        if values.contains(&value) {
+            return self;
+        }
+        match values.as_slice() {
+            [] => panic!("Attribute {:?} not found", key),
+            [actual] => panic!(
+                "Attribute {:?} has value {:?} but {:?} was expected",
+                key, actual, value
+            ),
+            _ => panic!(
+                "Attribute {:?} has values {:?} but {:?} was expected",
+                key, values, value
+            ),
+        }
+    }
+
+    /// Panics if there is no event of the given type and returns the event otherwise.
+    #[track_caller]
+    fn assert_event(&self, ty: &str) -> &Event {
+        match self.event(ty) {
+            Some(event) => event,
+            None => panic!("Event {:?} not found", ty),
+        }
+    }
+}
+
+impl<T> ResponseAssertions for Response<T> {
+    fn attribute_values(&self, key: &str) -> Vec<&str> {
+        find_attributes(&self.attributes, key)
+    }
+
+    fn event(&self, ty: &str) -> Option<&Event> {
+        let unprefixed = ty.strip_prefix("wasm-").unwrap_or(ty);
+        self.events
+            .iter()
+            .find(|event| event.ty == ty || event.ty == unprefixed)
+    }
+}
+
+impl ResponseAssertions for AppResponse {
+    fn attribute_values(&self, key: &str) -> Vec<&str> {
+        self.events
+            .iter()
+            .flat_map(|event| find_attributes(&event.attributes, key))
+            .collect()
+    }
+
+    fn event(&self, ty: &str) -> Option<&Event> {
+        self.events.iter().find(|event| event.ty == ty)
+    }
+}
+
+fn find_attributes<'a>(attributes: &'a [Attribute], key: &str) -> Vec<&'a str> {
+    attributes
+        .iter()
+        .filter(|attr| attr.key == key)
+        .map(|attr| attr.value.as_str())
+        .collect()
+}
+
+/// Panics if the response does not contain an attribute with the given key and value.
+/// See [`ResponseAssertions::assert_attribute`].
+#[track_caller]
+pub fn assert_attribute(res: &impl ResponseAssertions, key: &str, value: &str) {
+    res.assert_attribute(key, value);
+}
+
+/// Panics if the response does not contain an event of the given type.
+/// See [`ResponseAssertions::assert_event`].
+#[track_caller]
+pub fn assert_event<'a, R: ResponseAssertions>(res: &'a R, ty: &str) -> &'a Event {
+    res.assert_event(ty)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn assert_approx() {
         assert_approx_eq!(9_u32, 10_u32, "0.12");
@@ -105,4 +219,93 @@ mod tests {
             Foo(8),
         );
     }
+
+    fn response() -> Response {
+        Response::new()
+            .add_attribute("action", "transfer")
+            .add_attribute("amount", "12")
+            .add_event(Event::new("foo").add_attribute("bar", "baz"))
+    }
+
+    #[test]
+    fn response_assertions_work() {
+        let res = response();
+        res.assert_attribute("action", "transfer")
+            .assert_attribute("amount", "12");
+        assert_eq!(res.attribute("missing"), None);
+        assert_eq!(res.assert_event("foo").attributes[0].value, "baz");
+        assert_eq!(res.assert_event("wasm-foo").ty, "foo");
+        assert_eq!(res.event("wasm-bar"), None);
+
+        assert_attribute(&res, "action", "transfer");
+        assert_eq!(assert_event(&res, "wasm-foo").ty, "foo");
+    }
+
+    #[test]
+    fn response_assertions_work_for_app_response() {
+        let res = AppResponse {
+            events: vec![
+                Event::new("execute").add_attribute("_contract_address", "contract0"),
+                Event::new("wasm").add_attribute("action", "transfer"),
+                Event::new("wasm-foo").add_attribute("bar", "baz"),
+            ],
+            data: None,
+        };
+        res.assert_attribute("_contract_address", "contract0")
+            .assert_attribute("action", "transfer");
+        assert_eq!(res.assert_event("wasm-foo").attributes.len(), 1);
+        assert_eq!(res.event("foo"), None);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attribute \"action\" has value \"transfer\" but \"burn\" was expected"
+    )]
+    fn assert_attribute_panics_for_wrong_value() {
+        response().assert_attribute("action", "burn");
+    }
+
+    #[test]
+    fn assert_attribute_works_for_duplicate_keys() {
+        let res: Response = Response::new()
+            .add_attribute("recipient", "alice")
+            .add_attribute("recipient", "bob");
+        res.assert_attribute("recipient", "alice")
+            .assert_attribute("recipient", "bob");
+        assert_eq!(res.attribute("recipient"), Some("alice"));
+        assert_eq!(res.attribute_values("recipient"), ["alice", "bob"]);
+
+        let res = AppResponse {
+            events: vec![
+                Event::new("wasm").add_attribute("recipient", "alice"),
+                Event::new("wasm").add_attribute("recipient", "bob"),
+            ],
+            data: None,
+        };
+        res.assert_attribute("recipient", "alice")
+            .assert_attribute("recipient", "bob");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attribute \"recipient\" has values [\"alice\", \"bob\"] but \"carol\" was expected"
+    )]
+    fn assert_attribute_panics_for_wrong_value_with_duplicate_keys() {
+        let res: Response = Response::new()
+            .add_attribute("recipient", "alice")
+            .add_attribute("recipient", "bob");
+        res.assert_attribute("recipient", "carol");
+    }
+
+    #[test]
+    #[should_panic(expected = "Attribute \"sender\" not found")]
+    fn assert_attribute_panics_for_missing_attribute() {
+        assert_attribute(&response(), "sender", "alice");
+    }
+
+    #[test]
+    #[should_panic(expected = "Event \"wasm-bar\" not found")]
+    fn assert_event_panics_for_missing_event() {
+        assert_event(&response(), "wasm-bar");
+    }
 }
@@ -0,0 +1,155 @@
+//! A minimal bech32 (BIP-173) implementation for address handling in MockApi.
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+/// Maximum length of a bech32 string. BIP-173 limits this to 90 characters but the
+/// Cosmos SDK allows longer strings to support 32 byte addresses and beyond.
+const MAX_LENGTH: usize = 1023;
+
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    let mut chk: u32 = 1;
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ u32::from(value);
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let bytes = hrp.as_bytes();
+    let mut out: Vec<u8> = bytes.iter().map(|b| b >> 5).collect();
+    out.push(0);
+    out.extend(bytes.iter().map(|b| b & 0x1f));
+    out
+}
+
+/// Regroups bits, e.g. from 8-bit bytes to 5-bit words and back.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, String> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max = (1u32 << to) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for &value in data {
+        acc = (acc << from) | u32::from(value);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return Err("invalid padding".to_string());
+    }
+    Ok(out)
+}
+
+/// Encodes the data bytes with the given human readable part.
+pub fn encode(hrp: &str, data: &[u8]) -> String {
+    let words = convert_bits(data, 8, 5, true).expect("padding is always valid when encoding");
+    let mut values = hrp_expand(hrp);
+    values.extend(&words);
+    values.extend([0u8; 6]);
+    let checksum = polymod(values) ^ 1;
+
+    let mut out = String::with_capacity(hrp.len() + 1 + words.len() + 6);
+    out.push_str(hrp);
+    out.push('1');
+    for word in words {
+        out.push(CHARSET[word as usize] as char);
+    }
+    for i in 0..6 {
+        out.push(CHARSET[((checksum >> (5 * (5 - i))) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+/// Decodes a bech32 string into its human readable part and data bytes.
+pub fn decode(input: &str) -> Result<(String, Vec<u8>), String> {
+    if input.len() > MAX_LENGTH {
+        return Err("too long".to_string());
+    }
+    let has_lower = input.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = input.chars().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err("mixed case".to_string());
+    }
+    let input = input.to_ascii_lowercase();
+
+    let separator = input.rfind('1').ok_or("missing separator")?;
+    let (hrp, rest) = (&input[..separator], &input[separator + 1..]);
+    if hrp.is_empty() || rest.len() < 6 {
+        return Err("invalid length".to_string());
+    }
+    if !hrp.bytes().all(|b| (33..=126).contains(&b)) {
+        return Err("invalid character in human readable part".to_string());
+    }
+    let words = rest
+        .bytes()
+        .map(|c| {
+            CHARSET
+                .iter()
+                .position(|&x| x == c)
+                .map(|p| p as u8)
+                .ok_or_else(|| format!("invalid character '{}'", c as char))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+
+    let mut values = hrp_expand(hrp);
+    values.extend(&words);
+    if polymod(values) != 1 {
+        return Err("invalid checksum".to_string());
+    }
+
+    let data = convert_bits(&words[..words.len() - 6], 5, 8, false)?;
+    Ok((hrp.to_string(), data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_works_for_bip173_vectors() {
+        assert_eq!(decode("A12UEL5L").unwrap(), ("a".to_string(), vec![]));
+        assert_eq!(
+            decode("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw").unwrap(),
+            (
+                "abcdef".to_string(),
+                hex::decode("00443214c74254b635cf84653a56d7c675be77df").unwrap()
+            )
+        );
+
+        // invalid checksum
+        assert!(decode("A12UEL5M").is_err());
+        // mixed case
+        assert!(decode("A12uEL5L").is_err());
+        // missing separator
+        assert!(decode("pzry9x0s0muk").is_err());
+        // invalid character
+        assert!(decode("a1b2uel5l").is_err());
+        // empty human readable part
+        assert!(decode("10a06t8").is_err());
+    }
+
+    #[test]
+    fn encode_decode_roundtrip() {
+        let data = hex::decode("00443214c74254b635cf84653a56d7c675be77df").unwrap();
+        let encoded = encode("abcdef", &data);
+        assert_eq!(encoded, "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw");
+
+        for len in 0..40 {
+            let data: Vec<u8> = (0..len).map(|i| (i * 37 + 11) as u8).collect();
+            let encoded = encode("cosmwasm", &data);
+            assert_eq!(decode(&encoded).unwrap(), ("cosmwasm".to_string(), data));
+        }
+    }
+}
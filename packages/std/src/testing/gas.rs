@@ -0,0 +1,422 @@
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use crate::addresses::{Addr, CanonicalAddr};
+use crate::deps::OwnedDeps;
+use crate::errors::{RecoverPubkeyError, StdResult, VerificationError};
+#[cfg(feature = "iterator")]
+use crate::iterator::{Order, Record};
+use crate::query::CustomQuery;
+use crate::results::{ContractResult, SystemResult};
+use crate::traits::{Api, Querier, QuerierResult, Storage};
+
+use super::mock::{mock_dependencies, MockApi, MockQuerier};
+use super::storage::MockStorage;
+
+/// The costs used by a [`GasMeter`] to estimate gas consumption.
+///
+/// All values are in Cosmos SDK gas. The storage costs default to the SDK's `KVGasConfig`;
+/// the crypto costs are the CosmWasm defaults converted with wasmd's gas multiplier.
+/// Wasm execution itself is not metered, so the estimates are lower bounds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GasSchedule {
+    pub read_cost_flat: u64,
+    /// Charged per byte of key and value
+    pub read_cost_per_byte: u64,
+    pub write_cost_flat: u64,
+    /// Charged per byte of key and value
+    pub write_cost_per_byte: u64,
+    pub delete_cost: u64,
+    /// Charged for every item returned by a range, in addition to the per byte read costs
+    pub iter_next_cost_flat: u64,
+    pub query_cost_flat: u64,
+    /// Charged per byte of request and response
+    pub query_cost_per_byte: u64,
+    pub secp256k1_verify_cost: u64,
+    pub secp256k1_recover_pubkey_cost: u64,
+    pub ed25519_verify_cost: u64,
+    /// Charged per signature
+    pub ed25519_batch_verify_cost: u64,
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        GasSchedule {
+            read_cost_flat: 1000,
+            read_cost_per_byte: 3,
+            write_cost_flat: 2000,
+            write_cost_per_byte: 30,
+            delete_cost: 1000,
+            iter_next_cost_flat: 30,
+            query_cost_flat: 1000,
+            query_cost_per_byte: 3,
+            secp256k1_verify_cost: 1100,
+            secp256k1_recover_pubkey_cost: 1160,
+            ed25519_verify_cost: 450,
+            ed25519_batch_verify_cost: 225,
+        }
+    }
+}
+
+/// Accumulates the estimated gas of all operations performed through the metered
+/// dependencies created by [`mock_dependencies_with_gas_meter`] or [`GasMeter::wrap`].
+///
+/// Clones share the gas counter.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::Storage;
+/// # use cosmwasm_std::testing::{mock_dependencies_with_gas_meter, GasSchedule};
+/// let (mut deps, meter) = mock_dependencies_with_gas_meter(GasSchedule::default());
+/// let ((), gas) = meter.measure(|| deps.storage.set(b"foo", b"bar"));
+/// assert_eq!(gas, 2000 + 6 * 30);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GasMeter {
+    schedule: GasSchedule,
+    used: Rc<Cell<u64>>,
+}
+
+impl GasMeter {
+    pub fn new(schedule: GasSchedule) -> Self {
+        GasMeter {
+            schedule,
+            used: Rc::new(Cell::new(0)),
+        }
+    }
+
+    pub fn schedule(&self) -> &GasSchedule {
+        &self.schedule
+    }
+
+    /// Returns the gas consumed since creation or the last reset.
+    pub fn used(&self) -> u64 {
+        self.used.get()
+    }
+
+    pub fn reset(&self) {
+        self.used.set(0);
+    }
+
+    /// Adds the given amount, e.g. to account for operations that are not metered automatically.
+    pub fn consume(&self, amount: u64) {
+        self.used.set(self.used.get().saturating_add(amount));
+    }
+
+    /// Runs `action` (e.g. a call to `execute`) and returns its result along with the gas it consumed.
+    pub fn measure<T>(&self, action: impl FnOnce() -> T) -> (T, u64) {
+        let before = self.used();
+        let result = action();
+        (result, self.used() - before)
+    }
+
+    /// Wraps the given dependencies such that they report to this meter.
+    pub fn wrap<S, A, Q, C>(
+        &self,
+        deps: OwnedDeps<S, A, Q, C>,
+    ) -> OwnedDeps<MeteredStorage<S>, MeteredApi<A>, MeteredQuerier<Q>, C>
+    where
+        S: Storage,
+        A: Api,
+        Q: Querier,
+        C: CustomQuery,
+    {
+        OwnedDeps {
+            storage: MeteredStorage {
+                inner: deps.storage,
+                meter: self.clone(),
+            },
+            api: MeteredApi {
+                inner: deps.api,
+                meter: self.clone(),
+            },
+            querier: MeteredQuerier {
+                inner: deps.querier,
+                meter: self.clone(),
+            },
+            custom_query_type: PhantomData,
+        }
+    }
+
+    fn consume_per_byte(&self, flat: u64, per_byte: u64, bytes: usize) {
+        self.consume(per_byte.saturating_mul(bytes as u64).saturating_add(flat));
+    }
+}
+
+/// Creates mock dependencies like [`mock_dependencies`] along with a [`GasMeter`] that
+/// estimates the gas used by storage access, queries and crypto operations.
+pub fn mock_dependencies_with_gas_meter(
+    schedule: GasSchedule,
+) -> (
+    OwnedDeps<MeteredStorage, MeteredApi, MeteredQuerier>,
+    GasMeter,
+) {
+    let meter = GasMeter::new(schedule);
+    (meter.wrap(mock_dependencies()), meter)
+}
+
+/// A storage reporting all operations to a [`GasMeter`]
+pub struct MeteredStorage<S: Storage = MockStorage> {
+    inner: S,
+    meter: GasMeter,
+}
+
+impl<S: Storage> MeteredStorage<S> {
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Storage> Storage for MeteredStorage<S> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.inner.get(key);
+        let schedule = self.meter.schedule;
+        self.meter.consume_per_byte(
+            schedule.read_cost_flat,
+            schedule.read_cost_per_byte,
+            key.len() + value.as_ref().map_or(0, |v| v.len()),
+        );
+        value
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let schedule = self.meter.schedule;
+        Box::new(
+            self.inner
+                .range(start, end, order)
+                .inspect(move |(key, value)| {
+                    self.meter.consume_per_byte(
+                        schedule.iter_next_cost_flat,
+                        schedule.read_cost_per_byte,
+                        key.len() + value.len(),
+                    );
+                }),
+        )
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        let schedule = self.meter.schedule;
+        self.meter.consume_per_byte(
+            schedule.write_cost_flat,
+            schedule.write_cost_per_byte,
+            key.len() + value.len(),
+        );
+        self.inner.set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.meter.consume(self.meter.schedule.delete_cost);
+        self.inner.remove(key);
+    }
+}
+
+/// An API reporting crypto operations to a [`GasMeter`]. Address conversions are free.
+pub struct MeteredApi<A: Api = MockApi> {
+    inner: A,
+    meter: GasMeter,
+}
+
+impl<A: Api> MeteredApi<A> {
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+}
+
+impl<A: Api> Api for MeteredApi<A> {
+    fn addr_validate(&self, human: &str) -> StdResult<Addr> {
+        self.inner.addr_validate(human)
+    }
+
+    fn addr_canonicalize(&self, human: &str) -> StdResult<CanonicalAddr> {
+        self.inner.addr_canonicalize(human)
+    }
+
+    fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+        self.inner.addr_humanize(canonical)
+    }
+
+    fn secp256k1_verify(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.meter
+            .consume(self.meter.schedule.secp256k1_verify_cost);
+        self.inner
+            .secp256k1_verify(message_hash, signature, public_key)
+    }
+
+    fn secp256k1_recover_pubkey(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        recovery_param: u8,
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        self.meter
+            .consume(self.meter.schedule.secp256k1_recover_pubkey_cost);
+        self.inner
+            .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+    }
+
+    fn ed25519_verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.meter.consume(self.meter.schedule.ed25519_verify_cost);
+        self.inner.ed25519_verify(message, signature, public_key)
+    }
+
+    fn ed25519_batch_verify(
+        &self,
+        messages: &[&[u8]],
+        signatures: &[&[u8]],
+        public_keys: &[&[u8]],
+    ) -> Result<bool, VerificationError> {
+        self.meter.consume(
+            self.meter
+                .schedule
+                .ed25519_batch_verify_cost
+                .saturating_mul(signatures.len() as u64),
+        );
+        self.inner
+            .ed25519_batch_verify(messages, signatures, public_keys)
+    }
+
+    fn debug(&self, message: &str) {
+        self.inner.debug(message)
+    }
+}
+
+/// A querier reporting the size of requests and responses to a [`GasMeter`]
+pub struct MeteredQuerier<Q: Querier = MockQuerier> {
+    inner: Q,
+    meter: GasMeter,
+}
+
+impl<Q: Querier> MeteredQuerier<Q> {
+    pub fn inner(&self) -> &Q {
+        &self.inner
+    }
+
+    /// Gives access to the inner querier, e.g. to update the data it returns.
+    pub fn inner_mut(&mut self) -> &mut Q {
+        &mut self.inner
+    }
+}
+
+impl<Q: Querier> Querier for MeteredQuerier<Q> {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let result = self.inner.raw_query(bin_request);
+        let response_len = match &result {
+            SystemResult::Ok(ContractResult::Ok(response)) => response.len(),
+            _ => 0,
+        };
+        let schedule = self.meter.schedule;
+        self.meter.consume_per_byte(
+            schedule.query_cost_flat,
+            schedule.query_cost_per_byte,
+            bin_request.len() + response_len,
+        );
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{coins, QuerierWrapper};
+
+    #[test]
+    fn storage_operations_are_metered() {
+        let (mut deps, meter) = mock_dependencies_with_gas_meter(GasSchedule::default());
+
+        deps.storage.set(b"foo", b"bar");
+        assert_eq!(meter.used(), 2000 + 6 * 30);
+
+        meter.reset();
+        assert_eq!(deps.storage.get(b"foo"), Some(b"bar".to_vec()));
+        assert_eq!(meter.used(), 1000 + 6 * 3);
+
+        // misses are charged for the key only
+        meter.reset();
+        assert_eq!(deps.storage.get(b"fo"), None);
+        assert_eq!(meter.used(), 1000 + 2 * 3);
+
+        meter.reset();
+        deps.storage.remove(b"foo");
+        assert_eq!(meter.used(), 1000);
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn range_items_are_metered() {
+        let (mut deps, meter) = mock_dependencies_with_gas_meter(GasSchedule::default());
+        deps.storage.set(b"a", b"1");
+        deps.storage.set(b"b", b"22");
+
+        meter.reset();
+        let count = deps.storage.range(None, None, Order::Ascending).count();
+        assert_eq!(count, 2);
+        assert_eq!(meter.used(), (30 + 2 * 3) + (30 + 3 * 3));
+    }
+
+    #[test]
+    fn queries_and_crypto_are_metered() {
+        let (deps, meter) = mock_dependencies_with_gas_meter(GasSchedule {
+            query_cost_flat: 100,
+            query_cost_per_byte: 1,
+            ..GasSchedule::default()
+        });
+
+        let ((), gas) = meter.measure(|| {
+            QuerierWrapper::<crate::Empty>::new(&deps.querier)
+                .query_all_balances("alice")
+                .unwrap();
+        });
+        let request = br#"{"bank":{"all_balances":{"address":"alice"}}}"#;
+        let response = br#"{"amount":[]}"#;
+        assert_eq!(gas, 100 + (request.len() + response.len()) as u64);
+
+        meter.reset();
+        let _ = deps.api.ed25519_verify(b"msg", &[0; 64], &[0; 32]);
+        let _ = deps
+            .api
+            .ed25519_batch_verify(&[b"msg"], &[&[0; 64], &[0; 64]], &[&[0; 32]]);
+        assert_eq!(meter.used(), 450 + 2 * 225);
+
+        // address conversions are free
+        meter.reset();
+        deps.api.addr_validate("alice").unwrap();
+        assert_eq!(meter.used(), 0);
+    }
+
+    #[test]
+    fn wrap_works_for_custom_dependencies() {
+        let meter = GasMeter::new(GasSchedule::default());
+        let mut deps = meter.wrap(OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: MockQuerier::<crate::Empty>::new(&[("alice", &coins(5, "ucosm"))]),
+            custom_query_type: PhantomData::<crate::Empty>,
+        });
+        let clone = meter.clone();
+        deps.as_mut().storage.set(b"k", b"v");
+        assert_eq!(clone.used(), 2000 + 2 * 30);
+        assert_eq!(deps.storage.inner().get(b"k"), Some(b"v".to_vec()));
+    }
+}
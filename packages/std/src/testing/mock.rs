@@ -4,6 +4,8 @@ use serde::Serialize;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 
+use super::bech32;
+use super::storage::MockStorage;
 use crate::addresses::{Addr, CanonicalAddr};
 use crate::binary::Binary;
 use crate::coin::Coin;
@@ -26,11 +28,12 @@ use crate::query::{
     AllDelegationsResponse, AllValidatorsResponse, BondedDenomResponse, DelegationResponse,
     FullDelegation, StakingQuery, Validator, ValidatorResponse,
 };
+#[cfg(feature = "stargate")]
+use crate::query::{ChannelResponse, IbcQuery, ListChannelsResponse, PortIdResponse};
 use crate::results::{ContractResult, Empty, SystemResult};
 use crate::serde::{from_slice, to_binary};
-use crate::storage::MemoryStorage;
 use crate::timestamp::Timestamp;
-use crate::traits::{Api, Querier, QuerierResult};
+use crate::traits::{Api, Querier, QuerierResult, Storage};
 use crate::types::{BlockInfo, ContractInfo, Env, MessageInfo, TransactionInfo};
 use crate::Attribute;
 
@@ -71,17 +74,118 @@ pub fn mock_dependencies_with_balances(
     }
 }
 
-// Use MemoryStorage implementation (which is valid in non-testcode)
-// We can later make simplifications here if needed
-pub type MockStorage = MemoryStorage;
+/// Creates a builder for all external requirements that can be injected for unit tests.
+///
+/// This is an alternative to the `mock_dependencies*` functions when you need
+/// to combine multiple settings in one place.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{coins, Storage};
+/// # use cosmwasm_std::testing::{mock_dependencies_builder, MockApi, MOCK_CONTRACT_ADDR};
+/// let deps = mock_dependencies_builder()
+///     .with_api(MockApi::new_bech32("juno"))
+///     .with_balance(MOCK_CONTRACT_ADDR, &coins(123, "ujuno"))
+///     .with_storage_entry(b"config", b"{}")
+///     .build();
+/// assert_eq!(deps.storage.get(b"config"), Some(b"{}".to_vec()));
+/// ```
+pub fn mock_dependencies_builder() -> MockDependenciesBuilder {
+    MockDependenciesBuilder::default()
+}
+
+/// A builder for [`OwnedDeps`] with mock implementations. See [`mock_dependencies_builder`].
+pub struct MockDependenciesBuilder<C: DeserializeOwned = Empty> {
+    api: MockApi,
+    storage: MockStorage,
+    balances: Vec<(String, Vec<Coin>)>,
+    custom_handler: Option<CustomHandler<C>>,
+}
+
+impl Default for MockDependenciesBuilder {
+    fn default() -> Self {
+        MockDependenciesBuilder {
+            api: MockApi::default(),
+            storage: MockStorage::default(),
+            balances: vec![],
+            custom_handler: None,
+        }
+    }
+}
+
+impl<C: CustomQuery + DeserializeOwned> MockDependenciesBuilder<C> {
+    /// Sets the API implementation. Use [`MockApi::new_bech32`] for chain-like
+    /// bech32 addresses, which have 20 or 32 byte canonical addresses just like on chain.
+    pub fn with_api(mut self, api: MockApi) -> Self {
+        self.api = api;
+        self
+    }
+
+    /// Sets the length of canonical addresses created by the API.
+    /// See [`MockApi::with_canonical_length`].
+    pub fn with_canonical_length(mut self, length: usize) -> Self {
+        self.api = self.api.with_canonical_length(length);
+        self
+    }
+
+    /// Sets the initial balance of the given address. Calling this again
+    /// for the same address replaces the balance.
+    pub fn with_balance(mut self, addr: impl Into<String>, balance: &[Coin]) -> Self {
+        let addr = addr.into();
+        self.balances.retain(|(existing, _)| *existing != addr);
+        self.balances.push((addr, balance.to_vec()));
+        self
+    }
+
+    /// Writes the given key/value pair to the storage before it is handed out.
+    pub fn with_storage_entry(mut self, key: &[u8], value: &[u8]) -> Self {
+        self.storage.set(key, value);
+        self
+    }
+
+    /// Sets the handler for custom queries. This also determines the custom query type
+    /// of the resulting dependencies.
+    pub fn with_custom_handler<D, CH>(self, handler: CH) -> MockDependenciesBuilder<D>
+    where
+        D: CustomQuery + DeserializeOwned,
+        CH: Fn(&D) -> MockQuerierCustomHandlerResult + 'static,
+    {
+        MockDependenciesBuilder {
+            api: self.api,
+            storage: self.storage,
+            balances: self.balances,
+            custom_handler: Some(Box::new(handler)),
+        }
+    }
+
+    pub fn build(self) -> OwnedDeps<MockStorage, MockApi, MockQuerier<C>, C> {
+        let balances: Vec<(&str, &[Coin])> = self
+            .balances
+            .iter()
+            .map(|(addr, balance)| (addr.as_str(), balance.as_slice()))
+            .collect();
+        let mut querier = MockQuerier::new(&balances);
+        if let Some(custom_handler) = self.custom_handler {
+            querier.custom_handler = custom_handler;
+        }
+        OwnedDeps {
+            storage: self.storage,
+            api: self.api,
+            querier,
+            custom_query_type: PhantomData,
+        }
+    }
+}
 
 /// Length of canonical addresses created with this API. Contracts should not make any assumtions
 /// what this value is.
-/// The value here must be restorable with `SHUFFLES_ENCODE` + `SHUFFLES_DECODE` in-shuffles.
 const CANONICAL_LENGTH: usize = 54;
 
 const SHUFFLES_ENCODE: usize = 18;
-const SHUFFLES_DECODE: usize = 2;
+
+/// The maximum length of the address bytes in bech32 mode, as enforced by the Cosmos SDK.
+const BECH32_MAX_ADDRESS_LENGTH: usize = 255;
 
 // MockPrecompiles zero pads all human addresses to make them fit the canonical_length
 // it trims off zeros for the reverse operation.
@@ -91,13 +195,90 @@ pub struct MockApi {
     /// Length of canonical addresses created with this API. Contracts should not make any assumtions
     /// what this value is.
     canonical_length: usize,
+    /// When set, addresses are encoded as bech32 strings with this prefix.
+    bech32_prefix: Option<&'static str>,
 }
 
 impl Default for MockApi {
     fn default() -> Self {
         MockApi {
             canonical_length: CANONICAL_LENGTH,
+            bech32_prefix: None,
+        }
+    }
+}
+
+impl MockApi {
+    /// Creates an API that handles real bech32 addresses with the given prefix
+    /// (e.g. "juno"), just like the chain does. Canonical addresses are the decoded
+    /// address bytes and human addresses must have a valid checksum.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::Api;
+    /// # use cosmwasm_std::testing::MockApi;
+    /// let api = MockApi::new_bech32("juno");
+    /// let addr = api.addr_humanize(&vec![0xAA; 20].into()).unwrap();
+    /// assert_eq!(addr.as_str(), "juno142424242424242424242424242424242tvc0uq");
+    /// assert!(api.addr_validate("cosmos142424242424242424242424242424242a7m5mu").is_err());
+    /// ```
+    pub fn new_bech32(prefix: &'static str) -> Self {
+        MockApi {
+            bech32_prefix: Some(prefix),
+            ..MockApi::default()
+        }
+    }
+
+    /// Sets the length of canonical addresses, which is also the maximum length of
+    /// human addresses. This does not apply to APIs created with [`MockApi::new_bech32`].
+    ///
+    /// Panics if the length is odd or smaller than 4, since the encoding is built from
+    /// in-shuffles of the padded address.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_std::Api;
+    /// # use cosmwasm_std::testing::MockApi;
+    /// let api = MockApi::default().with_canonical_length(20);
+    /// let canonical = api.addr_canonicalize("creator").unwrap();
+    /// assert_eq!(canonical.len(), 20);
+    /// assert_eq!(api.addr_humanize(&canonical).unwrap().as_str(), "creator");
+    /// ```
+    pub fn with_canonical_length(mut self, length: usize) -> Self {
+        assert!(
+            length >= 4 && length % 2 == 0,
+            "Canonical length must be even and at least 4"
+        );
+        self.canonical_length = length;
+        self
+    }
+
+    fn bech32_canonicalize(&self, prefix: &str, input: &str) -> StdResult<CanonicalAddr> {
+        let (hrp, data) = bech32::decode(input)
+            .map_err(|e| StdError::generic_err(format!("Invalid input: {}", e)))?;
+        if hrp != prefix {
+            return Err(StdError::generic_err("Invalid input: wrong address prefix"));
         }
+        if data.is_empty() || data.len() > BECH32_MAX_ADDRESS_LENGTH {
+            return Err(StdError::generic_err(
+                "Invalid input: invalid address length",
+            ));
+        }
+        Ok(data.into())
+    }
+
+    fn bech32_humanize(&self, prefix: &str, canonical: &CanonicalAddr) -> StdResult<Addr> {
+        if canonical.is_empty() || canonical.len() > BECH32_MAX_ADDRESS_LENGTH {
+            return Err(StdError::generic_err(
+                "Invalid input: canonical address length not correct",
+            ));
+        }
+        Ok(Addr::unchecked(bech32::encode(
+            prefix,
+            canonical.as_slice(),
+        )))
     }
 }
 
@@ -115,6 +296,10 @@ impl Api for MockApi {
     }
 
     fn addr_canonicalize(&self, input: &str) -> StdResult<CanonicalAddr> {
+        if let Some(prefix) = self.bech32_prefix {
+            return self.bech32_canonicalize(prefix, input);
+        }
+
         // Dummy input validation. This is more sophisticated for formats like bech32, where format and checksum are validated.
         if input.len() < 3 {
             return Err(StdError::generic_err(
@@ -145,6 +330,10 @@ impl Api for MockApi {
     }
 
     fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+        if let Some(prefix) = self.bech32_prefix {
+            return self.bech32_humanize(prefix, canonical);
+        }
+
         if canonical.len() != self.canonical_length {
             return Err(StdError::generic_err(
                 "Invalid input: canonical address length not correct",
@@ -152,8 +341,9 @@ impl Api for MockApi {
         }
 
         let mut tmp: Vec<u8> = canonical.clone().into();
-        // Shuffle two more times which restored the original value (24 elements are back to original after 20 rounds)
-        for _ in 0..SHUFFLES_DECODE {
+        // Shuffle until the original value is restored (e.g. 54 elements are back to original after 20 rounds)
+        let period = riffle_shuffle_period(self.canonical_length);
+        for _ in 0..(period - SHUFFLES_ENCODE % period) % period {
             tmp = riffle_shuffle(&tmp);
         }
         // Rotate back
@@ -221,7 +411,7 @@ impl Api for MockApi {
 
 /// Returns a default enviroment with height, time, chain_id, and contract address
 /// You can submit as is to most contracts, or modify height/time if you want to
-/// test for expiration.
+/// test for expiration, e.g. using `mock_env().with_height(20_000)` from [`MockEnvExt`].
 ///
 /// This is intended for use in test code only.
 pub fn mock_env() -> Env {
@@ -238,6 +428,57 @@ pub fn mock_env() -> Env {
     }
 }
 
+/// Builder style setters to customize the environment returned by [`mock_env`].
+///
+/// This is intended for use in test code only.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::Timestamp;
+/// use cosmwasm_std::testing::{mock_env, MockEnvExt};
+///
+/// let env = mock_env()
+///     .with_height(20_000)
+///     .with_time(Timestamp::from_seconds(1_700_000_000));
+/// assert_eq!(env.block.height, 20_000);
+/// ```
+pub trait MockEnvExt {
+    /// Sets the block height
+    fn with_height(self, height: u64) -> Self;
+
+    /// Sets the block time
+    fn with_time(self, time: Timestamp) -> Self;
+
+    /// Sets the chain ID
+    fn with_chain_id(self, chain_id: impl Into<String>) -> Self;
+
+    /// Sets the address of the contract being executed
+    fn with_contract_address(self, address: impl Into<String>) -> Self;
+}
+
+impl MockEnvExt for Env {
+    fn with_height(mut self, height: u64) -> Self {
+        self.block.height = height;
+        self
+    }
+
+    fn with_time(mut self, time: Timestamp) -> Self {
+        self.block.time = time;
+        self
+    }
+
+    fn with_chain_id(mut self, chain_id: impl Into<String>) -> Self {
+        self.block.chain_id = chain_id.into();
+        self
+    }
+
+    fn with_contract_address(mut self, address: impl Into<String>) -> Self {
+        self.contract.address = Addr::unchecked(address);
+        self
+    }
+}
+
 /// Just set sender and funds for the message.
 /// This is intended for use in test code only.
 pub fn mock_info(sender: &str, funds: &[Coin]) -> MessageInfo {
@@ -247,6 +488,69 @@ pub fn mock_info(sender: &str, funds: &[Coin]) -> MessageInfo {
     }
 }
 
+/// Creates a message info for the given sender without funds. Funds can be added with
+/// the builder style setters of [`MockInfoExt`], e.g.
+/// `mock_info_with("creator").with_funds(coins(1000, "earth"))`.
+///
+/// The sender can be given as `&str`, `String` or `Addr`.
+///
+/// This is intended for use in test code only.
+pub fn mock_info_with(sender: impl Into<String>) -> MessageInfo {
+    MessageInfo {
+        sender: Addr::unchecked(sender),
+        funds: vec![],
+    }
+}
+
+/// Creates a `Vec<Coin>` from pairs of amount and denom, which are passed in the same
+/// order as to [`coins`](crate::coins).
+///
+/// Amounts must be `u128` compatible.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{coins, funds, Coin};
+/// let amount = 5;
+/// assert_eq!(funds![(1000, "earth")], coins(1000, "earth"));
+/// assert_eq!(
+///     funds![(1000, "earth"), (amount, "atom")],
+///     vec![Coin::new(1000, "earth"), Coin::new(5, "atom")],
+/// );
+/// assert_eq!(funds![], Vec::<Coin>::new());
+/// ```
+#[macro_export]
+macro_rules! funds {
+    ($(($amount:expr, $denom:expr)),* $(,)?) => {{
+        let funds: ::std::vec::Vec<$crate::Coin> = vec![$($crate::Coin::new($amount, $denom)),*];
+        funds
+    }};
+}
+
+/// Builder style setters to customize the message info returned by [`mock_info_with`].
+///
+/// This is intended for use in test code only.
+pub trait MockInfoExt {
+    /// Sets the funds sent along with the message, e.g. created with [`coins`](crate::coins)
+    /// or [`funds!`](crate::funds).
+    fn with_funds(self, funds: Vec<Coin>) -> Self;
+
+    /// Removes all funds, making explicit that none are sent.
+    fn without_funds(self) -> Self;
+}
+
+impl MockInfoExt for MessageInfo {
+    fn with_funds(mut self, funds: Vec<Coin>) -> Self {
+        self.funds = funds;
+        self
+    }
+
+    fn without_funds(mut self) -> Self {
+        self.funds.clear();
+        self
+    }
+}
+
 /// Creates an IbcChannel for testing. You set a few key parameters for handshaking,
 /// If you want to set more, use this as a default and mutate other fields
 #[cfg(feature = "stargate")]
@@ -425,20 +729,26 @@ pub struct MockQuerier<C: DeserializeOwned = Empty> {
     bank: BankQuerier,
     #[cfg(feature = "staking")]
     staking: StakingQuerier,
+    #[cfg(feature = "stargate")]
+    ibc: IbcQuerier,
     wasm: WasmQuerier,
     /// A handler to handle custom queries. This is set to a dummy handler that
     /// always errors by default. Update it via `with_custom_handler`.
     ///
     /// Use box to avoid the need of another generic type
-    custom_handler: Box<dyn for<'a> Fn(&'a C) -> MockQuerierCustomHandlerResult>,
+    custom_handler: CustomHandler<C>,
 }
 
+type CustomHandler<C> = Box<dyn for<'a> Fn(&'a C) -> MockQuerierCustomHandlerResult>;
+
 impl<C: DeserializeOwned> MockQuerier<C> {
     pub fn new(balances: &[(&str, &[Coin])]) -> Self {
         MockQuerier {
             bank: BankQuerier::new(balances),
             #[cfg(feature = "staking")]
             staking: StakingQuerier::default(),
+            #[cfg(feature = "stargate")]
+            ibc: IbcQuerier::default(),
             wasm: WasmQuerier::default(),
             // strange argument notation suggested as a workaround here: https://github.com/rust-lang/rust/issues/41078#issuecomment-294296365
             custom_handler: Box::from(|_: &_| -> MockQuerierCustomHandlerResult {
@@ -468,6 +778,11 @@ impl<C: DeserializeOwned> MockQuerier<C> {
         self.staking = StakingQuerier::new(denom, validators, delegations);
     }
 
+    #[cfg(feature = "stargate")]
+    pub fn update_ibc(&mut self, port_id: &str, channels: &[IbcChannel]) {
+        self.ibc = IbcQuerier::new(port_id, channels);
+    }
+
     pub fn update_wasm<WH: 'static>(&mut self, handler: WH)
     where
         WH: Fn(&WasmQuery) -> QuerierResult,
@@ -475,6 +790,33 @@ impl<C: DeserializeOwned> MockQuerier<C> {
         self.wasm.update_handler(handler)
     }
 
+    /// Registers a handler for Wasm queries to the given contract address.
+    ///
+    /// Queries to registered contracts are answered by their handler, all other
+    /// queries fall back to the handler set via `update_wasm`.
+    pub fn update_wasm_contract<WH: 'static>(
+        &mut self,
+        contract_addr: impl Into<String>,
+        handler: WH,
+    ) where
+        WH: Fn(&WasmQuery) -> QuerierResult,
+    {
+        self.wasm.update_contract_handler(contract_addr, handler)
+    }
+
+    /// Builder variant of [`MockQuerier::update_wasm_contract`].
+    pub fn with_wasm_handler<WH: 'static>(
+        mut self,
+        contract_addr: impl Into<String>,
+        handler: WH,
+    ) -> Self
+    where
+        WH: Fn(&WasmQuery) -> QuerierResult,
+    {
+        self.update_wasm_contract(contract_addr, handler);
+        self
+    }
+
     pub fn with_custom_handler<CH: 'static>(mut self, handler: CH) -> Self
     where
         CH: Fn(&C) -> MockQuerierCustomHandlerResult,
@@ -518,24 +860,29 @@ impl<C: CustomQuery + DeserializeOwned> MockQuerier<C> {
                 kind: "Stargate".to_string(),
             }),
             #[cfg(feature = "stargate")]
-            QueryRequest::Ibc(_) => SystemResult::Err(SystemError::UnsupportedRequest {
-                kind: "Ibc".to_string(),
-            }),
+            QueryRequest::Ibc(ibc_query) => self.ibc.query(ibc_query),
         }
     }
 }
 
+type WasmHandler = Box<dyn for<'a> Fn(&'a WasmQuery) -> QuerierResult>;
+
 struct WasmQuerier {
     /// A handler to handle Wasm queries. This is set to a dummy handler that
     /// always errors by default. Update it via `with_custom_handler`.
     ///
     /// Use box to avoid the need of generic type.
-    handler: Box<dyn for<'a> Fn(&'a WasmQuery) -> QuerierResult>,
+    handler: WasmHandler,
+    /// Handlers for queries to specific contract addresses. Those take precedence over `handler`.
+    contract_handlers: HashMap<String, WasmHandler>,
 }
 
 impl WasmQuerier {
-    fn new(handler: Box<dyn for<'a> Fn(&'a WasmQuery) -> QuerierResult>) -> Self {
-        Self { handler }
+    fn new(handler: WasmHandler) -> Self {
+        Self {
+            handler,
+            contract_handlers: HashMap::new(),
+        }
     }
 
     fn update_handler<WH: 'static>(&mut self, handler: WH)
@@ -545,20 +892,39 @@ impl WasmQuerier {
         self.handler = Box::from(handler)
     }
 
+    fn update_contract_handler<WH: 'static>(
+        &mut self,
+        contract_addr: impl Into<String>,
+        handler: WH,
+    ) where
+        WH: Fn(&WasmQuery) -> QuerierResult,
+    {
+        self.contract_handlers
+            .insert(contract_addr.into(), Box::from(handler));
+    }
+
     fn query(&self, request: &WasmQuery) -> QuerierResult {
-        (*self.handler)(request)
+        match self.contract_handlers.get(contract_addr(request)) {
+            Some(handler) => (*handler)(request),
+            None => (*self.handler)(request),
+        }
+    }
+}
+
+fn contract_addr(request: &WasmQuery) -> &String {
+    match request {
+        WasmQuery::Smart { contract_addr, .. } => contract_addr,
+        WasmQuery::Raw { contract_addr, .. } => contract_addr,
+        WasmQuery::ContractInfo { contract_addr, .. } => contract_addr,
+        #[cfg(feature = "raw_range")]
+        WasmQuery::RawRange { contract_addr, .. } => contract_addr,
     }
 }
 
 impl Default for WasmQuerier {
     fn default() -> Self {
         let handler = Box::from(|request: &WasmQuery| -> QuerierResult {
-            let addr = match request {
-                WasmQuery::Smart { contract_addr, .. } => contract_addr,
-                WasmQuery::Raw { contract_addr, .. } => contract_addr,
-                WasmQuery::ContractInfo { contract_addr, .. } => contract_addr,
-            }
-            .clone();
+            let addr = contract_addr(request).clone();
             SystemResult::Err(SystemError::NoSuchContract { addr })
         });
         Self::new(handler)
@@ -728,6 +1094,73 @@ impl StakingQuerier {
     }
 }
 
+#[cfg(feature = "stargate")]
+#[derive(Clone)]
+pub struct IbcQuerier {
+    port_id: String,
+    channels: Vec<IbcChannel>,
+}
+
+#[cfg(feature = "stargate")]
+impl IbcQuerier {
+    /// Creates a querier for a contract bound to `port_id` with the given channels.
+    /// Channels can be bound to any port, not only the contract's one.
+    pub fn new(port_id: &str, channels: &[IbcChannel]) -> Self {
+        IbcQuerier {
+            port_id: port_id.to_string(),
+            channels: channels.to_vec(),
+        }
+    }
+
+    pub fn query(&self, request: &IbcQuery) -> QuerierResult {
+        let contract_result: ContractResult<Binary> = match request {
+            IbcQuery::PortId {} => {
+                let res = PortIdResponse {
+                    port_id: self.port_id.clone(),
+                };
+                to_binary(&res).into()
+            }
+            IbcQuery::ListChannels { port_id } => {
+                let port_id = port_id.as_ref().unwrap_or(&self.port_id);
+                let channels = self
+                    .channels
+                    .iter()
+                    .filter(|channel| channel.endpoint.port_id == *port_id)
+                    .cloned()
+                    .collect();
+                let res = ListChannelsResponse { channels };
+                to_binary(&res).into()
+            }
+            IbcQuery::Channel {
+                channel_id,
+                port_id,
+            } => {
+                let port_id = port_id.as_ref().unwrap_or(&self.port_id);
+                let channel = self
+                    .channels
+                    .iter()
+                    .find(|channel| {
+                        channel.endpoint.channel_id == *channel_id
+                            && channel.endpoint.port_id == *port_id
+                    })
+                    .cloned();
+                let res = ChannelResponse { channel };
+                to_binary(&res).into()
+            }
+        };
+        // system result is always ok in the mock implementation
+        SystemResult::Ok(contract_result)
+    }
+}
+
+#[cfg(feature = "stargate")]
+impl Default for IbcQuerier {
+    /// A contract bound to the port used by `mock_ibc_channel` without any channels
+    fn default() -> Self {
+        IbcQuerier::new("my_port", &[])
+    }
+}
+
 /// Performs a perfect shuffle (in shuffle)
 ///
 /// https://en.wikipedia.org/wiki/Riffle_shuffle_permutation#Perfect_shuffles
@@ -790,6 +1223,20 @@ pub fn riffle_shuffle<T: Clone>(input: &[T]) -> Vec<T> {
     out
 }
 
+/// Returns the number of in-shuffles after which a slice of the given even length is
+/// back in its original order. This is the multiplicative order of 2 modulo `length + 1`.
+/// See https://en.wikipedia.org/wiki/In_shuffle#Mathematics and https://oeis.org/A002326
+fn riffle_shuffle_period(length: usize) -> usize {
+    let modulus = length + 1;
+    let mut power = 2 % modulus;
+    let mut period = 1;
+    while power != 1 {
+        power = power * 2 % modulus;
+        period += 1;
+    }
+    period
+}
+
 pub fn digit_sum(input: &[u8]) -> usize {
     input.iter().fold(0, |sum, val| sum + (*val as usize))
 }
@@ -810,7 +1257,7 @@ mod tests {
     #[cfg(feature = "staking")]
     use crate::{Decimal, Delegation};
     use hex_literal::hex;
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
 
     const SECP256K1_MSG_HASH_HEX: &str =
         "5ae8317d34d1e595e3fa7247db80c0af4320cce1116de187f8f7e2e099c0d8d0";
@@ -822,6 +1269,34 @@ mod tests {
     const ED25519_PUBKEY_HEX: &str =
         "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c";
 
+    #[test]
+    fn mock_env_setters_work() {
+        let env = mock_env()
+            .with_height(42)
+            .with_time(Timestamp::from_seconds(1_000))
+            .with_chain_id("juno-1")
+            .with_contract_address("contract2");
+        assert_eq!(env.block.height, 42);
+        assert_eq!(env.block.time, Timestamp::from_seconds(1_000));
+        assert_eq!(env.block.chain_id, "juno-1");
+        assert_eq!(env.contract.address, Addr::unchecked("contract2"));
+        assert_eq!(env.transaction, mock_env().transaction);
+    }
+
+    #[test]
+    fn mock_info_with_works() {
+        let info = mock_info_with("creator").with_funds(coins(1000, "earth"));
+        assert_eq!(info, mock_info("creator", &coins(1000, "earth")));
+
+        let info = mock_info_with(Addr::unchecked("creator"))
+            .with_funds(crate::funds![(1000, "earth"), (5, "atom")])
+            .without_funds();
+        assert_eq!(info, mock_info("creator", &[]));
+
+        let info = mock_info_with(String::from("creator"));
+        assert_eq!(info.funds, vec![]);
+    }
+
     #[test]
     fn mock_info_works() {
         let info = mock_info("my name", &coins(100, "atom"));
@@ -837,6 +1312,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mock_dependencies_builder_works() {
+        let deps = mock_dependencies_builder()
+            .with_api(MockApi::new_bech32("juno"))
+            .with_balance("alice", &coins(5, "ujuno"))
+            .with_balance("alice", &coins(7, "ujuno"))
+            .with_balance("bob", &coins(3, "uatom"))
+            .with_storage_entry(b"foo", b"bar")
+            .build();
+
+        assert_eq!(deps.storage.get(b"foo"), Some(b"bar".to_vec()));
+        deps.api
+            .addr_validate("juno142424242424242424242424242424242tvc0uq")
+            .unwrap();
+        let alice = deps.as_ref().querier.query_all_balances("alice").unwrap();
+        assert_eq!(alice, coins(7, "ujuno"));
+        let bob = deps.as_ref().querier.query_all_balances("bob").unwrap();
+        assert_eq!(bob, coins(3, "uatom"));
+    }
+
+    #[test]
+    fn mock_dependencies_builder_with_canonical_length_works() {
+        let deps = mock_dependencies_builder()
+            .with_canonical_length(32)
+            .build();
+        let canonical = deps.api.addr_canonicalize("creator").unwrap();
+        assert_eq!(canonical.len(), 32);
+        assert_eq!(deps.api.addr_humanize(&canonical).unwrap(), "creator");
+
+        // chain-like bech32 addresses
+        let deps = mock_dependencies_builder()
+            .with_api(MockApi::new_bech32("juno"))
+            .build();
+        for length in [20, 32] {
+            let addr = deps.api.addr_humanize(&vec![0xAA; length].into()).unwrap();
+            let canonical = deps.api.addr_canonicalize(addr.as_str()).unwrap();
+            assert_eq!(canonical.len(), length);
+        }
+    }
+
+    #[test]
+    fn mock_dependencies_builder_with_custom_handler_works() {
+        #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+        struct MyQuery {
+            ping: String,
+        }
+        impl CustomQuery for MyQuery {}
+
+        let deps = mock_dependencies_builder()
+            .with_custom_handler(|query: &MyQuery| {
+                SystemResult::Ok(to_binary(&format!("pong {}", query.ping)).into())
+            })
+            .build();
+        let res: String = deps
+            .as_ref()
+            .querier
+            .query(&QueryRequest::Custom(MyQuery {
+                ping: "foo".to_string(),
+            }))
+            .unwrap();
+        assert_eq!(res, "pong foo");
+    }
+
     #[test]
     fn addr_validate_works() {
         let api = MockApi::default();
@@ -906,6 +1444,47 @@ mod tests {
         api.addr_humanize(&input).unwrap();
     }
 
+    #[test]
+    fn bech32_addr_canonicalize_and_humanize_work() {
+        let api = MockApi::new_bech32("cosmwasm");
+        let human = "cosmwasm1qqqsyqcyq5rqwzqfpg9scrgwpugpzysnzs23v9ccrydpk8qarc0s5c2um2";
+        let canonical = api.addr_canonicalize(human).unwrap();
+        assert_eq!(canonical.as_slice(), (0u8..32).collect::<Vec<_>>());
+        assert_eq!(api.addr_humanize(&canonical).unwrap(), human);
+
+        // upper case is a valid bech32 encoding of the same address
+        let canonical = api.addr_canonicalize(&human.to_uppercase()).unwrap();
+        assert_eq!(api.addr_humanize(&canonical).unwrap(), human);
+    }
+
+    #[test]
+    fn bech32_addr_validate_works() {
+        let api = MockApi::new_bech32("juno");
+        let human = "juno142424242424242424242424242424242tvc0uq";
+        assert_eq!(api.addr_validate(human).unwrap(), human);
+
+        // not normalized
+        api.addr_validate(&human.to_uppercase()).unwrap_err();
+        // bad checksum
+        api.addr_validate("juno142424242424242424242424242424242tvc0ur")
+            .unwrap_err();
+        // wrong prefix
+        api.addr_validate("cosmos142424242424242424242424242424242a7m5mu")
+            .unwrap_err();
+        // not bech32 at all
+        api.addr_validate("foobar123").unwrap_err();
+    }
+
+    #[test]
+    fn bech32_addr_humanize_checks_length() {
+        let api = MockApi::new_bech32("juno");
+        api.addr_humanize(&CanonicalAddr::from(vec![])).unwrap_err();
+        api.addr_humanize(&CanonicalAddr::from(vec![1; 256]))
+            .unwrap_err();
+        api.addr_humanize(&CanonicalAddr::from(vec![1; 32]))
+            .unwrap();
+    }
+
     // Basic "works" test. Exhaustive tests on VM's side (packages/vm/src/imports.rs)
     #[test]
     fn secp256k1_verify_works() {
@@ -1511,6 +2090,12 @@ mod tests {
                         })
                     }
                 }
+                #[cfg(feature = "raw_range")]
+                WasmQuery::RawRange { contract_addr, .. } => {
+                    SystemResult::Err(SystemError::NoSuchContract {
+                        addr: contract_addr.clone(),
+                    })
+                }
             }
         });
 
@@ -1569,6 +2154,131 @@ mod tests {
         }
     }
 
+    #[test]
+    fn wasm_querier_contract_handlers_work() {
+        let querier: MockQuerier =
+            MockQuerier::new(&[]).with_wasm_handler("contract1", |request| match request {
+                WasmQuery::Smart { msg, .. } => SystemResult::Ok(ContractResult::Ok(msg.clone())),
+                _ => SystemResult::Ok(ContractResult::Ok(b"raw".into())),
+            });
+
+        // registered contract
+        let result = querier.handle_query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: "contract1".into(),
+            msg: b"{}".into(),
+        }));
+        match result {
+            SystemResult::Ok(ContractResult::Ok(value)) => assert_eq!(value, b"{}" as &[u8]),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let result = querier.handle_query(&QueryRequest::Wasm(WasmQuery::Raw {
+            contract_addr: "contract1".into(),
+            key: b"the key".into(),
+        }));
+        match result {
+            SystemResult::Ok(ContractResult::Ok(value)) => assert_eq!(value, b"raw" as &[u8]),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // other contracts use the general handler
+        let result = querier.handle_query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: "contract2".into(),
+            msg: b"{}".into(),
+        }));
+        match result {
+            SystemResult::Err(SystemError::NoSuchContract { addr }) => {
+                assert_eq!(addr, "contract2")
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[cfg(feature = "stargate")]
+    #[test]
+    fn ibc_querier_works() {
+        let chan1 = mock_ibc_channel("channel-0", IbcOrder::Ordered, "ibc");
+        let chan2 = mock_ibc_channel("channel-1", IbcOrder::Ordered, "ibc");
+        let mut chan3 = mock_ibc_channel("channel-2", IbcOrder::Unordered, "ibc");
+        chan3.endpoint.port_id = "other_port".to_string();
+        let ibc = IbcQuerier::new("my_port", &[chan1.clone(), chan2.clone(), chan3.clone()]);
+
+        let raw = ibc.query(&IbcQuery::PortId {}).unwrap().unwrap();
+        let res: PortIdResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.port_id, "my_port");
+
+        let raw = ibc
+            .query(&IbcQuery::ListChannels { port_id: None })
+            .unwrap()
+            .unwrap();
+        let res: ListChannelsResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.channels, vec![chan1, chan2.clone()]);
+        let raw = ibc
+            .query(&IbcQuery::ListChannels {
+                port_id: Some("other_port".to_string()),
+            })
+            .unwrap()
+            .unwrap();
+        let res: ListChannelsResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.channels, vec![chan3.clone()]);
+
+        let raw = ibc
+            .query(&IbcQuery::Channel {
+                channel_id: "channel-1".to_string(),
+                port_id: None,
+            })
+            .unwrap()
+            .unwrap();
+        let res: ChannelResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.channel, Some(chan2));
+        let raw = ibc
+            .query(&IbcQuery::Channel {
+                channel_id: "channel-2".to_string(),
+                port_id: Some("other_port".to_string()),
+            })
+            .unwrap()
+            .unwrap();
+        let res: ChannelResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.channel, Some(chan3));
+        let raw = ibc
+            .query(&IbcQuery::Channel {
+                channel_id: "channel-2".to_string(),
+                port_id: None,
+            })
+            .unwrap()
+            .unwrap();
+        let res: ChannelResponse = from_binary(&raw).unwrap();
+        assert_eq!(res.channel, None);
+    }
+
+    #[test]
+    fn with_canonical_length_works() {
+        for length in [4, 20, 32, 40, 54, 64, 100] {
+            let api = MockApi::default().with_canonical_length(length);
+            let canonical = api.addr_canonicalize("foo").unwrap();
+            assert_eq!(canonical.len(), length);
+            assert_eq!(api.addr_humanize(&canonical).unwrap(), "foo");
+            api.addr_validate("foo").unwrap();
+        }
+
+        let api = MockApi::default().with_canonical_length(20);
+        let err = api.addr_canonicalize(&"a".repeat(21)).unwrap_err();
+        assert!(err.to_string().contains("human address too long"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Canonical length must be even and at least 4")]
+    fn with_canonical_length_panics_for_odd_length() {
+        let _ = MockApi::default().with_canonical_length(21);
+    }
+
+    #[test]
+    fn riffle_shuffle_period_works() {
+        assert_eq!(riffle_shuffle_period(6), 3);
+        assert_eq!(riffle_shuffle_period(14), 4);
+        assert_eq!(riffle_shuffle_period(24), 20);
+        assert_eq!(riffle_shuffle_period(CANONICAL_LENGTH), 20);
+    }
+
     #[test]
     fn riffle_shuffle_works() {
         // Example from https://en.wikipedia.org/wiki/In_shuffle
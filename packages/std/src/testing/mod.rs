@@ -3,17 +3,37 @@
 // Exposed for testing only
 // Both unit tests and integration tests are compiled to native code, so everything in here does not need to compile to Wasm.
 
+mod app;
 mod assertions;
+mod bech32;
+mod gas;
 mod mock;
+mod recording;
+mod storage;
 
-pub use assertions::assert_approx_eq_impl;
+pub use app::{App, AppResponse, Contract, ContractWrapper};
+pub use assertions::{assert_approx_eq_impl, assert_attribute, assert_event, ResponseAssertions};
+pub use gas::{
+    mock_dependencies_with_gas_meter, GasMeter, GasSchedule, MeteredApi, MeteredQuerier,
+    MeteredStorage,
+};
+pub use recording::RecordingQuerier;
+#[cfg(feature = "iterator")]
+pub use storage::StorageDiff;
+pub use storage::{
+    FailingStorage, MockStorage, StorageChange, StorageFailure, StorageLimits, StorageOp,
+    StorageStats,
+};
 
+#[cfg(feature = "stargate")]
+pub use mock::IbcQuerier;
 #[cfg(feature = "staking")]
 pub use mock::StakingQuerier;
 pub use mock::{
-    digit_sum, mock_dependencies, mock_dependencies_with_balance, mock_dependencies_with_balances,
-    mock_env, mock_info, mock_wasmd_attr, riffle_shuffle, BankQuerier, MockApi, MockQuerier,
-    MockQuerierCustomHandlerResult, MockStorage, MOCK_CONTRACT_ADDR,
+    digit_sum, mock_dependencies, mock_dependencies_builder, mock_dependencies_with_balance,
+    mock_dependencies_with_balances, mock_env, mock_info, mock_info_with, mock_wasmd_attr,
+    riffle_shuffle, BankQuerier, MockApi, MockDependenciesBuilder, MockEnvExt, MockInfoExt,
+    MockQuerier, MockQuerierCustomHandlerResult, MOCK_CONTRACT_ADDR,
 };
 #[cfg(feature = "stargate")]
 pub use mock::{
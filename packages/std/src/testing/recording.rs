@@ -0,0 +1,212 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fmt::Debug;
+
+use crate::binary::Binary;
+use crate::query::{CustomQuery, QueryRequest, WasmQuery};
+use crate::results::Empty;
+use crate::serde::{from_slice, to_binary};
+use crate::traits::{Querier, QuerierResult};
+
+use super::mock::MockQuerier;
+
+/// A querier that forwards all queries to an inner querier and records the
+/// requests, such that tests can check which queries a contract issued.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::{Empty, QuerierWrapper};
+/// # use cosmwasm_std::testing::{MockQuerier, RecordingQuerier};
+/// let querier = RecordingQuerier::new(MockQuerier::default());
+/// let wrapper = QuerierWrapper::<Empty>::new(&querier);
+/// let _ = wrapper.query_wasm_smart::<String>("oracle", &"price");
+///
+/// querier.assert_smart_queried("oracle", &"price");
+/// querier.assert_query_count(1);
+/// ```
+pub struct RecordingQuerier<Q: Querier = MockQuerier> {
+    inner: Q,
+    requests: RefCell<Vec<Binary>>,
+}
+
+impl<Q: Querier> RecordingQuerier<Q> {
+    pub fn new(inner: Q) -> Self {
+        RecordingQuerier {
+            inner,
+            requests: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn inner(&self) -> &Q {
+        &self.inner
+    }
+
+    /// Gives access to the inner querier, e.g. to update the data it returns.
+    pub fn inner_mut(&mut self) -> &mut Q {
+        &mut self.inner
+    }
+
+    /// Returns all requests in their serialized form, in the order they were issued.
+    pub fn raw_requests(&self) -> Vec<Binary> {
+        self.requests.borrow().clone()
+    }
+
+    /// Returns all requests that can be deserialized as `QueryRequest<C>`,
+    /// in the order they were issued.
+    pub fn requests<C: CustomQuery + DeserializeOwned>(&self) -> Vec<QueryRequest<C>> {
+        self.requests
+            .borrow()
+            .iter()
+            .filter_map(|raw| from_slice(raw).ok())
+            .collect()
+    }
+
+    /// Forgets all recorded requests.
+    pub fn clear(&mut self) {
+        self.requests.borrow_mut().clear();
+    }
+
+    /// Panics if the number of recorded requests is not `expected`.
+    pub fn assert_query_count(&self, expected: usize) {
+        let count = self.requests.borrow().len();
+        assert_eq!(
+            count, expected,
+            "Expected {} queries but {} were issued",
+            expected, count
+        );
+    }
+
+    /// Panics if the given request was not issued.
+    pub fn assert_queried<C>(&self, request: &QueryRequest<C>)
+    where
+        C: CustomQuery + DeserializeOwned + PartialEq + Debug,
+    {
+        let requests = self.requests::<C>();
+        assert!(
+            requests.contains(request),
+            "Query {:?} was not issued. Issued queries: {:?}",
+            request,
+            requests
+        );
+    }
+
+    /// Panics if no smart query with the given message was sent to `contract_addr`.
+    pub fn assert_smart_queried(&self, contract_addr: &str, msg: &impl Serialize) {
+        let msg = to_binary(msg).expect("Failed to serialize query message");
+        let found = self.wasm_queries().iter().any(|query| match query {
+            WasmQuery::Smart {
+                contract_addr: addr,
+                msg: sent,
+            } => addr == contract_addr && *sent == msg,
+            _ => false,
+        });
+        assert!(
+            found,
+            "No smart query with message {} was sent to {}. Issued Wasm queries: {:?}",
+            msg,
+            contract_addr,
+            self.wasm_queries()
+        );
+    }
+
+    fn wasm_queries(&self) -> Vec<WasmQuery> {
+        // the custom query type does not matter for Wasm queries
+        self.requests::<Empty>()
+            .into_iter()
+            .filter_map(|request| match request {
+                QueryRequest::Wasm(query) => Some(query),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl<Q: Querier> Querier for RecordingQuerier<Q> {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        self.requests.borrow_mut().push(Binary::from(bin_request));
+        self.inner.raw_query(bin_request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::BankQuery;
+    use crate::testing::mock_dependencies;
+    use crate::{coins, Coin, ContractResult, QuerierWrapper, SystemResult};
+
+    #[test]
+    fn records_requests() {
+        let mut querier =
+            RecordingQuerier::new(MockQuerier::<Empty>::new(&[("alice", &coins(5, "ucosm"))]));
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        let balance = wrapper.query_balance("alice", "ucosm").unwrap();
+        assert_eq!(balance, Coin::new(5, "ucosm"));
+        wrapper.query_all_balances("bob").unwrap();
+
+        querier.assert_query_count(2);
+        querier.assert_queried::<Empty>(&QueryRequest::Bank(BankQuery::Balance {
+            address: "alice".to_string(),
+            denom: "ucosm".to_string(),
+        }));
+        querier.assert_queried::<Empty>(&QueryRequest::Bank(BankQuery::AllBalances {
+            address: "bob".to_string(),
+        }));
+        assert_eq!(querier.raw_requests().len(), 2);
+
+        querier.clear();
+        querier.assert_query_count(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "was not issued")]
+    fn assert_queried_panics_for_other_request() {
+        let querier = RecordingQuerier::new(MockQuerier::default());
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        wrapper.query_all_balances("alice").unwrap();
+
+        querier.assert_queried::<Empty>(&QueryRequest::Bank(BankQuery::AllBalances {
+            address: "bob".to_string(),
+        }));
+    }
+
+    #[test]
+    fn assert_smart_queried_works() {
+        let mut inner = mock_dependencies().querier;
+        inner.update_wasm(|_| SystemResult::Ok(ContractResult::Ok(to_binary(&42u64).unwrap())));
+        let mut querier = RecordingQuerier::new(inner);
+
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        let price: u64 = wrapper
+            .query_wasm_smart("oracle", &("price", "ATOM"))
+            .unwrap();
+        assert_eq!(price, 42);
+        querier.assert_smart_queried("oracle", &("price", "ATOM"));
+
+        // the inner querier can still be updated
+        querier
+            .inner_mut()
+            .update_wasm(|_| SystemResult::Ok(ContractResult::Ok(to_binary(&7u64).unwrap())));
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        let res: u64 = wrapper
+            .query(&QueryRequest::Wasm(WasmQuery::Raw {
+                contract_addr: "oracle".to_string(),
+                key: Binary::from(b"config"),
+            }))
+            .unwrap();
+        assert_eq!(res, 7);
+        querier.assert_query_count(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "No smart query with message")]
+    fn assert_smart_queried_panics_for_wrong_contract() {
+        let querier = RecordingQuerier::new(MockQuerier::default());
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+        let _ = wrapper.query_wasm_smart::<u64>("oracle", &"price");
+
+        querier.assert_smart_queried("other", &"price");
+    }
+}
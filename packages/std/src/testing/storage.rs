@@ -0,0 +1,620 @@
+use std::cell::Cell;
+use std::fmt;
+
+#[cfg(feature = "iterator")]
+use crate::iterator::{Order, Record};
+use crate::storage::MemoryStorage;
+use crate::traits::Storage;
+
+const KI: usize = 1024;
+/// The maximum key length the VM accepts for storage operations (`MAX_LENGTH_DB_KEY`)
+const MAX_LENGTH_DB_KEY: usize = 64 * KI;
+/// The maximum value length the VM accepts for storage writes (`MAX_LENGTH_DB_VALUE`)
+const MAX_LENGTH_DB_VALUE: usize = 128 * KI;
+
+/// Size limits for keys and values, enforced by [`MockStorage::with_limits`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StorageLimits {
+    pub max_key_length: usize,
+    pub max_value_length: usize,
+}
+
+impl Default for StorageLimits {
+    /// The limits used by cosmwasm-vm
+    fn default() -> Self {
+        StorageLimits {
+            max_key_length: MAX_LENGTH_DB_KEY,
+            max_value_length: MAX_LENGTH_DB_VALUE,
+        }
+    }
+}
+
+/// Counters for the operations performed on a [`MockStorage`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageStats {
+    /// Number of `get` calls
+    pub reads: u64,
+    /// Number of `set` calls
+    pub writes: u64,
+    /// Number of `remove` calls
+    pub removes: u64,
+    /// Number of records returned from ranges
+    pub range_items: u64,
+    /// Bytes of keys and values returned from `get` and ranges
+    pub bytes_read: u64,
+    /// Bytes of keys and values passed to `set`
+    pub bytes_written: u64,
+}
+
+/// An in-memory storage for unit tests.
+///
+/// It counts the operations performed on it (see [`MockStorage::stats`]) and can
+/// optionally enforce the key and value size limits of the VM, such that writes which
+/// would fail on chain already fail in unit tests.
+#[derive(Default, Clone)]
+pub struct MockStorage {
+    storage: MemoryStorage,
+    limits: Option<StorageLimits>,
+    stats: Cell<StorageStats>,
+}
+
+impl MockStorage {
+    pub fn new() -> Self {
+        MockStorage::default()
+    }
+
+    /// Enforces the given limits. Operations exceeding them panic, just like the
+    /// contract execution is aborted by the VM.
+    ///
+    /// Use `StorageLimits::default()` for the limits of cosmwasm-vm.
+    pub fn with_limits(mut self, limits: StorageLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Returns the operations counted since creation or the last [`MockStorage::reset_stats`].
+    pub fn stats(&self) -> StorageStats {
+        self.stats.get()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats.set(StorageStats::default());
+    }
+
+    fn update_stats(&self, update: impl FnOnce(&mut StorageStats)) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        self.stats.set(stats);
+    }
+
+    fn check_key(&self, key: &[u8]) {
+        if let Some(limits) = self.limits {
+            if key.len() > limits.max_key_length {
+                panic!(
+                    "Storage key too long: {} bytes exceeds the limit of {} bytes",
+                    key.len(),
+                    limits.max_key_length
+                );
+            }
+        }
+    }
+
+    fn check_value(&self, value: &[u8]) {
+        if let Some(limits) = self.limits {
+            if value.len() > limits.max_value_length {
+                panic!(
+                    "Storage value too long: {} bytes exceeds the limit of {} bytes",
+                    value.len(),
+                    limits.max_value_length
+                );
+            }
+        }
+    }
+}
+
+impl Storage for MockStorage {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.check_key(key);
+        let value = self.storage.get(key);
+        self.update_stats(|stats| {
+            stats.reads += 1;
+            stats.bytes_read += value.as_ref().map_or(0, |v| v.len()) as u64;
+        });
+        value
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        for key in start.iter().chain(end.iter()) {
+            self.check_key(key);
+        }
+        Box::new(
+            self.storage
+                .range(start, end, order)
+                .inspect(move |(key, value)| {
+                    self.update_stats(|stats| {
+                        stats.range_items += 1;
+                        stats.bytes_read += (key.len() + value.len()) as u64;
+                    });
+                }),
+        )
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.check_key(key);
+        self.check_value(value);
+        self.storage.set(key, value);
+        self.update_stats(|stats| {
+            stats.writes += 1;
+            stats.bytes_written += (key.len() + value.len()) as u64;
+        });
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.check_key(key);
+        self.storage.remove(key);
+        self.update_stats(|stats| stats.removes += 1);
+    }
+}
+
+impl fmt::Debug for MockStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.storage.fmt(f)
+    }
+}
+
+/// A storage operation, used to program failures in [`FailingStorage`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StorageOp {
+    Get,
+    Set,
+    Remove,
+    Range,
+}
+
+/// What happens when a programmed failure of [`FailingStorage`] is triggered.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StorageFailure {
+    /// Panics like the VM aborts the execution when the backend fails
+    Panic,
+    /// The operation has no effect, i.e. reads return no data and writes are lost
+    Ignore,
+}
+
+/// A storage wrapper that fails on programmed calls, for testing partial failure behavior.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::Storage;
+/// # use cosmwasm_std::testing::{FailingStorage, MockStorage, StorageFailure, StorageOp};
+/// let mut storage = FailingStorage::new(MockStorage::new())
+///     .fail_on(StorageOp::Set, 2, StorageFailure::Ignore);
+/// storage.set(b"a", b"1");
+/// storage.set(b"b", b"2"); // lost
+/// assert_eq!(storage.get(b"a"), Some(b"1".to_vec()));
+/// assert_eq!(storage.get(b"b"), None);
+/// ```
+pub struct FailingStorage<S: Storage = MockStorage> {
+    inner: S,
+    failures: Vec<(StorageOp, u64, StorageFailure)>,
+    calls: Cell<[u64; 4]>,
+}
+
+impl<S: Storage> FailingStorage<S> {
+    pub fn new(inner: S) -> Self {
+        FailingStorage {
+            inner,
+            failures: vec![],
+            calls: Cell::new([0; 4]),
+        }
+    }
+
+    /// Makes the `n`th call (starting at 1) of the given operation fail.
+    pub fn fail_on(mut self, op: StorageOp, n: u64, failure: StorageFailure) -> Self {
+        self.failures.push((op, n, failure));
+        self
+    }
+
+    /// Returns the number of calls of the given operation so far.
+    pub fn calls(&self, op: StorageOp) -> u64 {
+        self.calls.get()[op as usize]
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Counts the call and returns true if the operation should be executed.
+    fn call(&self, op: StorageOp) -> bool {
+        let mut calls = self.calls.get();
+        calls[op as usize] += 1;
+        self.calls.set(calls);
+        let n = calls[op as usize];
+
+        match self
+            .failures
+            .iter()
+            .find(|(failing_op, failing_n, _)| *failing_op == op && *failing_n == n)
+        {
+            Some((_, _, StorageFailure::Panic)) => {
+                panic!("Programmed storage failure in call {} of {:?}", n, op)
+            }
+            Some((_, _, StorageFailure::Ignore)) => false,
+            None => true,
+        }
+    }
+}
+
+impl<S: Storage> Storage for FailingStorage<S> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        if self.call(StorageOp::Get) {
+            self.inner.get(key)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        if self.call(StorageOp::Range) {
+            self.inner.range(start, end, order)
+        } else {
+            Box::new(std::iter::empty())
+        }
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        if self.call(StorageOp::Set) {
+            self.inner.set(key, value)
+        }
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        if self.call(StorageOp::Remove) {
+            self.inner.remove(key)
+        }
+    }
+}
+
+/// A single key-level difference between two storages. See [`StorageDiff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StorageChange {
+    Added {
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Changed {
+        key: Vec<u8>,
+        old: Vec<u8>,
+        new: Vec<u8>,
+    },
+    Removed {
+        key: Vec<u8>,
+        old: Vec<u8>,
+    },
+}
+
+impl StorageChange {
+    pub fn key(&self) -> &[u8] {
+        match self {
+            StorageChange::Added { key, .. }
+            | StorageChange::Changed { key, .. }
+            | StorageChange::Removed { key, .. } => key,
+        }
+    }
+}
+
+/// The changes between two states of a storage, ordered by key.
+///
+/// Take a snapshot by cloning the storage before executing a message and compare it
+/// with the storage afterwards. The `Display` implementation decodes length-prefixed
+/// namespaces as used by the storage helpers (e.g. `balance/alice`), which makes
+/// state transitions easy to review.
+///
+/// ## Examples
+///
+/// ```
+/// # use cosmwasm_std::Storage;
+/// # use cosmwasm_std::testing::{MockStorage, StorageDiff};
+/// let mut storage = MockStorage::new();
+/// storage.set(b"config", b"{}");
+/// let before = storage.clone();
+///
+/// storage.set(b"config", br#"{"paused":true}"#);
+/// storage.set(b"\x00\x07balancealice", b"\"12\"");
+///
+/// let diff = StorageDiff::between(&before, &storage);
+/// assert_eq!(
+///     diff.to_string(),
+///     "+ balance/alice: \"12\"\n~ config: {} -> {\"paused\":true}\n"
+/// );
+/// ```
+#[cfg(feature = "iterator")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageDiff {
+    pub changes: Vec<StorageChange>,
+}
+
+#[cfg(feature = "iterator")]
+impl StorageDiff {
+    pub fn between(before: &impl Storage, after: &impl Storage) -> Self {
+        let mut before = before.range(None, None, Order::Ascending).peekable();
+        let mut after = after.range(None, None, Order::Ascending).peekable();
+        let mut changes = vec![];
+        loop {
+            let ordering = match (before.peek(), after.peek()) {
+                (None, None) => break,
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (Some((old_key, _)), Some((new_key, _))) => old_key.cmp(new_key),
+            };
+            match ordering {
+                std::cmp::Ordering::Less => {
+                    let (key, old) = before.next().unwrap();
+                    changes.push(StorageChange::Removed { key, old });
+                }
+                std::cmp::Ordering::Greater => {
+                    let (key, value) = after.next().unwrap();
+                    changes.push(StorageChange::Added { key, value });
+                }
+                std::cmp::Ordering::Equal => {
+                    let (key, old) = before.next().unwrap();
+                    let (_, new) = after.next().unwrap();
+                    if old != new {
+                        changes.push(StorageChange::Changed { key, old, new });
+                    }
+                }
+            }
+        }
+        StorageDiff { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+#[cfg(feature = "iterator")]
+impl fmt::Display for StorageDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            let key = decode_key(change.key());
+            match change {
+                StorageChange::Added { value, .. } => {
+                    writeln!(f, "+ {}: {}", key, format_bytes(value))?
+                }
+                StorageChange::Changed { old, new, .. } => writeln!(
+                    f,
+                    "~ {}: {} -> {}",
+                    key,
+                    format_bytes(old),
+                    format_bytes(new)
+                )?,
+                StorageChange::Removed { old, .. } => {
+                    writeln!(f, "- {}: {}", key, format_bytes(old))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Splits off length-prefixed namespaces (2 byte big endian length) from the key and
+/// joins all parts with "/".
+#[cfg(feature = "iterator")]
+fn decode_key(key: &[u8]) -> String {
+    let mut parts = vec![];
+    let mut rest = key;
+    while rest.len() >= 2 {
+        let len = u16::from_be_bytes([rest[0], rest[1]]) as usize;
+        if len == 0 || rest.len() < 2 + len {
+            break;
+        }
+        parts.push(format_bytes(&rest[2..2 + len]));
+        rest = &rest[2 + len..];
+    }
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(format_bytes(rest));
+    }
+    parts.join("/")
+}
+
+/// Shows printable UTF-8 as text and everything else as hex.
+#[cfg(feature = "iterator")]
+fn format_bytes(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !text.chars().any(|c| c.is_control()) => text.to_string(),
+        _ => format!("0x{}", hex::encode(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_work() {
+        let mut store = MockStorage::new();
+        assert_eq!(store.stats(), StorageStats::default());
+
+        store.set(b"foo", b"bar");
+        store.set(b"foo2", b"x");
+        assert_eq!(store.get(b"foo"), Some(b"bar".to_vec()));
+        assert_eq!(store.get(b"food"), None);
+        store.remove(b"foo2");
+        assert_eq!(
+            store.stats(),
+            StorageStats {
+                reads: 2,
+                writes: 2,
+                removes: 1,
+                range_items: 0,
+                bytes_read: 3,
+                bytes_written: 11,
+            }
+        );
+
+        store.reset_stats();
+        assert_eq!(store.stats(), StorageStats::default());
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn stats_count_range_items() {
+        let mut store = MockStorage::new();
+        store.set(b"a", b"1");
+        store.set(b"b", b"22");
+        store.set(b"c", b"333");
+        store.reset_stats();
+
+        let items: Vec<_> = store.range(None, None, Order::Ascending).take(2).collect();
+        assert_eq!(items.len(), 2);
+        assert_eq!(store.stats().range_items, 2);
+        assert_eq!(store.stats().bytes_read, 5);
+    }
+
+    #[test]
+    fn limits_allow_values_within_limits() {
+        let mut store = MockStorage::new().with_limits(StorageLimits::default());
+        store.set(&[1; MAX_LENGTH_DB_KEY], &[2; MAX_LENGTH_DB_VALUE]);
+        assert!(store.get(&[1; MAX_LENGTH_DB_KEY]).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "Storage key too long: 65537 bytes exceeds the limit of 65536 bytes")]
+    fn limits_reject_long_keys() {
+        let mut store = MockStorage::new().with_limits(StorageLimits::default());
+        store.set(&[1; MAX_LENGTH_DB_KEY + 1], b"foo");
+    }
+
+    #[test]
+    #[should_panic(expected = "Storage value too long: 11 bytes exceeds the limit of 10 bytes")]
+    fn limits_reject_long_values() {
+        let mut store = MockStorage::new().with_limits(StorageLimits {
+            max_key_length: 10,
+            max_value_length: 10,
+        });
+        store.set(b"foo", &[1; 11]);
+    }
+
+    #[test]
+    fn no_limits_by_default() {
+        let mut store = MockStorage::new();
+        store.set(&[1; MAX_LENGTH_DB_KEY + 1], &[2; MAX_LENGTH_DB_VALUE + 1]);
+    }
+
+    #[test]
+    fn failing_storage_counts_calls() {
+        let mut store = FailingStorage::new(MockStorage::new());
+        store.set(b"foo", b"bar");
+        store.get(b"foo");
+        store.get(b"foo");
+        store.remove(b"foo");
+        assert_eq!(store.calls(StorageOp::Set), 1);
+        assert_eq!(store.calls(StorageOp::Get), 2);
+        assert_eq!(store.calls(StorageOp::Remove), 1);
+        assert_eq!(store.calls(StorageOp::Range), 0);
+        assert_eq!(store.inner().stats().writes, 1);
+    }
+
+    #[test]
+    fn failing_storage_ignores_programmed_calls() {
+        let mut store = FailingStorage::new(MockStorage::new())
+            .fail_on(StorageOp::Get, 2, StorageFailure::Ignore)
+            .fail_on(StorageOp::Remove, 1, StorageFailure::Ignore);
+        store.set(b"foo", b"bar");
+        assert_eq!(store.get(b"foo"), Some(b"bar".to_vec()));
+        assert_eq!(store.get(b"foo"), None);
+        assert_eq!(store.get(b"foo"), Some(b"bar".to_vec()));
+
+        store.remove(b"foo");
+        assert_eq!(store.into_inner().get(b"foo"), Some(b"bar".to_vec()));
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn failing_storage_ignores_programmed_ranges() {
+        let mut store = FailingStorage::new(MockStorage::new()).fail_on(
+            StorageOp::Range,
+            1,
+            StorageFailure::Ignore,
+        );
+        store.set(b"foo", b"bar");
+        assert_eq!(store.range(None, None, Order::Ascending).count(), 0);
+        assert_eq!(store.range(None, None, Order::Ascending).count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Programmed storage failure in call 2 of Set")]
+    fn failing_storage_panics_on_programmed_calls() {
+        let mut store = FailingStorage::new(MockStorage::new()).fail_on(
+            StorageOp::Set,
+            2,
+            StorageFailure::Panic,
+        );
+        store.set(b"foo", b"bar");
+        store.set(b"foo", b"baz");
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn storage_diff_works() {
+        let mut storage = MockStorage::new();
+        storage.set(b"a", b"1");
+        storage.set(b"b", b"2");
+        storage.set(b"c", b"3");
+        let before = storage.clone();
+        assert!(StorageDiff::between(&before, &storage).is_empty());
+
+        storage.remove(b"a");
+        storage.set(b"b", b"22");
+        storage.set(b"c", b"3");
+        storage.set(b"d", b"4");
+        let diff = StorageDiff::between(&before, &storage);
+        assert_eq!(
+            diff.changes,
+            vec![
+                StorageChange::Removed {
+                    key: b"a".to_vec(),
+                    old: b"1".to_vec()
+                },
+                StorageChange::Changed {
+                    key: b"b".to_vec(),
+                    old: b"2".to_vec(),
+                    new: b"22".to_vec()
+                },
+                StorageChange::Added {
+                    key: b"d".to_vec(),
+                    value: b"4".to_vec()
+                },
+            ]
+        );
+        assert_eq!(diff.to_string(), "- a: 1\n~ b: 2 -> 22\n+ d: 4\n");
+    }
+
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn decode_key_works() {
+        assert_eq!(decode_key(b"config"), "config");
+        assert_eq!(decode_key(b"\x00\x06config"), "config");
+        assert_eq!(decode_key(b"\x00\x07balancealice"), "balance/alice");
+        assert_eq!(decode_key(b"\x00\x05users\x00\x03bobage"), "users/bob/age");
+        assert_eq!(decode_key(b"\x00\x03ids\x00\x00\x00\x01"), "ids/0x00000001");
+        assert_eq!(decode_key(b""), "");
+    }
+}
@@ -6,8 +6,12 @@ use crate::addresses::{Addr, CanonicalAddr};
 use crate::binary::Binary;
 use crate::coin::Coin;
 use crate::errors::{RecoverPubkeyError, StdError, StdResult, VerificationError};
+#[cfg(any(feature = "iterator", feature = "raw_range"))]
+use crate::iterator::Order;
 #[cfg(feature = "iterator")]
-use crate::iterator::{Order, Record};
+use crate::iterator::Record;
+#[cfg(feature = "raw_range")]
+use crate::query::RawRangeResponse;
 #[cfg(feature = "cosmwasm_1_1")]
 use crate::query::SupplyResponse;
 use crate::query::{
@@ -304,6 +308,28 @@ impl<'a, C: CustomQuery> QuerierWrapper<'a, C> {
         self.query(&request)
     }
 
+    /// Queries up to `limit` raw key-value pairs of the given contract's storage in the
+    /// range `[start, end)`. Use `next_key` of the response to query the next page.
+    #[cfg(feature = "raw_range")]
+    pub fn query_wasm_raw_range(
+        &self,
+        contract_addr: impl Into<String>,
+        start: Option<Binary>,
+        end: Option<Binary>,
+        limit: u16,
+        order: Order,
+    ) -> StdResult<RawRangeResponse> {
+        let request = WasmQuery::RawRange {
+            contract_addr: contract_addr.into(),
+            start,
+            end,
+            limit,
+            order,
+        }
+        .into();
+        self.query(&request)
+    }
+
     #[cfg(feature = "staking")]
     pub fn query_all_validators(&self) -> StdResult<Vec<Validator>> {
         let request = StakingQuery::AllValidators {}.into();
@@ -453,6 +479,38 @@ mod tests {
         assert_eq!(contract_info, mock_resp());
     }
 
+    #[cfg(feature = "raw_range")]
+    #[test]
+    fn raw_range() {
+        let mut querier: MockQuerier<Empty> = MockQuerier::new(&[]);
+        querier.update_wasm(|q| -> QuerierResult {
+            match q {
+                WasmQuery::RawRange {
+                    contract_addr,
+                    start,
+                    limit,
+                    order,
+                    ..
+                } if contract_addr == "foobar" && *limit == 1 && *order == Order::Ascending => {
+                    assert_eq!(start, &Some(Binary::from(b"a")));
+                    let response = RawRangeResponse::new(
+                        vec![(Binary::from(b"a"), Binary::from(b"1"))],
+                        Some(Binary::from(b"b")),
+                    );
+                    SystemResult::Ok(ContractResult::Ok(to_binary(&response).unwrap()))
+                }
+                _ => panic!("Unexpected query: {:?}", q),
+            }
+        });
+        let wrapper = QuerierWrapper::<Empty>::new(&querier);
+
+        let response = wrapper
+            .query_wasm_raw_range("foobar", Some(b"a".into()), None, 1, Order::Ascending)
+            .unwrap();
+        assert_eq!(response.data, vec![(b"a".into(), b"1".into())]);
+        assert_eq!(response.next_key, Some(Binary::from(b"b")));
+    }
+
     #[test]
     fn contract_info_err() {
         const ACCT: &str = "foobar";
@@ -5,8 +5,8 @@ use tempfile::TempDir;
 use cosmwasm_std::{coins, Empty};
 use cosmwasm_vm::testing::{mock_backend, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
 use cosmwasm_vm::{
-    call_execute, call_instantiate, capabilities_from_csv, Cache, CacheOptions, InstanceOptions,
-    Size,
+    call_execute, call_instantiate, capabilities_from_csv, Cache, CacheOptions, GasConfig,
+    InstanceOptions, Size,
 };
 
 // Instance
@@ -15,6 +15,9 @@ const DEFAULT_GAS_LIMIT: u64 = 400_000 * 150_000;
 const DEFAULT_INSTANCE_OPTIONS: InstanceOptions = InstanceOptions {
     gas_limit: DEFAULT_GAS_LIMIT,
     print_debug: false,
+    gas_config: GasConfig::DEFAULT,
+    query_response_limit: None,
+    memoize_queries: false,
 };
 // Cache
 const MEMORY_CACHE_SIZE: Size = Size::mebi(200);
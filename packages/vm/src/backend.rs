@@ -201,6 +201,32 @@ impl BackendError {
     pub fn user_err(msg: impl Into<String>) -> Self {
         BackendError::UserErr { msg: msg.into() }
     }
+
+    /// A stable numeric code for the error case, which allows hosts written in other
+    /// languages to branch on the error kind without parsing the message.
+    ///
+    /// | Case                   | Code |
+    /// | ---------------------- | ---- |
+    /// | `ForeignPanic`         | 1    |
+    /// | `BadArgument`          | 2    |
+    /// | `InvalidUtf8`          | 3    |
+    /// | `IteratorDoesNotExist` | 4    |
+    /// | `OutOfGas`             | 5    |
+    /// | `Unknown`              | 6    |
+    /// | `UserErr`              | 7    |
+    ///
+    /// Codes are never reused. New cases get new codes.
+    pub fn code(&self) -> u32 {
+        match self {
+            BackendError::ForeignPanic {} => 1,
+            BackendError::BadArgument {} => 2,
+            BackendError::InvalidUtf8 {} => 3,
+            BackendError::IteratorDoesNotExist { .. } => 4,
+            BackendError::OutOfGas {} => 5,
+            BackendError::Unknown { .. } => 6,
+            BackendError::UserErr { .. } => 7,
+        }
+    }
 }
 
 impl From<FromUtf8Error> for BackendError {
@@ -213,6 +239,17 @@ impl From<FromUtf8Error> for BackendError {
 mod tests {
     use super::*;
 
+    #[test]
+    fn backend_error_code_works() {
+        assert_eq!(BackendError::foreign_panic().code(), 1);
+        assert_eq!(BackendError::bad_argument().code(), 2);
+        assert_eq!(BackendError::InvalidUtf8 {}.code(), 3);
+        assert_eq!(BackendError::iterator_does_not_exist(3).code(), 4);
+        assert_eq!(BackendError::out_of_gas().code(), 5);
+        assert_eq!(BackendError::unknown("broken").code(), 6);
+        assert_eq!(BackendError::user_err("invalid input").code(), 7);
+    }
+
     #[test]
     fn gas_info_with_cost_works() {
         let gas_info = GasInfo::with_cost(21);
@@ -9,13 +9,16 @@ use crate::backend::{Backend, BackendApi, Querier, Storage};
 use crate::capabilities::required_capabilities_from_module;
 use crate::checksum::Checksum;
 use crate::compatibility::check_wasm;
+use crate::environment::GasConfig;
 use crate::errors::{VmError, VmResult};
 use crate::filesystem::mkdir_p;
 use crate::instance::{Instance, InstanceOptions};
+use crate::instance_pool::InstancePool;
+use crate::logging::vm_log;
 use crate::modules::{FileSystemCache, InMemoryCache, PinnedMemoryCache};
 use crate::size::Size;
-use crate::static_analysis::{deserialize_wasm, has_ibc_entry_points};
-use crate::wasm_backend::{compile, make_runtime_store};
+use crate::static_analysis::{build_info, deserialize_wasm, has_ibc_entry_points};
+use crate::wasm_backend::{CompilerBackend, WasmerBackend};
 
 const STATE_DIR: &str = "state";
 // Things related to the state of the blockchain.
@@ -27,6 +30,7 @@ const MODULES_DIR: &str = "modules";
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Stats {
+    pub hits_instance_pool: u32,
     pub hits_pinned_memory_cache: u32,
     pub hits_memory_cache: u32,
     pub hits_fs_cache: u32,
@@ -72,6 +76,9 @@ pub struct Cache<A: BackendApi, S: Storage, Q: Querier> {
     /// i.e. any number of read-only references is allowed to access it concurrently.
     available_capabilities: HashSet<String>,
     inner: Mutex<CacheInner>,
+    /// Instances that were created ahead of time for hot contracts. This is locked
+    /// separately from `inner` since instantiation is slow.
+    instance_pool: Mutex<InstancePool<A, S, Q>>,
     // Those two don't store data but only fix type information
     type_api: PhantomData<A>,
     type_storage: PhantomData<S>,
@@ -84,6 +91,8 @@ pub struct Cache<A: BackendApi, S: Storage, Q: Querier> {
 pub struct AnalysisReport {
     pub has_ibc_entry_points: bool,
     pub required_capabilities: HashSet<String>,
+    /// The build information embedded via `cosmwasm_std::embed_build_info!`, if any
+    pub build_info: Option<String>,
 }
 
 impl<A, S, Q> Cache<A, S, Q>
@@ -129,6 +138,7 @@ where
                 fs_cache,
                 stats: Stats::default(),
             }),
+            instance_pool: Mutex::new(InstancePool::new()),
             type_storage: PhantomData::<S>,
             type_api: PhantomData::<A>,
             type_querier: PhantomData::<Q>,
@@ -151,13 +161,20 @@ where
         }
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
     pub fn save_wasm(&self, wasm: &[u8]) -> VmResult<Checksum> {
-        check_wasm(wasm, &self.available_capabilities)?;
-        let module = compile(wasm, None, &[])?;
+        let checked = check_wasm(wasm, &self.available_capabilities);
+        #[cfg(feature = "log")]
+        if let Err(err) = &checked {
+            vm_log!(warn, "Wasm validation failed: {}", err);
+        }
+        checked?;
+        let module = WasmerBackend.compile(wasm, None)?;
 
         let mut cache = self.inner.lock().unwrap();
         let checksum = save_wasm_to_disk(&cache.wasm_path, wasm)?;
         cache.fs_cache.store(&checksum, &module)?;
+        vm_log!(debug, "Saved Wasm with checksum {}", checksum);
         Ok(checksum)
     }
 
@@ -191,6 +208,7 @@ where
         Ok(AnalysisReport {
             has_ibc_entry_points: has_ibc_entry_points(&module),
             required_capabilities: required_capabilities_from_module(&module),
+            build_info: build_info(&module),
         })
     }
 
@@ -215,10 +233,12 @@ where
         }
 
         // Try to get module from file system cache
-        let store = make_runtime_store(Some(cache.instance_memory_limit));
-        if let Some(module) = cache.fs_cache.load(checksum, &store)? {
+        if let Some(module) = cache
+            .fs_cache
+            .load(checksum, Some(cache.instance_memory_limit))?
+        {
             cache.stats.hits_fs_cache += 1;
-            let module_size = loupe::size_of_val(&module);
+            let module_size = WasmerBackend.module_size(&module);
             return cache
                 .pinned_memory_cache
                 .store(checksum, module, module_size);
@@ -226,10 +246,10 @@ where
 
         // Re-compile from original Wasm bytecode
         let code = self.load_wasm_with_path(&cache.wasm_path, checksum)?;
-        let module = compile(&code, Some(cache.instance_memory_limit), &[])?;
+        let module = WasmerBackend.compile(&code, Some(cache.instance_memory_limit))?;
         // Store into the fs cache too
         cache.fs_cache.store(checksum, &module)?;
-        let module_size = loupe::size_of_val(&module);
+        let module_size = WasmerBackend.module_size(&module);
         cache
             .pinned_memory_cache
             .store(checksum, module, module_size)
@@ -247,21 +267,78 @@ where
             .remove(checksum)
     }
 
+    /// Sets the number of instances that are kept ready for the given checksum.
+    ///
+    /// Pooled instances are created by [`Cache::fill_instance_pool`] and handed out by
+    /// [`Cache::get_instance`]. A capacity of 0 disables pooling for this checksum.
+    pub fn set_instance_pool_capacity(&self, checksum: &Checksum, capacity: usize) {
+        self.instance_pool
+            .lock()
+            .unwrap()
+            .set_capacity(checksum, capacity);
+    }
+
+    /// Instantiates the given contract until its instance pool is full.
+    ///
+    /// This is meant to be called outside of the critical path, e.g. in a background thread
+    /// after a pooled instance was used. The API is replaced by the one of the backend
+    /// passed to [`Cache::get_instance`] when the instance is handed out.
+    pub fn fill_instance_pool(&self, checksum: &Checksum, api: A) -> VmResult<()> {
+        let missing = self.instance_pool.lock().unwrap().missing(checksum);
+        if missing == 0 {
+            return Ok(());
+        }
+
+        let module = self.get_module(checksum)?;
+        for _ in 0..missing {
+            // gas limit and settings are replaced when the instance is handed out
+            let options = InstanceOptions {
+                gas_limit: 0,
+                print_debug: false,
+                gas_config: GasConfig::DEFAULT,
+                query_response_limit: None,
+                memoize_queries: false,
+            };
+            let instance = Instance::from_module_unbound(
+                &module,
+                api,
+                options,
+                None,
+                Some(&self.instantiation_lock),
+            )?;
+            if !self.instance_pool.lock().unwrap().put(checksum, instance) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     /// Returns an Instance tied to a previously saved Wasm.
     ///
-    /// It takes a module from cache or Wasm code and instantiates it.
+    /// If the instance pool of this checksum is not empty, a pooled instance is used.
+    /// Otherwise it takes a module from cache or Wasm code and instantiates it.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(checksum = %checksum))
+    )]
     pub fn get_instance(
         &self,
         checksum: &Checksum,
         backend: Backend<A, S, Q>,
         options: InstanceOptions,
     ) -> VmResult<Instance<A, S, Q>> {
+        let pooled = self.instance_pool.lock().unwrap().take(checksum);
+        if let Some(mut instance) = pooled {
+            self.inner.lock().unwrap().stats.hits_instance_pool += 1;
+            instance.adopt(backend, options);
+            return Ok(instance);
+        }
+
         let module = self.get_module(checksum)?;
         let instance = Instance::from_module(
             &module,
             backend,
-            options.gas_limit,
-            options.print_debug,
+            options,
             None,
             Some(&self.instantiation_lock),
         )?;
@@ -271,25 +348,33 @@ where
     /// Returns a module tied to a previously saved Wasm.
     /// Depending on availability, this is either generated from a memory cache, file system cache or Wasm code.
     /// This is part of `get_instance` but pulled out to reduce the locking time.
-    fn get_module(&self, checksum: &Checksum) -> VmResult<wasmer::Module> {
+    fn get_module(
+        &self,
+        checksum: &Checksum,
+    ) -> VmResult<<WasmerBackend as CompilerBackend>::Module> {
         let mut cache = self.inner.lock().unwrap();
         // Try to get module from the pinned memory cache
         if let Some(module) = cache.pinned_memory_cache.load(checksum)? {
             cache.stats.hits_pinned_memory_cache += 1;
+            vm_log!(trace, "Module {} found in pinned memory cache", checksum);
             return Ok(module);
         }
 
         // Get module from memory cache
         if let Some(module) = cache.memory_cache.load(checksum)? {
             cache.stats.hits_memory_cache += 1;
+            vm_log!(trace, "Module {} found in memory cache", checksum);
             return Ok(module.module);
         }
 
         // Get module from file system cache
-        let store = make_runtime_store(Some(cache.instance_memory_limit));
-        if let Some(module) = cache.fs_cache.load(checksum, &store)? {
+        if let Some(module) = cache
+            .fs_cache
+            .load(checksum, Some(cache.instance_memory_limit))?
+        {
             cache.stats.hits_fs_cache += 1;
-            let module_size = loupe::size_of_val(&module);
+            vm_log!(debug, "Module {} loaded from file system cache", checksum);
+            let module_size = WasmerBackend.module_size(&module);
             cache
                 .memory_cache
                 .store(checksum, module.clone(), module_size)?;
@@ -303,9 +388,14 @@ where
         // stored the old module format.
         let wasm = self.load_wasm_with_path(&cache.wasm_path, checksum)?;
         cache.stats.misses += 1;
-        let module = compile(&wasm, Some(cache.instance_memory_limit), &[])?;
+        vm_log!(
+            info,
+            "Module {} not found in any cache, recompiling",
+            checksum
+        );
+        let module = WasmerBackend.compile(&wasm, Some(cache.instance_memory_limit))?;
         cache.fs_cache.store(checksum, &module)?;
-        let module_size = loupe::size_of_val(&module);
+        let module_size = WasmerBackend.module_size(&module);
         cache
             .memory_cache
             .store(checksum, module.clone(), module_size)?;
@@ -381,6 +471,9 @@ mod tests {
     const TESTING_OPTIONS: InstanceOptions = InstanceOptions {
         gas_limit: TESTING_GAS_LIMIT,
         print_debug: false,
+        gas_config: GasConfig::DEFAULT,
+        query_response_limit: None,
+        memoize_queries: false,
     };
     const TESTING_MEMORY_CACHE_SIZE: Size = Size::mebi(200);
 
@@ -650,6 +743,49 @@ mod tests {
         assert_eq!(cache.stats().misses, 0);
     }
 
+    #[test]
+    fn get_instance_uses_instance_pool() {
+        let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
+        let checksum = cache.save_wasm(CONTRACT).unwrap();
+
+        // not pooled by default
+        cache
+            .fill_instance_pool(&checksum, MockApi::default())
+            .unwrap();
+        assert_eq!(cache.stats().hits_fs_cache, 0);
+
+        cache.set_instance_pool_capacity(&checksum, 2);
+        cache
+            .fill_instance_pool(&checksum, MockApi::default())
+            .unwrap();
+        assert_eq!(cache.stats().hits_fs_cache, 1);
+
+        for _ in 0..2 {
+            let mut instance = cache
+                .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+                .unwrap();
+            assert_eq!(instance.get_gas_left(), TESTING_GAS_LIMIT);
+
+            // init
+            let info = mock_info("creator", &coins(1000, "earth"));
+            let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+            let res =
+                call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg).unwrap();
+            let msgs = res.unwrap().messages;
+            assert_eq!(msgs.len(), 0);
+            assert!(instance.recycle().is_some());
+        }
+        assert_eq!(cache.stats().hits_instance_pool, 2);
+        assert_eq!(cache.stats().hits_memory_cache, 0);
+
+        // pool is empty now
+        let _instance = cache
+            .get_instance(&checksum, mock_backend(&[]), TESTING_OPTIONS)
+            .unwrap();
+        assert_eq!(cache.stats().hits_instance_pool, 2);
+        assert_eq!(cache.stats().hits_memory_cache, 1);
+    }
+
     #[test]
     fn call_instantiate_on_cached_contract() {
         let cache = unsafe { Cache::new(make_testing_options()).unwrap() };
@@ -910,6 +1046,9 @@ mod tests {
         let options = InstanceOptions {
             gas_limit: 10,
             print_debug: false,
+            gas_config: GasConfig::DEFAULT,
+            query_response_limit: None,
+            memoize_queries: false,
         };
         let mut instance1 = cache.get_instance(&checksum, backend1, options).unwrap();
         assert_eq!(cache.stats().hits_fs_cache, 1);
@@ -930,6 +1069,9 @@ mod tests {
         let options = InstanceOptions {
             gas_limit: TESTING_GAS_LIMIT,
             print_debug: false,
+            gas_config: GasConfig::DEFAULT,
+            query_response_limit: None,
+            memoize_queries: false,
         };
         let mut instance2 = cache.get_instance(&checksum, backend2, options).unwrap();
         assert_eq!(cache.stats().hits_pinned_memory_cache, 0);
@@ -1000,6 +1142,7 @@ mod tests {
             AnalysisReport {
                 has_ibc_entry_points: false,
                 required_capabilities: HashSet::new(),
+                build_info: None,
             }
         );
 
@@ -1014,6 +1157,7 @@ mod tests {
                     "staking".to_string(),
                     "stargate".to_string()
                 ]),
+                build_info: None,
             }
         );
     }
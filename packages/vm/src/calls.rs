@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use serde::de::DeserializeOwned;
 use wasmer::Val;
 
@@ -11,9 +13,10 @@ use cosmwasm_std::{
 
 use crate::backend::{BackendApi, Querier, Storage};
 use crate::conversion::ref_to_u32;
-use crate::errors::{VmError, VmResult};
+use crate::errors::{CommunicationError, VmError, VmResult};
 use crate::instance::Instance;
 use crate::serde::{from_slice, to_vec};
+use crate::size::Size;
 
 /// The limits in here protect the host from allocating an unreasonable amount of memory
 /// and copying an unreasonable amount of data.
@@ -427,6 +430,9 @@ where
 
 /// Calls Wasm export "query" and returns raw data from the contract.
 /// The result is length limited to prevent abuse but otherwise unchecked.
+///
+/// If the instance has a query response limit, results exceeding it cause a
+/// [`VmError::QueryResponseTooLarge`] error.
 pub fn call_query_raw<A, S, Q>(
     instance: &mut Instance<A, S, Q>,
     env: &[u8],
@@ -438,7 +444,18 @@ where
     Q: Querier + 'static,
 {
     instance.set_storage_readonly(true);
-    call_raw(instance, "query", &[env, msg], read_limits::RESULT_QUERY)
+    match instance.query_response_limit() {
+        Some(Size(limit)) if limit < read_limits::RESULT_QUERY => {
+            call_raw(instance, "query", &[env, msg], limit).map_err(|err| match err {
+                VmError::CommunicationErr {
+                    source: CommunicationError::RegionLengthTooBig { length, .. },
+                    ..
+                } => VmError::query_response_too_large(length, limit),
+                err => err,
+            })
+        }
+        _ => call_raw(instance, "query", &[env, msg], read_limits::RESULT_QUERY),
+    }
 }
 
 #[cfg(feature = "stargate")]
@@ -563,7 +580,48 @@ where
 
 /// Calls a function with the given arguments.
 /// The exported function must return exactly one result (an offset to the result Region).
-pub(crate) fn call_raw<A, S, Q>(
+///
+/// Every argument is copied into a newly allocated Region and the export is called
+/// with the Region pointers. The result is read from Wasm memory with a maximum
+/// length of `result_max_length` and freed afterwards.
+///
+/// This is exported with the `custom_entry_points` feature to allow calling
+/// entry points unknown to this crate. In this case the caller is responsible for
+/// setting the readonly storage flag via [`Instance::set_storage_readonly`].
+pub fn call_raw<A, S, Q>(
+    instance: &mut Instance<A, S, Q>,
+    name: &str,
+    args: &[&[u8]],
+    result_max_length: usize,
+) -> VmResult<Vec<u8>>
+where
+    A: BackendApi + 'static,
+    S: Storage + 'static,
+    Q: Querier + 'static,
+{
+    #[cfg(feature = "tracing")]
+    let span =
+        tracing::info_span!("call", entry_point = name, gas_used = tracing::field::Empty).entered();
+    let start = Instant::now();
+    let gas_before = instance.get_gas_left();
+    // every call starts with an empty query cache
+    instance.clear_query_cache();
+
+    let result = call_raw_impl(instance, name, args, result_max_length);
+    let gas_used = gas_before.saturating_sub(instance.get_gas_left());
+    instance.record_entry_point(name, start.elapsed(), gas_used);
+
+    #[cfg(feature = "log")]
+    if let Err(VmError::GasDepletion { .. }) = &result {
+        crate::logging::vm_log!(warn, "Ran out of gas in entry point {}", name);
+    }
+
+    #[cfg(feature = "tracing")]
+    span.record("gas_used", &gas_used);
+    result
+}
+
+fn call_raw_impl<A, S, Q>(
     instance: &mut Instance<A, S, Q>,
     name: &str,
     args: &[&[u8]],
@@ -591,7 +649,9 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::testing::{mock_env, mock_info, mock_instance};
+    use crate::testing::{
+        mock_env, mock_info, mock_instance, mock_instance_with_options, MockInstanceOptions,
+    };
     use cosmwasm_std::{coins, Empty};
 
     static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
@@ -735,6 +795,60 @@ mod tests {
         assert_eq!(query_response.as_slice(), b"{\"verifier\":\"verifies\"}");
     }
 
+    #[test]
+    fn call_query_fails_for_too_large_response() {
+        let mut instance = mock_instance_with_options(
+            CONTRACT,
+            MockInstanceOptions {
+                query_response_limit: Some(Size(10)),
+                ..Default::default()
+            },
+        );
+
+        // init
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        // query
+        let msg = br#"{"verifier":{}}"#;
+        let err = call_query(&mut instance, &mock_env(), msg).unwrap_err();
+        match err {
+            VmError::QueryResponseTooLarge {
+                length, max_length, ..
+            } => {
+                assert_eq!(length, 41);
+                assert_eq!(max_length, 10);
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn call_raw_works_for_any_export() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+
+        // init
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        // query
+        instance.set_storage_readonly(true);
+        let env = to_vec(&mock_env()).unwrap();
+        let msg: &[u8] = br#"{"verifier":{}}"#;
+        let data = call_raw(&mut instance, "query", &[env.as_slice(), msg], 1024).unwrap();
+        assert_eq!(data, br#"{"ok":"eyJ2ZXJpZmllciI6InZlcmlmaWVzIn0="}"#);
+
+        // unknown export
+        let err = call_raw(&mut instance, "cron", &[env.as_slice()], 1024).unwrap_err();
+        assert!(matches!(err, VmError::ResolveErr { .. }));
+    }
+
     #[cfg(feature = "stargate")]
     mod ibc {
         use super::*;
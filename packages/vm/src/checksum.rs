@@ -1,6 +1,6 @@
 use std::fmt;
 
-use sha2::{Digest, Sha256};
+use cosmwasm_crypto::sha256;
 
 use crate::errors::VmError;
 
@@ -14,7 +14,7 @@ pub struct Checksum([u8; 32]);
 
 impl Checksum {
     pub fn generate(wasm: &[u8]) -> Self {
-        Checksum(Sha256::digest(wasm).into())
+        Checksum(sha256(wasm))
     }
 
     /// Creates a lowercase hex encoded copy of this checksum.
@@ -5,7 +5,9 @@ use std::collections::HashSet;
 use crate::capabilities::required_capabilities_from_module;
 use crate::errors::{VmError, VmResult};
 use crate::limited::LimitedDisplay;
+use crate::size::Size;
 use crate::static_analysis::{deserialize_wasm, ExportInfo};
+use crate::wasm_backend::{CompilerBackend, WasmerBackend};
 
 /// Lists all imports we provide upon instantiating the instance in Instance::from_module()
 /// This should be updated when new imports are added
@@ -27,6 +29,8 @@ const SUPPORTED_IMPORTS: &[&str] = &[
     "env.db_scan",
     #[cfg(feature = "iterator")]
     "env.db_next",
+    #[cfg(feature = "iterator")]
+    "env.db_next_batch",
 ];
 
 /// Lists all entry points we expect to be present when calling a contract.
@@ -61,6 +65,104 @@ pub fn check_wasm(wasm_code: &[u8], available_capabilities: &HashSet<String>) ->
     Ok(())
 }
 
+/// Chain specific requirements for [`check_wasm_with_options`] and [`validate_contract`]
+#[derive(Clone, Debug, Default)]
+pub struct ValidationOptions {
+    pub available_capabilities: HashSet<String>,
+    /// Maximum size of the Wasm blob in bytes. Use `None` for no limit.
+    pub max_wasm_size: Option<Size>,
+    /// Exports a contract must provide in addition to the ones required by the VM,
+    /// e.g. "migrate" for chains that only accept migratable contracts.
+    pub required_exports: BTreeSet<String>,
+}
+
+/// Like [`check_wasm`] but also enforces the size limit and the chain specific
+/// required exports of the given options
+pub fn check_wasm_with_options(wasm_code: &[u8], options: &ValidationOptions) -> VmResult<()> {
+    check_wasm_size(wasm_code, options.max_wasm_size)?;
+    check_wasm(wasm_code, &options.available_capabilities)?;
+    let module = deserialize_wasm(wasm_code)?;
+    check_wasm_required_exports(&module, &options.required_exports)?;
+    Ok(())
+}
+
+/// The result of [`validate_contract`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// All problems found in the contract, in the order the checks ran
+    pub errors: Vec<String>,
+    /// The names of all exported functions, including entry points like "execute"
+    pub exports: BTreeSet<String>,
+    /// The capabilities required by the contract
+    pub required_capabilities: BTreeSet<String>,
+}
+
+impl ValidationReport {
+    /// Returns true iff no problems were found
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Runs all static checks as well as a compilation with the deterministic middlewares
+/// (which detects floats and unsupported Wasm features) on a contract.
+///
+/// In contrast to [`check_wasm`], this does not stop at the first problem but collects
+/// all of them in the returned report.
+pub fn validate_contract(wasm_code: &[u8], options: &ValidationOptions) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    if let Err(err) = check_wasm_size(wasm_code, options.max_wasm_size) {
+        report.errors.push(err.to_string());
+    }
+
+    let module = match deserialize_wasm(wasm_code) {
+        Ok(module) => module,
+        Err(err) => {
+            // Nothing else can be checked without a valid module
+            report.errors.push(err.to_string());
+            return report;
+        }
+    };
+    report.exports = module.exported_function_names(None).into_iter().collect();
+    report.required_capabilities = required_capabilities_from_module(&module)
+        .into_iter()
+        .collect();
+
+    let checks = [
+        check_wasm_memories(&module),
+        check_interface_version(&module),
+        check_wasm_exports(&module),
+        check_wasm_imports(&module, SUPPORTED_IMPORTS),
+        check_wasm_capabilities(&module, &options.available_capabilities),
+        check_wasm_required_exports(&module, &options.required_exports),
+    ];
+    report.errors.extend(
+        checks
+            .into_iter()
+            .filter_map(|result| result.err().map(|err| err.to_string())),
+    );
+
+    if let Err(err) = WasmerBackend.compile(wasm_code, None) {
+        report.errors.push(err.to_string());
+    }
+
+    report
+}
+
+fn check_wasm_size(wasm_code: &[u8], max_wasm_size: Option<Size>) -> VmResult<()> {
+    match max_wasm_size {
+        Some(Size(max_wasm_size)) if wasm_code.len() > max_wasm_size => {
+            Err(VmError::static_validation_err(format!(
+                "Wasm contract size of {} bytes exceeds the limit of {} bytes",
+                wasm_code.len(),
+                max_wasm_size
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
 fn check_wasm_memories(module: &Module) -> VmResult<()> {
     let section = match module.memory_section() {
         Some(section) => section,
@@ -140,6 +242,24 @@ fn check_wasm_exports(module: &Module) -> VmResult<()> {
     Ok(())
 }
 
+fn check_wasm_required_exports(
+    module: &Module,
+    required_exports: &BTreeSet<String>,
+) -> VmResult<()> {
+    let available_exports: HashSet<String> = module.exported_function_names(None);
+    let missing: BTreeSet<_> = required_exports
+        .iter()
+        .filter(|export| !available_exports.contains(*export))
+        .collect();
+    if !missing.is_empty() {
+        return Err(VmError::static_validation_err(format!(
+            "Wasm contract is missing exports required by the chain: {}",
+            missing.to_string_limited(200)
+        )));
+    }
+    Ok(())
+}
+
 /// Checks if the import requirements of the contract are satisfied.
 /// When this is not the case, we either have an incompatibility between contract and VM
 /// or a error in the contract.
@@ -213,6 +333,39 @@ mod tests {
         check_wasm(CONTRACT, &default_capabilities()).unwrap();
     }
 
+    #[test]
+    fn validate_contract_works() {
+        let options = ValidationOptions {
+            available_capabilities: default_capabilities(),
+            ..ValidationOptions::default()
+        };
+        let report = validate_contract(CONTRACT, &options);
+        assert!(report.is_valid(), "{:?}", report.errors);
+        assert!(report.exports.contains("instantiate"));
+        assert!(report.exports.contains("execute"));
+    }
+
+    #[test]
+    fn validate_contract_collects_all_errors() {
+        let options = ValidationOptions {
+            available_capabilities: HashSet::new(),
+            max_wasm_size: Some(Size(100)),
+            required_exports: BTreeSet::new(),
+        };
+        let report = validate_contract(CONTRACT_0_15, &options);
+        assert!(!report.is_valid());
+        assert!(report.errors.len() >= 2, "{:?}", report.errors);
+        assert!(report.errors[0].contains("exceeds the limit of 100 bytes"));
+        assert!(report
+            .errors
+            .iter()
+            .any(|err| err.contains("unknown interface_version_* marker export")));
+
+        let report = validate_contract(b"not wasm", &options);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.exports.is_empty());
+    }
+
     #[test]
     fn check_wasm_old_contract() {
         match check_wasm(CONTRACT_0_15, &default_capabilities()) {
@@ -541,6 +694,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn check_wasm_with_options_works() {
+        let mut options = ValidationOptions {
+            available_capabilities: default_capabilities(),
+            ..ValidationOptions::default()
+        };
+        check_wasm_with_options(CONTRACT, &options).unwrap();
+
+        options.required_exports.insert("migrate".to_string());
+        check_wasm_with_options(CONTRACT, &options).unwrap();
+        assert!(validate_contract(CONTRACT, &options).is_valid());
+
+        options
+            .required_exports
+            .insert("ibc_channel_open".to_string());
+        options.required_exports.insert("foo".to_string());
+        let report = validate_contract(CONTRACT, &options);
+        assert_eq!(
+            report.errors,
+            vec!["Error during static Wasm validation: Wasm contract is missing exports required by the chain: {\"foo\", \"ibc_channel_open\"}"]
+        );
+        match check_wasm_with_options(CONTRACT, &options) {
+            Err(VmError::StaticValidationErr { msg, .. }) => assert_eq!(
+                msg,
+                "Wasm contract is missing exports required by the chain: {\"foo\", \"ibc_channel_open\"}"
+            ),
+            Err(e) => panic!("Unexpected error {:?}", e),
+            Ok(_) => panic!("This must not succeeed"),
+        }
+    }
+
     #[test]
     fn check_wasm_imports_ok() {
         let wasm = wat::parse_str(
@@ -1,13 +1,16 @@
 //! Internal details to be used by instance.rs only
 use std::borrow::{Borrow, BorrowMut};
+use std::collections::{BTreeMap, HashMap};
 use std::ptr::NonNull;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use wasmer::{HostEnvInitError, Instance as WasmerInstance, Memory, Val, WasmerEnv};
 use wasmer_middlewares::metering::{get_remaining_points, set_remaining_points, MeteringPoints};
 
 use crate::backend::{BackendApi, GasInfo, Querier, Storage};
 use crate::errors::{VmError, VmResult};
+use crate::size::Size;
 
 /// Never can never be instantiated.
 /// Replace this with the [never primitive type](https://doc.rust-lang.org/std/primitive.never.html) when stable.
@@ -16,7 +19,7 @@ pub enum Never {}
 
 /** gas config data */
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct GasConfig {
     /// Gas costs of VM (not Backend) provided functionality
     /// secp256k1 signature verification cost
@@ -29,13 +32,23 @@ pub struct GasConfig {
     pub ed25519_batch_verify_cost: u64,
     /// ed25519 batch signature verification cost (single public key)
     pub ed25519_batch_verify_one_pubkey_cost: u64,
+    /// Base cost of answering a `query_chain` request from the query cache,
+    /// see [`InstanceOptions::memoize_queries`](crate::InstanceOptions::memoize_queries)
+    pub query_cache_hit_base_cost: u64,
+    /// Cost per byte of a serialized query response answered from the query cache
+    pub query_cache_hit_per_byte_cost: u64,
+    /// Gas costs of storage access. If set, those replace the cost reported by the backend
+    /// for every storage operation. Use `None` to keep the backend's pricing.
+    pub storage: Option<StorageGasConfig>,
 }
 
-impl Default for GasConfig {
-    fn default() -> Self {
+impl GasConfig {
+    /// The default gas config. This is the same as `GasConfig::default()` but
+    /// can be used in const contexts.
+    pub const DEFAULT: GasConfig = {
         // Target is 10^12 per millisecond (see GAS.md), i.e. 10^9 gas per µ second.
         const GAS_PER_US: u64 = 1_000_000_000;
-        Self {
+        GasConfig {
             // ~154 us in crypto benchmarks
             secp256k1_verify_cost: 154 * GAS_PER_US,
             // ~162 us in crypto benchmarks
@@ -46,7 +59,56 @@ impl Default for GasConfig {
             // From https://docs.rs/ed25519-zebra/2.2.0/ed25519_zebra/batch/index.html
             ed25519_batch_verify_cost: 63 * GAS_PER_US / 2,
             ed25519_batch_verify_one_pubkey_cost: 63 * GAS_PER_US / 4,
+            // A hash map lookup and copying the response into Wasm memory
+            query_cache_hit_base_cost: GAS_PER_US,
+            query_cache_hit_per_byte_cost: GAS_PER_US / 1000,
+            // Storage is priced by the backend by default
+            storage: None,
         }
+    };
+
+    /// Cost of answering a query with the given serialized response length from the query cache
+    pub fn query_cache_hit_cost(&self, length: usize) -> u64 {
+        self.query_cache_hit_per_byte_cost
+            .saturating_mul(length as u64)
+            .saturating_add(self.query_cache_hit_base_cost)
+    }
+}
+
+impl Default for GasConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Gas costs of storage access, see [`GasConfig::storage`]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct StorageGasConfig {
+    /// Base cost of reading a storage entry (db_read, db_scan, db_next)
+    pub read_base_cost: u64,
+    /// Cost per byte of key and value read from storage
+    pub read_per_byte_cost: u64,
+    /// Base cost of writing a storage entry
+    pub write_base_cost: u64,
+    /// Cost per byte of key and value written to storage
+    pub write_per_byte_cost: u64,
+    /// Cost of removing a storage entry
+    pub delete_cost: u64,
+}
+
+impl StorageGasConfig {
+    /// Cost of reading an entry with the given total length of key and value
+    pub fn read_cost(&self, length: usize) -> u64 {
+        self.read_per_byte_cost
+            .saturating_mul(length as u64)
+            .saturating_add(self.read_base_cost)
+    }
+
+    /// Cost of writing an entry with the given total length of key and value
+    pub fn write_cost(&self, length: usize) -> u64 {
+        self.write_per_byte_cost
+            .saturating_mul(length as u64)
+            .saturating_add(self.write_base_cost)
     }
 }
 
@@ -72,13 +134,32 @@ impl GasState {
     }
 }
 
+/// Time and gas spent in one entry point, summed up over all calls
+/// made while profiling was enabled.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct EntryPointProfile {
+    /// Number of calls into the entry point
+    pub calls: u64,
+    /// Wall clock time spent in the entry point, including host function calls
+    pub time: Duration,
+    /// Gas consumed from the instance's gas limit, including gas used in host functions
+    pub gas_used: u64,
+}
+
+/// Collected by an instance with profiling enabled.
+/// See [`Instance::set_profiling`](crate::Instance::set_profiling).
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ProfileReport {
+    /// Profiles by entry point name, e.g. "execute" or "query"
+    pub entry_points: BTreeMap<String, EntryPointProfile>,
+    /// Number of calls by host function name, e.g. "db_read"
+    pub host_calls: BTreeMap<String, u64>,
+}
+
 /// A environment that provides access to the ContextData.
 /// The environment is clonable but clones access the same underlying data.
 pub struct Environment<A: BackendApi, S: Storage, Q: Querier> {
-    pub api: A,
-    pub print_debug: bool,
-    pub gas_config: GasConfig,
-    data: Arc<RwLock<ContextData<S, Q>>>,
+    data: Arc<RwLock<ContextData<A, S, Q>>>,
 }
 
 unsafe impl<A: BackendApi, S: Storage, Q: Querier> Send for Environment<A, S, Q> {}
@@ -88,9 +169,6 @@ unsafe impl<A: BackendApi, S: Storage, Q: Querier> Sync for Environment<A, S, Q>
 impl<A: BackendApi, S: Storage, Q: Querier> Clone for Environment<A, S, Q> {
     fn clone(&self) -> Self {
         Environment {
-            api: self.api,
-            print_debug: self.print_debug,
-            gas_config: self.gas_config.clone(),
             data: self.data.clone(),
         }
     }
@@ -105,16 +183,77 @@ impl<A: BackendApi, S: Storage, Q: Querier> WasmerEnv for Environment<A, S, Q> {
 impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
     pub fn new(api: A, gas_limit: u64, print_debug: bool) -> Self {
         Environment {
-            api,
-            print_debug,
-            gas_config: GasConfig::default(),
-            data: Arc::new(RwLock::new(ContextData::new(gas_limit))),
+            data: Arc::new(RwLock::new(ContextData::new(api, gas_limit, print_debug))),
         }
     }
 
+    pub fn api(&self) -> A {
+        self.with_context_data(|context_data| context_data.api)
+    }
+
+    pub fn print_debug(&self) -> bool {
+        self.with_context_data(|context_data| context_data.print_debug)
+    }
+
+    pub fn gas_config(&self) -> GasConfig {
+        self.with_context_data(|context_data| context_data.gas_config)
+    }
+
+    pub fn set_gas_config(&self, gas_config: GasConfig) {
+        self.with_context_data_mut(|context_data| {
+            context_data.gas_config = gas_config;
+        })
+    }
+
+    /// Returns the maximum size of query responses in bytes, if set
+    pub fn query_response_limit(&self) -> Option<Size> {
+        self.with_context_data(|context_data| context_data.query_response_limit)
+    }
+
+    pub fn set_query_response_limit(&self, limit: Option<Size>) {
+        self.with_context_data_mut(|context_data| {
+            context_data.query_response_limit = limit;
+        })
+    }
+
+    /// Enables or disables the query cache. Any cached responses are dropped.
+    pub fn set_memoize_queries(&self, enabled: bool) {
+        self.with_context_data_mut(|context_data| {
+            context_data.query_cache = if enabled { Some(HashMap::new()) } else { None };
+        })
+    }
+
+    /// Returns the cached response for the given raw query request, if any
+    pub fn cached_query_response(&self, request: &[u8]) -> Option<Vec<u8>> {
+        self.with_context_data(|context_data| {
+            context_data
+                .query_cache
+                .as_ref()
+                .and_then(|cache| cache.get(request).cloned())
+        })
+    }
+
+    /// Stores a serialized query response. This is a no-op when the query cache is disabled.
+    pub fn cache_query_response(&self, request: Vec<u8>, response: Vec<u8>) {
+        self.with_context_data_mut(|context_data| {
+            if let Some(cache) = context_data.query_cache.as_mut() {
+                cache.insert(request, response);
+            }
+        })
+    }
+
+    /// Drops all cached query responses, keeping the cache enabled if it was enabled
+    pub fn clear_query_cache(&self) {
+        self.with_context_data_mut(|context_data| {
+            if let Some(cache) = context_data.query_cache.as_mut() {
+                cache.clear();
+            }
+        })
+    }
+
     fn with_context_data_mut<C, R>(&self, callback: C) -> R
     where
-        C: FnOnce(&mut ContextData<S, Q>) -> R,
+        C: FnOnce(&mut ContextData<A, S, Q>) -> R,
     {
         let mut guard = self.data.as_ref().write().unwrap();
         let context_data = guard.borrow_mut();
@@ -123,7 +262,7 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
 
     fn with_context_data<C, R>(&self, callback: C) -> R
     where
-        C: FnOnce(&ContextData<S, Q>) -> R,
+        C: FnOnce(&ContextData<A, S, Q>) -> R,
     {
         let guard = self.data.as_ref().read().unwrap();
         let context_data = guard.borrow();
@@ -301,6 +440,8 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
             context_data.storage = Some(storage);
             context_data.querier = Some(querier);
         });
+        // responses of the previous querier must not leak into the new one
+        self.clear_query_cache();
     }
 
     /// Returns the original storage and querier as owned instances, and closes any remaining
@@ -310,24 +451,91 @@ impl<A: BackendApi, S: Storage, Q: Querier> Environment<A, S, Q> {
             (context_data.storage.take(), context_data.querier.take())
         })
     }
+
+    /// Enables or disables profiling. Enabling starts with an empty report,
+    /// disabling drops the collected data.
+    pub fn set_profiling(&self, enabled: bool) {
+        self.with_context_data_mut(|context_data| {
+            context_data.profile = if enabled {
+                Some(ProfileReport::default())
+            } else {
+                None
+            };
+        })
+    }
+
+    /// Returns a copy of the data collected so far, if profiling is enabled
+    pub fn profile_report(&self) -> Option<ProfileReport> {
+        self.with_context_data(|context_data| context_data.profile.clone())
+    }
+
+    /// Counts a call of the given host function. This is a no-op when profiling is disabled.
+    pub fn record_host_call(&self, name: &str) {
+        self.with_context_data_mut(|context_data| {
+            if let Some(profile) = context_data.profile.as_mut() {
+                *profile.host_calls.entry(name.to_string()).or_default() += 1;
+            }
+        })
+    }
+
+    /// Adds a finished entry point call to the profile. This is a no-op when profiling is disabled.
+    pub fn record_entry_point(&self, name: &str, time: Duration, gas_used: u64) {
+        self.with_context_data_mut(|context_data| {
+            if let Some(profile) = context_data.profile.as_mut() {
+                let entry = profile.entry_points.entry(name.to_string()).or_default();
+                entry.calls += 1;
+                entry.time += time;
+                entry.gas_used += gas_used;
+            }
+        })
+    }
+
+    /// Replaces the API and the per-call settings of an environment that was created
+    /// ahead of time. The gas state is reset to the given limit.
+    ///
+    /// This must only be used for instances that were never executed, since leftover
+    /// state of a previous call is not cleaned up.
+    pub fn reset(&self, api: A, gas_limit: u64, print_debug: bool) {
+        self.with_context_data_mut(|context_data| {
+            context_data.api = api;
+            context_data.print_debug = print_debug;
+            context_data.gas_state = GasState::with_limit(gas_limit);
+            context_data.storage_readonly = true;
+        });
+        self.set_gas_left(gas_limit);
+    }
 }
 
-pub struct ContextData<S: Storage, Q: Querier> {
+pub struct ContextData<A: BackendApi, S: Storage, Q: Querier> {
+    api: A,
+    print_debug: bool,
+    gas_config: GasConfig,
+    query_response_limit: Option<Size>,
+    /// Serialized `query_chain` responses by raw request, if memoization is enabled
+    query_cache: Option<HashMap<Vec<u8>, Vec<u8>>>,
     gas_state: GasState,
     storage: Option<S>,
     storage_readonly: bool,
     querier: Option<Q>,
+    /// Collected data if profiling is enabled
+    profile: Option<ProfileReport>,
     /// A non-owning link to the wasmer instance
     wasmer_instance: Option<NonNull<WasmerInstance>>,
 }
 
-impl<S: Storage, Q: Querier> ContextData<S, Q> {
-    pub fn new(gas_limit: u64) -> Self {
-        ContextData::<S, Q> {
+impl<A: BackendApi, S: Storage, Q: Querier> ContextData<A, S, Q> {
+    pub fn new(api: A, gas_limit: u64, print_debug: bool) -> Self {
+        ContextData::<A, S, Q> {
+            api,
+            print_debug,
+            gas_config: GasConfig::default(),
+            query_response_limit: None,
+            query_cache: None,
             gas_state: GasState::with_limit(gas_limit),
             storage: None,
             storage_readonly: true,
             querier: None,
+            profile: None,
             wasmer_instance: None,
         }
     }
@@ -103,6 +103,13 @@ pub enum VmError {
         #[cfg(feature = "backtraces")]
         backtrace: Backtrace,
     },
+    #[error("Query response too large. Got: {length} bytes; limit: {max_length} bytes")]
+    QueryResponseTooLarge {
+        length: usize,
+        max_length: usize,
+        #[cfg(feature = "backtraces")]
+        backtrace: Backtrace,
+    },
     #[error("Error resolving Wasm function: {}", msg)]
     ResolveErr {
         msg: String,
@@ -259,6 +266,15 @@ impl VmError {
         }
     }
 
+    pub(crate) fn query_response_too_large(length: usize, max_length: usize) -> Self {
+        VmError::QueryResponseTooLarge {
+            length,
+            max_length,
+            #[cfg(feature = "backtraces")]
+            backtrace: Backtrace::capture(),
+        }
+    }
+
     pub(crate) fn resolve_err(msg: impl Into<String>) -> Self {
         VmError::ResolveErr {
             msg: msg.into(),
@@ -519,6 +535,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn query_response_too_large_works() {
+        let error = VmError::query_response_too_large(300, 200);
+        match error {
+            VmError::QueryResponseTooLarge {
+                length, max_length, ..
+            } => {
+                assert_eq!(length, 300);
+                assert_eq!(max_length, 200);
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
     #[test]
     fn resolve_err_works() {
         let error = VmError::resolve_err("function has different signature");
@@ -11,6 +11,7 @@ use cosmwasm_crypto::{
 
 #[cfg(feature = "iterator")]
 use cosmwasm_std::Order;
+use cosmwasm_std::{Binary, ContractResult, SystemError, SystemResult};
 
 use crate::backend::{BackendApi, BackendError, Querier, Storage};
 use crate::conversion::{ref_to_u32, to_u32};
@@ -23,6 +24,7 @@ use crate::sections::decode_sections;
 #[allow(unused_imports)]
 use crate::sections::encode_sections;
 use crate::serde::to_vec;
+use crate::size::Size;
 use crate::GasInfo;
 
 /// A kibi (kilo binary)
@@ -57,6 +59,11 @@ const MAX_LENGTH_DEBUG: usize = 2 * MI;
 /// Max length for an abort message
 const MAX_LENGTH_ABORT: usize = 2 * MI;
 
+/// Max number of records returned by a single db_next_batch call.
+/// This is an arbitrary value limiting the size of the result region.
+#[cfg(feature = "iterator")]
+const MAX_DB_NEXT_BATCH_ITEMS: u32 = 100;
+
 // Import implementations
 //
 // This block of do_* prefixed functions is tailored for Wasmer's
@@ -64,14 +71,36 @@ const MAX_LENGTH_ABORT: usize = 2 * MI;
 // argument and cannot capture other variables. Thus everything is accessed
 // through the env.
 
+/// Applies the storage costs of the [`GasConfig`](crate::GasConfig), if set.
+/// The configured cost replaces the cost reported by the backend. Gas the backend
+/// used externally has already been consumed and is still accounted for.
+fn storage_gas(reported: GasInfo, configured_cost: Option<u64>) -> GasInfo {
+    match configured_cost {
+        Some(cost) => GasInfo::new(cost, reported.externally_used),
+        None => reported,
+    }
+}
+
 /// Reads a storage entry from the VM's storage into Wasm memory
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_db_read<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     key_ptr: u32,
 ) -> VmResult<u32> {
+    env.record_host_call("db_read");
     let key = read_region(&env.memory(), key_ptr, MAX_LENGTH_DB_KEY)?;
 
     let (result, gas_info) = env.with_storage_from_context::<_, _>(|store| Ok(store.get(&key)))?;
+    let value_len = match &result {
+        Ok(Some(value)) => value.len(),
+        _ => 0,
+    };
+    let gas_info = storage_gas(
+        gas_info,
+        env.gas_config()
+            .storage
+            .map(|storage| storage.read_cost(key.len() + value_len)),
+    );
     process_gas_info::<A, S, Q>(env, gas_info)?;
     let value = result?;
 
@@ -83,11 +112,13 @@ pub fn do_db_read<A: BackendApi, S: Storage, Q: Querier>(
 }
 
 /// Writes a storage entry from Wasm memory into the VM's storage
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_db_write<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     key_ptr: u32,
     value_ptr: u32,
 ) -> VmResult<()> {
+    env.record_host_call("db_write");
     if env.is_storage_readonly() {
         return Err(VmError::write_access_denied());
     }
@@ -97,16 +128,26 @@ pub fn do_db_write<A: BackendApi, S: Storage, Q: Querier>(
 
     let (result, gas_info) =
         env.with_storage_from_context::<_, _>(|store| Ok(store.set(&key, &value)))?;
+    let gas_info = storage_gas(
+        gas_info,
+        env.gas_config()
+            .storage
+            .map(|storage| storage.write_cost(key.len() + value.len())),
+    );
     process_gas_info::<A, S, Q>(env, gas_info)?;
     result?;
+    // The contract might query its own state, so cached responses can be outdated now
+    env.clear_query_cache();
 
     Ok(())
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_db_remove<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     key_ptr: u32,
 ) -> VmResult<()> {
+    env.record_host_call("db_remove");
     if env.is_storage_readonly() {
         return Err(VmError::write_access_denied());
     }
@@ -115,16 +156,24 @@ pub fn do_db_remove<A: BackendApi, S: Storage, Q: Querier>(
 
     let (result, gas_info) =
         env.with_storage_from_context::<_, _>(|store| Ok(store.remove(&key)))?;
+    let gas_info = storage_gas(
+        gas_info,
+        env.gas_config().storage.map(|storage| storage.delete_cost),
+    );
     process_gas_info(env, gas_info)?;
     result?;
+    // The contract might query its own state, so cached responses can be outdated now
+    env.clear_query_cache();
 
     Ok(())
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_addr_validate<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     source_ptr: u32,
 ) -> VmResult<u32> {
+    env.record_host_call("addr_validate");
     let source_data = read_region(&env.memory(), source_ptr, MAX_LENGTH_HUMAN_ADDRESS)?;
     if source_data.is_empty() {
         return write_to_contract::<A, S, Q>(env, b"Input is empty");
@@ -135,7 +184,7 @@ pub fn do_addr_validate<A: BackendApi, S: Storage, Q: Querier>(
         Err(_) => return write_to_contract::<A, S, Q>(env, b"Input is not valid UTF-8"),
     };
 
-    let (result, gas_info) = env.api.canonical_address(&source_string);
+    let (result, gas_info) = env.api().canonical_address(&source_string);
     process_gas_info::<A, S, Q>(env, gas_info)?;
     let canonical = match result {
         Ok(data) => data,
@@ -145,7 +194,7 @@ pub fn do_addr_validate<A: BackendApi, S: Storage, Q: Querier>(
         Err(err) => return Err(VmError::from(err)),
     };
 
-    let (result, gas_info) = env.api.human_address(&canonical);
+    let (result, gas_info) = env.api().human_address(&canonical);
     process_gas_info::<A, S, Q>(env, gas_info)?;
     let normalized = match result {
         Ok(addr) => addr,
@@ -162,11 +211,13 @@ pub fn do_addr_validate<A: BackendApi, S: Storage, Q: Querier>(
     Ok(0)
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_addr_canonicalize<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     source_ptr: u32,
     destination_ptr: u32,
 ) -> VmResult<u32> {
+    env.record_host_call("addr_canonicalize");
     let source_data = read_region(&env.memory(), source_ptr, MAX_LENGTH_HUMAN_ADDRESS)?;
     if source_data.is_empty() {
         return write_to_contract::<A, S, Q>(env, b"Input is empty");
@@ -177,7 +228,7 @@ pub fn do_addr_canonicalize<A: BackendApi, S: Storage, Q: Querier>(
         Err(_) => return write_to_contract::<A, S, Q>(env, b"Input is not valid UTF-8"),
     };
 
-    let (result, gas_info) = env.api.canonical_address(&source_string);
+    let (result, gas_info) = env.api().canonical_address(&source_string);
     process_gas_info::<A, S, Q>(env, gas_info)?;
     match result {
         Ok(canonical) => {
@@ -191,14 +242,16 @@ pub fn do_addr_canonicalize<A: BackendApi, S: Storage, Q: Querier>(
     }
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_addr_humanize<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     source_ptr: u32,
     destination_ptr: u32,
 ) -> VmResult<u32> {
+    env.record_host_call("addr_humanize");
     let canonical = read_region(&env.memory(), source_ptr, MAX_LENGTH_CANONICAL_ADDRESS)?;
 
-    let (result, gas_info) = env.api.human_address(&canonical);
+    let (result, gas_info) = env.api().human_address(&canonical);
     process_gas_info::<A, S, Q>(env, gas_info)?;
     match result {
         Ok(human) => {
@@ -212,17 +265,19 @@ pub fn do_addr_humanize<A: BackendApi, S: Storage, Q: Querier>(
     }
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_secp256k1_verify<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     hash_ptr: u32,
     signature_ptr: u32,
     pubkey_ptr: u32,
 ) -> VmResult<u32> {
+    env.record_host_call("secp256k1_verify");
     let hash = read_region(&env.memory(), hash_ptr, MESSAGE_HASH_MAX_LEN)?;
     let signature = read_region(&env.memory(), signature_ptr, ECDSA_SIGNATURE_LEN)?;
     let pubkey = read_region(&env.memory(), pubkey_ptr, ECDSA_PUBKEY_MAX_LEN)?;
 
-    let gas_info = GasInfo::with_cost(env.gas_config.secp256k1_verify_cost);
+    let gas_info = GasInfo::with_cost(env.gas_config().secp256k1_verify_cost);
     process_gas_info::<A, S, Q>(env, gas_info)?;
     let result = secp256k1_verify(&hash, &signature, &pubkey);
     Ok(result.map_or_else(
@@ -230,7 +285,9 @@ pub fn do_secp256k1_verify<A: BackendApi, S: Storage, Q: Querier>(
             CryptoError::InvalidHashFormat { .. }
             | CryptoError::InvalidPubkeyFormat { .. }
             | CryptoError::InvalidSignatureFormat { .. }
-            | CryptoError::GenericErr { .. } => err.code(),
+            | CryptoError::GenericErr { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::AggregationErr { .. } => err.code(),
             CryptoError::BatchErr { .. } | CryptoError::InvalidRecoveryParam { .. } => {
                 panic!("Error must not happen for this call")
             }
@@ -239,12 +296,14 @@ pub fn do_secp256k1_verify<A: BackendApi, S: Storage, Q: Querier>(
     ))
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_secp256k1_recover_pubkey<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     hash_ptr: u32,
     signature_ptr: u32,
     recover_param: u32,
 ) -> VmResult<u64> {
+    env.record_host_call("secp256k1_recover_pubkey");
     let hash = read_region(&env.memory(), hash_ptr, MESSAGE_HASH_MAX_LEN)?;
     let signature = read_region(&env.memory(), signature_ptr, ECDSA_SIGNATURE_LEN)?;
     let recover_param: u8 = match recover_param.try_into() {
@@ -252,7 +311,7 @@ pub fn do_secp256k1_recover_pubkey<A: BackendApi, S: Storage, Q: Querier>(
         Err(_) => return Ok((CryptoError::invalid_recovery_param().code() as u64) << 32),
     };
 
-    let gas_info = GasInfo::with_cost(env.gas_config.secp256k1_recover_pubkey_cost);
+    let gas_info = GasInfo::with_cost(env.gas_config().secp256k1_recover_pubkey_cost);
     process_gas_info::<A, S, Q>(env, gas_info)?;
     let result = secp256k1_recover_pubkey(&hash, &signature, recover_param);
     match result {
@@ -264,7 +323,9 @@ pub fn do_secp256k1_recover_pubkey<A: BackendApi, S: Storage, Q: Querier>(
             CryptoError::InvalidHashFormat { .. }
             | CryptoError::InvalidSignatureFormat { .. }
             | CryptoError::InvalidRecoveryParam { .. }
-            | CryptoError::GenericErr { .. } => Ok(to_high_half(err.code())),
+            | CryptoError::GenericErr { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::AggregationErr { .. } => Ok(to_high_half(err.code())),
             CryptoError::BatchErr { .. } | CryptoError::InvalidPubkeyFormat { .. } => {
                 panic!("Error must not happen for this call")
             }
@@ -272,24 +333,28 @@ pub fn do_secp256k1_recover_pubkey<A: BackendApi, S: Storage, Q: Querier>(
     }
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_ed25519_verify<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     message_ptr: u32,
     signature_ptr: u32,
     pubkey_ptr: u32,
 ) -> VmResult<u32> {
+    env.record_host_call("ed25519_verify");
     let message = read_region(&env.memory(), message_ptr, MAX_LENGTH_ED25519_MESSAGE)?;
     let signature = read_region(&env.memory(), signature_ptr, MAX_LENGTH_ED25519_SIGNATURE)?;
     let pubkey = read_region(&env.memory(), pubkey_ptr, EDDSA_PUBKEY_LEN)?;
 
-    let gas_info = GasInfo::with_cost(env.gas_config.ed25519_verify_cost);
+    let gas_info = GasInfo::with_cost(env.gas_config().ed25519_verify_cost);
     process_gas_info::<A, S, Q>(env, gas_info)?;
     let result = ed25519_verify(&message, &signature, &pubkey);
     Ok(result.map_or_else(
         |err| match err {
             CryptoError::InvalidPubkeyFormat { .. }
             | CryptoError::InvalidSignatureFormat { .. }
-            | CryptoError::GenericErr { .. } => err.code(),
+            | CryptoError::GenericErr { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::AggregationErr { .. } => err.code(),
             CryptoError::BatchErr { .. }
             | CryptoError::InvalidHashFormat { .. }
             | CryptoError::InvalidRecoveryParam { .. } => {
@@ -300,12 +365,14 @@ pub fn do_ed25519_verify<A: BackendApi, S: Storage, Q: Querier>(
     ))
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_ed25519_batch_verify<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     messages_ptr: u32,
     signatures_ptr: u32,
     public_keys_ptr: u32,
 ) -> VmResult<u32> {
+    env.record_host_call("ed25519_batch_verify");
     let messages = read_region(
         &env.memory(),
         messages_ptr,
@@ -327,11 +394,11 @@ pub fn do_ed25519_batch_verify<A: BackendApi, S: Storage, Q: Querier>(
     let public_keys = decode_sections(&public_keys);
 
     let gas_cost = if public_keys.len() == 1 {
-        env.gas_config.ed25519_batch_verify_one_pubkey_cost
+        env.gas_config().ed25519_batch_verify_one_pubkey_cost
     } else {
-        env.gas_config.ed25519_batch_verify_cost
+        env.gas_config().ed25519_batch_verify_cost
     } * signatures.len() as u64;
-    let gas_info = GasInfo::with_cost(max(gas_cost, env.gas_config.ed25519_verify_cost));
+    let gas_info = GasInfo::with_cost(max(gas_cost, env.gas_config().ed25519_verify_cost));
     process_gas_info::<A, S, Q>(env, gas_info)?;
     let result = ed25519_batch_verify(&messages, &signatures, &public_keys);
     Ok(result.map_or_else(
@@ -339,7 +406,9 @@ pub fn do_ed25519_batch_verify<A: BackendApi, S: Storage, Q: Querier>(
             CryptoError::BatchErr { .. }
             | CryptoError::InvalidPubkeyFormat { .. }
             | CryptoError::InvalidSignatureFormat { .. }
-            | CryptoError::GenericErr { .. } => err.code(),
+            | CryptoError::GenericErr { .. }
+            | CryptoError::InvalidPoint { .. }
+            | CryptoError::AggregationErr { .. } => err.code(),
             CryptoError::InvalidHashFormat { .. } | CryptoError::InvalidRecoveryParam { .. } => {
                 panic!("Error must not happen for this call")
             }
@@ -350,11 +419,13 @@ pub fn do_ed25519_batch_verify<A: BackendApi, S: Storage, Q: Querier>(
 
 /// Prints a debug message to console.
 /// This does not charge gas, so debug printing should be disabled when used in a blockchain module.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_debug<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     message_ptr: u32,
 ) -> VmResult<()> {
-    if env.print_debug {
+    env.record_host_call("debug");
+    if env.print_debug() {
         let message_data = read_region(&env.memory(), message_ptr, MAX_LENGTH_DEBUG)?;
         let msg = String::from_utf8_lossy(&message_data);
         println!("{}", msg);
@@ -363,10 +434,12 @@ pub fn do_debug<A: BackendApi, S: Storage, Q: Querier>(
 }
 
 /// Aborts the contract and shows the given error message
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_abort<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     message_ptr: u32,
 ) -> VmResult<()> {
+    env.record_host_call("abort");
     let message_data = read_region(&env.memory(), message_ptr, MAX_LENGTH_ABORT)?;
     let msg = String::from_utf8_lossy(&message_data);
     Err(VmError::aborted(msg))
@@ -387,28 +460,56 @@ fn write_to_contract<A: BackendApi, S: Storage, Q: Querier>(
     Ok(target_ptr)
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_query_chain<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     request_ptr: u32,
 ) -> VmResult<u32> {
+    env.record_host_call("query_chain");
     let request = read_region(&env.memory(), request_ptr, MAX_LENGTH_QUERY_CHAIN_REQUEST)?;
+    if let Some(serialized) = env.cached_query_response(&request) {
+        let gas_info = GasInfo::with_cost(env.gas_config().query_cache_hit_cost(serialized.len()));
+        process_gas_info::<A, S, Q>(env, gas_info)?;
+        return write_to_contract::<A, S, Q>(env, &serialized);
+    }
 
     let gas_remaining = env.get_gas_left();
     let (result, gas_info) = env.with_querier_from_context::<_, _>(|querier| {
         Ok(querier.query_raw(&request, gas_remaining))
     })?;
     process_gas_info::<A, S, Q>(env, gas_info)?;
-    let serialized = to_vec(&result?)?;
-    write_to_contract::<A, S, Q>(env, &serialized)
+    // Oversized responses are reported to the contract, which can handle them like any other
+    // failing query
+    let result = match (env.query_response_limit(), result?) {
+        (Some(Size(max_length)), SystemResult::Ok(ContractResult::Ok(data)))
+            if data.len() > max_length =>
+        {
+            SystemResult::Err(SystemError::InvalidResponse {
+                error: format!(
+                    "Query response of {} bytes exceeds the limit of {} bytes",
+                    data.len(),
+                    max_length
+                ),
+                response: Binary::default(),
+            })
+        }
+        (_, result) => result,
+    };
+    let serialized = to_vec(&result)?;
+    let response_ptr = write_to_contract::<A, S, Q>(env, &serialized)?;
+    env.cache_query_response(request, serialized);
+    Ok(response_ptr)
 }
 
 #[cfg(feature = "iterator")]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_db_scan<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     start_ptr: u32,
     end_ptr: u32,
     order: i32,
 ) -> VmResult<u32> {
+    env.record_host_call("db_scan");
     let start = maybe_read_region(&env.memory(), start_ptr, MAX_LENGTH_DB_KEY)?;
     let end = maybe_read_region(&env.memory(), end_ptr, MAX_LENGTH_DB_KEY)?;
     let order: Order = order
@@ -418,18 +519,37 @@ pub fn do_db_scan<A: BackendApi, S: Storage, Q: Querier>(
     let (result, gas_info) = env.with_storage_from_context::<_, _>(|store| {
         Ok(store.scan(start.as_deref(), end.as_deref(), order))
     })?;
+    let bounds_len = start.as_ref().map_or(0, Vec::len) + end.as_ref().map_or(0, Vec::len);
+    let gas_info = storage_gas(
+        gas_info,
+        env.gas_config()
+            .storage
+            .map(|storage| storage.read_cost(bounds_len)),
+    );
     process_gas_info::<A, S, Q>(env, gas_info)?;
     let iterator_id = result?;
     Ok(iterator_id)
 }
 
 #[cfg(feature = "iterator")]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
 pub fn do_db_next<A: BackendApi, S: Storage, Q: Querier>(
     env: &Environment<A, S, Q>,
     iterator_id: u32,
 ) -> VmResult<u32> {
+    env.record_host_call("db_next");
     let (result, gas_info) =
         env.with_storage_from_context::<_, _>(|store| Ok(store.next(iterator_id)))?;
+    let record_len = match &result {
+        Ok(Some((key, value))) => key.len() + value.len(),
+        _ => 0,
+    };
+    let gas_info = storage_gas(
+        gas_info,
+        env.gas_config()
+            .storage
+            .map(|storage| storage.read_cost(record_len)),
+    );
     process_gas_info::<A, S, Q>(env, gas_info)?;
 
     // Empty key will later be treated as _no more element_.
@@ -439,6 +559,44 @@ pub fn do_db_next<A: BackendApi, S: Storage, Q: Querier>(
     write_to_contract::<A, S, Q>(env, &out_data)
 }
 
+#[cfg(feature = "iterator")]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+pub fn do_db_next_batch<A: BackendApi, S: Storage, Q: Querier>(
+    env: &Environment<A, S, Q>,
+    iterator_id: u32,
+    max_items: u32,
+) -> VmResult<u32> {
+    env.record_host_call("db_next_batch");
+    let max_items = max_items.min(MAX_DB_NEXT_BATCH_ITEMS);
+    let mut sections = Vec::<Vec<u8>>::new();
+    for _ in 0..max_items {
+        let (result, gas_info) =
+            env.with_storage_from_context::<_, _>(|store| Ok(store.next(iterator_id)))?;
+        let record_len = match &result {
+            Ok(Some((key, value))) => key.len() + value.len(),
+            _ => 0,
+        };
+        let gas_info = storage_gas(
+            gas_info,
+            env.gas_config()
+                .storage
+                .map(|storage| storage.read_cost(record_len)),
+        );
+        process_gas_info::<A, S, Q>(env, gas_info)?;
+
+        match result? {
+            Some((key, value)) => {
+                sections.push(key);
+                sections.push(value);
+            }
+            None => break,
+        }
+    }
+
+    let out_data = encode_sections(&sections)?;
+    write_to_contract::<A, S, Q>(env, &out_data)
+}
+
 /// Returns the data shifted by 32 bits towards the most significant bit.
 ///
 /// This is independent of endianness. But to get the idea, it would be
@@ -463,7 +621,7 @@ fn to_low_half(data: u32) -> u64 {
 mod tests {
     use super::*;
     use cosmwasm_std::{
-        coins, from_binary, AllBalanceResponse, BankQuery, Binary, Empty, QueryRequest,
+        coins, from_binary, AllBalanceResponse, BankQuery, Binary, Coin, Empty, QueryRequest,
         SystemError, SystemResult, WasmQuery,
     };
     use hex_literal::hex;
@@ -471,6 +629,7 @@ mod tests {
     use wasmer::{imports, Function, Instance as WasmerInstance};
 
     use crate::backend::{BackendError, Storage};
+    use crate::environment::{GasConfig, StorageGasConfig};
     use crate::size::Size;
     use crate::testing::{MockApi, MockQuerier, MockStorage};
     use crate::wasm_backend::compile;
@@ -642,6 +801,75 @@ mod tests {
         assert_eq!(val, Some(b"new value".to_vec()));
     }
 
+    #[test]
+    fn storage_gas_works() {
+        let reported = GasInfo::new(500, 7);
+        assert_eq!(storage_gas(reported, None), reported);
+        assert_eq!(storage_gas(reported, Some(100)), GasInfo::new(100, 7));
+        assert_eq!(storage_gas(reported, Some(0)), GasInfo::new(0, 7));
+    }
+
+    #[test]
+    fn do_db_write_charges_storage_gas_config() {
+        let api = MockApi::default();
+        let (env, _instance) = make_instance(api);
+        env.set_gas_config(GasConfig {
+            storage: Some(StorageGasConfig {
+                write_base_cost: 1000,
+                write_per_byte_cost: 10,
+                ..StorageGasConfig::default()
+            }),
+            ..GasConfig::default()
+        });
+
+        let key_ptr = write_data(&env, b"new storage key");
+        let value_ptr = write_data(&env, b"new value");
+
+        leave_default_data(&env);
+
+        let gas_before = env.get_gas_left();
+        do_db_write(&env, key_ptr, value_ptr).unwrap();
+        let gas_used = gas_before - env.get_gas_left();
+
+        // configured cost for 24 bytes plus the 24 gas MockStorage uses externally
+        assert_eq!(gas_used, 1000 + 24 * 10 + 24);
+    }
+
+    #[test]
+    fn do_db_read_charges_storage_gas_config_for_every_key_length() {
+        let api = MockApi::default();
+        let (env, _instance) = make_instance(api);
+        let storage_config = StorageGasConfig {
+            read_base_cost: 1000,
+            read_per_byte_cost: 10,
+            ..StorageGasConfig::default()
+        };
+        env.set_gas_config(GasConfig {
+            storage: Some(storage_config),
+            ..GasConfig::default()
+        });
+
+        let keys: [&[u8]; 4] = [b"", b"k", b"missing", b"a much longer missing key"];
+        let key_ptrs: Vec<u32> = keys.iter().map(|key| write_data(&env, key)).collect();
+
+        leave_default_data(&env);
+
+        let mut previous = 0;
+        for (key, key_ptr) in keys.iter().zip(key_ptrs) {
+            let gas_before = env.get_gas_left();
+            assert_eq!(do_db_read(&env, key_ptr).unwrap(), 0);
+            let gas_used = gas_before - env.get_gas_left();
+
+            // MockStorage uses the key length externally, on top of the configured cost
+            assert_eq!(
+                gas_used,
+                storage_config.read_cost(key.len()) + key.len() as u64
+            );
+            assert!(gas_used > previous);
+            previous = gas_used;
+        }
+    }
+
     #[test]
     fn do_db_write_can_override() {
         let api = MockApi::default();
@@ -1694,6 +1922,98 @@ mod tests {
         assert_eq!(parsed_again.amount, coins(INIT_AMOUNT, INIT_DENOM));
     }
 
+    #[test]
+    fn do_query_chain_fails_for_response_exceeding_limit() {
+        let api = MockApi::default();
+        let (env, _instance) = make_instance(api);
+        env.set_query_response_limit(Some(Size(10)));
+
+        let request: QueryRequest<Empty> = QueryRequest::Bank(BankQuery::AllBalances {
+            address: INIT_ADDR.to_string(),
+        });
+        let request_data = cosmwasm_std::to_vec(&request).unwrap();
+        let request_ptr = write_data(&env, &request_data);
+
+        leave_default_data(&env);
+
+        let response_ptr = do_query_chain(&env, request_ptr).unwrap();
+        let response = force_read(&env, response_ptr);
+
+        let query_result: cosmwasm_std::QuerierResult =
+            cosmwasm_std::from_slice(&response).unwrap();
+        match query_result {
+            SystemResult::Err(SystemError::InvalidResponse { error, response }) => {
+                assert!(
+                    error.ends_with("exceeds the limit of 10 bytes"),
+                    "{}",
+                    error
+                );
+                assert_eq!(response, Binary::default());
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn do_query_chain_memoization_works() {
+        let api = MockApi::default();
+        let (env, _instance) = make_instance(api);
+        env.set_memoize_queries(true);
+        env.set_gas_config(GasConfig {
+            query_cache_hit_base_cost: 1000,
+            query_cache_hit_per_byte_cost: 10,
+            ..GasConfig::default()
+        });
+
+        let request: QueryRequest<Empty> = QueryRequest::Bank(BankQuery::AllBalances {
+            address: INIT_ADDR.to_string(),
+        });
+        let request_data = cosmwasm_std::to_vec(&request).unwrap();
+        let request_ptr = write_data(&env, &request_data);
+
+        leave_default_data(&env);
+
+        let query_balances = || -> Vec<Coin> {
+            let response_ptr = do_query_chain(&env, request_ptr).unwrap();
+            let response = force_read(&env, response_ptr);
+            let query_result: cosmwasm_std::QuerierResult =
+                cosmwasm_std::from_slice(&response).unwrap();
+            let response: AllBalanceResponse =
+                from_binary(&query_result.unwrap().unwrap()).unwrap();
+            response.amount
+        };
+
+        let gas_before = env.get_gas_left();
+        assert_eq!(query_balances(), coins(INIT_AMOUNT, INIT_DENOM));
+        let gas_used_uncached = gas_before - env.get_gas_left();
+
+        // querier is not hit for the same request
+        env.with_querier_from_context::<_, _>(|querier| {
+            querier.update_balance(INIT_ADDR, coins(7, INIT_DENOM));
+            Ok(())
+        })
+        .unwrap();
+        let gas_before = env.get_gas_left();
+        assert_eq!(query_balances(), coins(INIT_AMOUNT, INIT_DENOM));
+        let gas_used_cached = gas_before - env.get_gas_left();
+
+        // and a hit is charged the configured cost, which is less than the query
+        let response_len = env.cached_query_response(&request_data).unwrap().len();
+        assert_eq!(gas_used_cached, 1000 + 10 * response_len as u64);
+        assert!(gas_used_cached < gas_used_uncached);
+
+        // storage writes clear the cache
+        let key_ptr = write_data(&env, b"new storage key");
+        let value_ptr = write_data(&env, b"new value");
+        do_db_write(&env, key_ptr, value_ptr).unwrap();
+        assert_eq!(query_balances(), coins(7, INIT_DENOM));
+
+        // moving in a new querier clears the cache
+        env.move_out();
+        leave_default_data(&env);
+        assert_eq!(query_balances(), coins(INIT_AMOUNT, INIT_DENOM));
+    }
+
     #[test]
     fn do_query_chain_fails_for_broken_request() {
         let api = MockApi::default();
@@ -1916,6 +2236,53 @@ mod tests {
         // API makes no guarantees for value_ptr in this case
     }
 
+    #[test]
+    #[cfg(feature = "iterator")]
+    fn do_db_next_batch_works() {
+        let api = MockApi::default();
+        let (env, _instance) = make_instance(api);
+
+        leave_default_data(&env);
+
+        let id = do_db_scan(&env, 0, 0, Order::Ascending.into()).unwrap();
+
+        // Entry 1
+        let batch_ptr = do_db_next_batch(&env, id, 1).unwrap();
+        assert_eq!(
+            force_read(&env, batch_ptr),
+            [KEY1, b"\0\0\0\x03", VALUE1, b"\0\0\0\x06"].concat()
+        );
+
+        // Entry 2 (fewer than requested)
+        let batch_ptr = do_db_next_batch(&env, id, 10).unwrap();
+        assert_eq!(
+            force_read(&env, batch_ptr),
+            [KEY2, b"\0\0\0\x04", VALUE2, b"\0\0\0\x05"].concat()
+        );
+
+        // End
+        let batch_ptr = do_db_next_batch(&env, id, 10).unwrap();
+        assert_eq!(force_read(&env, batch_ptr), b"");
+
+        // Multiple entries at once
+        let id = do_db_scan(&env, 0, 0, Order::Descending.into()).unwrap();
+        let batch_ptr = do_db_next_batch(&env, id, 5).unwrap();
+        assert_eq!(
+            force_read(&env, batch_ptr),
+            [
+                KEY2,
+                b"\0\0\0\x04",
+                VALUE2,
+                b"\0\0\0\x05",
+                KEY1,
+                b"\0\0\0\x03",
+                VALUE1,
+                b"\0\0\0\x06"
+            ]
+            .concat()
+        );
+    }
+
     #[test]
     #[cfg(feature = "iterator")]
     fn do_db_next_fails_for_non_existent_id() {
@@ -1,13 +1,14 @@
 use std::collections::{HashMap, HashSet};
 use std::ptr::NonNull;
 use std::sync::Mutex;
+use std::time::Duration;
 
 use wasmer::{Exports, Function, ImportObject, Instance as WasmerInstance, Module, Val};
 
 use crate::backend::{Backend, BackendApi, Querier, Storage};
 use crate::capabilities::required_capabilities_from_module;
 use crate::conversion::{ref_to_u32, to_u32};
-use crate::environment::Environment;
+use crate::environment::{Environment, GasConfig, ProfileReport};
 use crate::errors::{CommunicationError, VmError, VmResult};
 use crate::imports::{
     do_abort, do_addr_canonicalize, do_addr_humanize, do_addr_validate, do_db_read, do_db_remove,
@@ -15,10 +16,10 @@ use crate::imports::{
     do_secp256k1_recover_pubkey, do_secp256k1_verify,
 };
 #[cfg(feature = "iterator")]
-use crate::imports::{do_db_next, do_db_scan};
+use crate::imports::{do_db_next, do_db_next_batch, do_db_scan};
 use crate::memory::{read_region, write_region};
 use crate::size::Size;
-use crate::wasm_backend::compile;
+use crate::wasm_backend::{CompilerBackend, WasmerBackend};
 
 #[derive(Copy, Clone, Debug)]
 pub struct GasReport {
@@ -38,6 +39,23 @@ pub struct InstanceOptions {
     /// Gas limit measured in [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
     pub gas_limit: u64,
     pub print_debug: bool,
+    /// Gas costs of operations performed by the VM on behalf of the contract.
+    /// Use `GasConfig::DEFAULT` if you don't need to customize those.
+    pub gas_config: GasConfig,
+    /// Maximum size of query responses in bytes. This applies to responses the contract
+    /// receives from `query_chain` as well as to the data returned by the contract's query
+    /// export. Use `None` to only apply the built-in limits.
+    pub query_response_limit: Option<Size>,
+    /// When enabled, the responses to `query_chain` calls are cached during a single contract
+    /// call, such that repeated identical requests do not hit the querier again. The cache is
+    /// cleared at the start of every call and on every storage write or removal, since the
+    /// contract might query its own state.
+    ///
+    /// Cache hits are charged `GasConfig::query_cache_hit_base_cost` plus
+    /// `GasConfig::query_cache_hit_per_byte_cost` per byte of the response instead of the gas
+    /// of the original query. Since hits are deterministic, this is safe to enable on chain
+    /// but changes gas consumption of contracts repeating queries.
+    pub memoize_queries: bool,
 }
 
 pub struct Instance<A: BackendApi, S: Storage, Q: Querier> {
@@ -48,6 +66,9 @@ pub struct Instance<A: BackendApi, S: Storage, Q: Querier> {
     /// This instance should only be accessed via the Environment, which provides safe access.
     _inner: Box<WasmerInstance>,
     env: Environment<A, S, Q>,
+    /// A copy of the API stored in the environment, such that [`Instance::api`]
+    /// can return a reference.
+    api: A,
 }
 
 impl<A, S, Q> Instance<A, S, Q>
@@ -64,28 +85,43 @@ where
         options: InstanceOptions,
         memory_limit: Option<Size>,
     ) -> VmResult<Self> {
-        let module = compile(code, memory_limit, &[])?;
-        Instance::from_module(
-            &module,
-            backend,
-            options.gas_limit,
-            options.print_debug,
-            None,
-            None,
-        )
+        let module = WasmerBackend.compile(code, memory_limit)?;
+        Instance::from_module(&module, backend, options, None, None)
     }
 
     pub(crate) fn from_module(
         module: &Module,
         backend: Backend<A, S, Q>,
-        gas_limit: u64,
-        print_debug: bool,
+        options: InstanceOptions,
+        extra_imports: Option<HashMap<&str, Exports>>,
+        instantiation_lock: Option<&Mutex<()>>,
+    ) -> VmResult<Self> {
+        let instance = Instance::from_module_unbound(
+            module,
+            backend.api,
+            options,
+            extra_imports,
+            instantiation_lock,
+        )?;
+        instance.env.move_in(backend.storage, backend.querier);
+        Ok(instance)
+    }
+
+    /// Creates an instance that has an API but no storage and querier yet.
+    /// Those are moved in later using [`Instance::adopt`].
+    pub(crate) fn from_module_unbound(
+        module: &Module,
+        api: A,
+        options: InstanceOptions,
         extra_imports: Option<HashMap<&str, Exports>>,
         instantiation_lock: Option<&Mutex<()>>,
     ) -> VmResult<Self> {
         let store = module.store();
 
-        let env = Environment::new(backend.api, gas_limit, print_debug);
+        let env = Environment::new(api, options.gas_limit, options.print_debug);
+        env.set_gas_config(options.gas_config);
+        env.set_query_response_limit(options.query_response_limit);
+        env.set_memoize_queries(options.memoize_queries);
 
         let mut import_obj = ImportObject::new();
         let mut env_imports = Exports::new();
@@ -217,6 +253,18 @@ where
             Function::new_native_with_env(store, env.clone(), do_db_next),
         );
 
+        // Get up to `max_items` next elements of iterator with ID `iterator_id` in one call.
+        // Creates a region containing all keys and values and returns its address.
+        // Ownership of the result region is transferred to the contract.
+        // The region uses the sections format key1 || key1len || value1 || value1len || key2 || …,
+        // where the lengths are fixed size big endian u32 values.
+        // An empty region means no more elements. The host may return fewer than `max_items` elements.
+        #[cfg(feature = "iterator")]
+        env_imports.insert(
+            "db_next_batch",
+            Function::new_native_with_env(store, env.clone(), do_db_next_batch),
+        );
+
         import_obj.register("env", env_imports);
 
         if let Some(extra_imports) = extra_imports {
@@ -237,24 +285,39 @@ where
 
         let instance_ptr = NonNull::from(wasmer_instance.as_ref());
         env.set_wasmer_instance(Some(instance_ptr));
-        env.set_gas_left(gas_limit);
-        env.move_in(backend.storage, backend.querier);
+        env.set_gas_left(options.gas_limit);
         let instance = Instance {
             _inner: wasmer_instance,
             env,
+            api,
         };
         Ok(instance)
     }
 
     pub fn api(&self) -> &A {
-        &self.env.api
+        &self.api
+    }
+
+    /// Moves the external dependencies and call options into an instance
+    /// created via [`Instance::from_module_unbound`].
+    ///
+    /// This must only be used for instances that were never executed.
+    pub(crate) fn adopt(&mut self, backend: Backend<A, S, Q>, options: InstanceOptions) {
+        self.api = backend.api;
+        self.env
+            .reset(backend.api, options.gas_limit, options.print_debug);
+        self.env.set_gas_config(options.gas_config);
+        self.env
+            .set_query_response_limit(options.query_response_limit);
+        self.env.set_memoize_queries(options.memoize_queries);
+        self.env.move_in(backend.storage, backend.querier);
     }
 
     /// Decomposes this instance into its components.
     /// External dependencies are returned for reuse, the rest is dropped.
     pub fn recycle(self) -> Option<Backend<A, S, Q>> {
         if let (Some(storage), Some(querier)) = self.env.move_out() {
-            let api = self.env.api;
+            let api = self.env.api();
             Some(Backend {
                 api,
                 storage,
@@ -282,6 +345,11 @@ where
         self.env.memory().size().0 as _
     }
 
+    /// Returns the maximum size of query responses in bytes, if set.
+    pub fn query_response_limit(&self) -> Option<Size> {
+        self.env.query_response_limit()
+    }
+
     /// Returns the currently remaining gas.
     pub fn get_gas_left(&self) -> u64 {
         self.env.get_gas_left()
@@ -307,6 +375,31 @@ where
         }
     }
 
+    /// Enables or disables profiling for subsequent calls. When enabled, the instance
+    /// collects time and gas per entry point as well as the number of host function
+    /// calls, which can be retrieved via [`Instance::profile_report`].
+    ///
+    /// This is meant for contract development and should not be enabled in production
+    /// as it adds overhead to every host function call.
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.env.set_profiling(enabled);
+    }
+
+    /// Returns the data collected since profiling was enabled, or `None` if profiling
+    /// is disabled.
+    pub fn profile_report(&self) -> Option<ProfileReport> {
+        self.env.profile_report()
+    }
+
+    /// Drops all cached `query_chain` responses. See [`InstanceOptions::memoize_queries`].
+    pub(crate) fn clear_query_cache(&self) {
+        self.env.clear_query_cache();
+    }
+
+    pub(crate) fn record_entry_point(&self, name: &str, time: Duration, gas_used: u64) {
+        self.env.record_entry_point(name, time, gas_used);
+    }
+
     /// Sets the readonly storage flag on this instance. Since one instance can be used
     /// for multiple calls in integration tests, this should be set to the desired value
     /// right before every call.
@@ -379,7 +472,14 @@ where
     S: Storage + 'static, // 'static is needed here to allow using this in an Environment that is cloned into closures
     Q: Querier + 'static,
 {
-    Instance::from_module(module, backend, gas_limit, print_debug, extra_imports, None)
+    let options = InstanceOptions {
+        gas_limit,
+        print_debug,
+        gas_config: GasConfig::default(),
+        query_response_limit: None,
+        memoize_queries: false,
+    };
+    Instance::from_module(module, backend, options, extra_imports, None)
 }
 
 #[cfg(test)]
@@ -452,7 +552,7 @@ mod tests {
 
         let backend = mock_backend(&[]);
         let (instance_options, memory_limit) = mock_instance_options();
-        let module = compile(&wasm, memory_limit, &[]).unwrap();
+        let module = WasmerBackend.compile(&wasm, memory_limit).unwrap();
 
         #[derive(wasmer::WasmerEnv, Clone)]
         struct MyEnv {
@@ -475,8 +575,7 @@ mod tests {
         let instance = Instance::from_module(
             &module,
             backend,
-            instance_options.gas_limit,
-            false,
+            instance_options,
             Some(extra_imports),
             None,
         )
@@ -716,6 +815,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn profiling_works() {
+        let mut instance = mock_instance(CONTRACT, &[]);
+        assert_eq!(instance.profile_report(), None);
+
+        instance.set_profiling(true);
+        assert_eq!(instance.profile_report(), Some(ProfileReport::default()));
+
+        // init contract
+        let info = mock_info("creator", &coins(1000, "earth"));
+        let msg = br#"{"verifier": "verifies", "beneficiary": "benefits"}"#;
+        call_instantiate::<_, _, _, Empty>(&mut instance, &mock_env(), &info, msg)
+            .unwrap()
+            .unwrap();
+
+        // query twice
+        let msg = br#"{"verifier":{}}"#;
+        for _ in 0..2 {
+            call_query(&mut instance, &mock_env(), msg)
+                .unwrap()
+                .unwrap();
+        }
+
+        let report = instance.profile_report().unwrap();
+        assert_eq!(report.entry_points.len(), 2);
+        let instantiate = &report.entry_points["instantiate"];
+        assert_eq!(instantiate.calls, 1);
+        assert!(instantiate.gas_used > 0);
+        let query = &report.entry_points["query"];
+        assert_eq!(query.calls, 2);
+        assert!(query.gas_used > 0);
+        assert_eq!(report.host_calls["db_write"], 1);
+        assert_eq!(report.host_calls["db_read"], 2);
+
+        instance.set_profiling(false);
+        assert_eq!(instance.profile_report(), None);
+    }
+
     #[test]
     fn set_storage_readonly_works() {
         let mut instance = mock_instance(CONTRACT, &[]);
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use crate::backend::{BackendApi, Querier, Storage};
+use crate::checksum::Checksum;
+use crate::instance::Instance;
+
+struct PoolEntry<A: BackendApi, S: Storage, Q: Querier> {
+    capacity: usize,
+    instances: Vec<Instance<A, S, Q>>,
+}
+
+/// A pool of instances that were created ahead of time and never executed.
+///
+/// Every checksum has its own capacity. Checksums without a capacity are not pooled.
+pub struct InstancePool<A: BackendApi, S: Storage, Q: Querier> {
+    entries: HashMap<Checksum, PoolEntry<A, S, Q>>,
+}
+
+impl<A: BackendApi, S: Storage, Q: Querier> InstancePool<A, S, Q> {
+    /// Creates a new pool without any pooled checksums
+    pub fn new() -> Self {
+        InstancePool {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Sets the maximum number of pooled instances for the given checksum.
+    /// Superfluous instances are dropped. A capacity of 0 disables pooling for this checksum.
+    pub fn set_capacity(&mut self, checksum: &Checksum, capacity: usize) {
+        if capacity == 0 {
+            self.entries.remove(checksum);
+            return;
+        }
+        let entry = self.entries.entry(*checksum).or_insert_with(|| PoolEntry {
+            capacity,
+            instances: Vec::with_capacity(capacity),
+        });
+        entry.capacity = capacity;
+        entry.instances.truncate(capacity);
+    }
+
+    /// Returns the number of instances that can be added until the pool
+    /// of the given checksum is full.
+    pub fn missing(&self, checksum: &Checksum) -> usize {
+        match self.entries.get(checksum) {
+            Some(entry) => entry.capacity.saturating_sub(entry.instances.len()),
+            None => 0,
+        }
+    }
+
+    /// Adds an instance to the pool of the given checksum.
+    ///
+    /// Returns false and drops the instance if the pool is full
+    /// or the checksum is not pooled.
+    pub fn put(&mut self, checksum: &Checksum, instance: Instance<A, S, Q>) -> bool {
+        match self.entries.get_mut(checksum) {
+            Some(entry) if entry.instances.len() < entry.capacity => {
+                entry.instances.push(instance);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Takes an instance out of the pool of the given checksum, if available.
+    pub fn take(&mut self, checksum: &Checksum) -> Option<Instance<A, S, Q>> {
+        self.entries
+            .get_mut(checksum)
+            .and_then(|entry| entry.instances.pop())
+    }
+
+    /// Returns the number of pooled instances across all checksums.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.entries
+            .values()
+            .map(|entry| entry.instances.len())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{mock_instance, MockApi, MockQuerier, MockStorage};
+
+    static CONTRACT: &[u8] = include_bytes!("../testdata/hackatom.wasm");
+
+    #[test]
+    fn set_capacity_works() {
+        let mut pool = InstancePool::<MockApi, MockStorage, MockQuerier>::new();
+        let checksum = Checksum::generate(CONTRACT);
+        assert_eq!(pool.missing(&checksum), 0);
+
+        pool.set_capacity(&checksum, 3);
+        assert_eq!(pool.missing(&checksum), 3);
+        assert!(pool.put(&checksum, mock_instance(CONTRACT, &[])));
+        assert!(pool.put(&checksum, mock_instance(CONTRACT, &[])));
+        assert_eq!(pool.missing(&checksum), 1);
+        assert_eq!(pool.len(), 2);
+
+        // shrinking drops instances
+        pool.set_capacity(&checksum, 1);
+        assert_eq!(pool.missing(&checksum), 0);
+        assert_eq!(pool.len(), 1);
+
+        // 0 disables pooling
+        pool.set_capacity(&checksum, 0);
+        assert_eq!(pool.missing(&checksum), 0);
+        assert_eq!(pool.len(), 0);
+    }
+
+    #[test]
+    fn put_and_take_work() {
+        let mut pool = InstancePool::<MockApi, MockStorage, MockQuerier>::new();
+        let checksum = Checksum::generate(CONTRACT);
+
+        // not pooled
+        assert!(!pool.put(&checksum, mock_instance(CONTRACT, &[])));
+        assert!(pool.take(&checksum).is_none());
+
+        pool.set_capacity(&checksum, 1);
+        assert!(pool.put(&checksum, mock_instance(CONTRACT, &[])));
+        // full
+        assert!(!pool.put(&checksum, mock_instance(CONTRACT, &[])));
+
+        assert!(pool.take(&checksum).is_some());
+        assert!(pool.take(&checksum).is_none());
+        assert_eq!(pool.missing(&checksum), 1);
+    }
+}
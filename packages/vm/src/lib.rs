@@ -12,7 +12,9 @@ mod errors;
 mod filesystem;
 mod imports;
 mod instance;
+mod instance_pool;
 mod limited;
+mod logging;
 mod memory;
 mod modules;
 mod sections;
@@ -26,6 +28,8 @@ pub use crate::backend::{
     Backend, BackendApi, BackendError, BackendResult, GasInfo, Querier, Storage,
 };
 pub use crate::cache::{AnalysisReport, Cache, CacheOptions, Metrics, Stats};
+#[cfg(feature = "custom_entry_points")]
+pub use crate::calls::call_raw;
 pub use crate::calls::{
     call_execute, call_execute_raw, call_instantiate, call_instantiate_raw, call_migrate,
     call_migrate_raw, call_query, call_query_raw, call_reply, call_reply_raw, call_sudo,
@@ -40,6 +44,10 @@ pub use crate::calls::{
 };
 pub use crate::capabilities::capabilities_from_csv;
 pub use crate::checksum::Checksum;
+pub use crate::compatibility::{
+    check_wasm_with_options, validate_contract, ValidationOptions, ValidationReport,
+};
+pub use crate::environment::{EntryPointProfile, GasConfig, ProfileReport, StorageGasConfig};
 pub use crate::errors::{
     CommunicationError, CommunicationResult, RegionValidationError, RegionValidationResult,
     VmError, VmResult,
@@ -0,0 +1,14 @@
+//! Structured logging through the `log` crate, enabled by the "log" feature.
+//! Without this feature, no logging code is compiled in.
+
+/// Emits a log record with the target `cosmwasm_vm` if the "log" feature is enabled.
+///
+/// The first argument is the level as the name of the `log` macro, e.g. `debug` or `warn`.
+macro_rules! vm_log {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "log")]
+        log::$level!(target: "cosmwasm_vm", $($arg)+);
+    };
+}
+
+pub(crate) use vm_log;
@@ -1,14 +1,15 @@
-use std::io;
 use std::path::PathBuf;
 use thiserror::Error;
 
-use wasmer::{DeserializeError, Module, Store};
+use wasmer::Module;
 
 use crate::checksum::Checksum;
 use crate::errors::{VmError, VmResult};
 
 use crate::filesystem::mkdir_p;
 use crate::modules::current_wasmer_module_version;
+use crate::size::Size;
+use crate::wasm_backend::{CompilerBackend, WasmerBackend};
 
 /// Bump this version whenever the module system changes in a way
 /// that old stored modules would be corrupt when loaded in the new system.
@@ -100,27 +101,17 @@ impl FileSystemCache {
         }
     }
 
-    /// Loads a serialized module from the file system and returns a module (i.e. artifact + store),
-    /// along with the size of the serialized module.
-    pub fn load(&self, checksum: &Checksum, store: &Store) -> VmResult<Option<Module>> {
+    /// Loads a serialized module from the file system and returns a module (i.e. artifact + store).
+    /// Instances created from the module get the given memory limit (in bytes).
+    pub fn load(
+        &self,
+        checksum: &Checksum,
+        memory_limit: Option<Size>,
+    ) -> VmResult<Option<Module>> {
         let filename = checksum.to_hex();
         let file_path = self.latest_modules_path().join(filename);
 
-        let result = unsafe { Module::deserialize_from_file(store, &file_path) };
-        match result {
-            Ok(module) => Ok(Some(module)),
-            Err(DeserializeError::Io(err)) => match err.kind() {
-                io::ErrorKind::NotFound => Ok(None),
-                _ => Err(VmError::cache_err(format!(
-                    "Error opening module file: {}",
-                    err
-                ))),
-            },
-            Err(err) => Err(VmError::cache_err(format!(
-                "Error deserializing module: {}",
-                err
-            ))),
-        }
+        unsafe { WasmerBackend.deserialize_from_file(&file_path, memory_limit) }
     }
 
     /// Stores a serialized module to the file system. Returns the size of the serialized module.
@@ -131,10 +122,7 @@ impl FileSystemCache {
 
         let filename = checksum.to_hex();
         let path = modules_dir.join(filename);
-        module
-            .serialize_to_file(path)
-            .map_err(|e| VmError::cache_err(format!("Error writing module to disk: {}", e)))?;
-        Ok(())
+        WasmerBackend.serialize_to_file(module, &path)
     }
 
     /// The path to the latest version of the modules.
@@ -152,8 +140,7 @@ mod tests {
     use std::fs;
 
     use super::*;
-    use crate::size::Size;
-    use crate::wasm_backend::{compile, make_runtime_store};
+    use crate::wasm_backend::compile;
     use tempfile::TempDir;
     use wasmer::{imports, Instance as WasmerInstance};
     use wasmer_middlewares::metering::set_remaining_points;
@@ -179,8 +166,7 @@ mod tests {
         let checksum = Checksum::generate(&wasm);
 
         // Module does not exist
-        let store = make_runtime_store(TESTING_MEMORY_LIMIT);
-        let cached = cache.load(&checksum, &store).unwrap();
+        let cached = cache.load(&checksum, TESTING_MEMORY_LIMIT).unwrap();
         assert!(cached.is_none());
 
         // Store module
@@ -188,8 +174,7 @@ mod tests {
         cache.store(&checksum, &module).unwrap();
 
         // Load module
-        let store = make_runtime_store(TESTING_MEMORY_LIMIT);
-        let cached = cache.load(&checksum, &store).unwrap();
+        let cached = cache.load(&checksum, TESTING_MEMORY_LIMIT).unwrap();
         assert!(cached.is_some());
 
         // Check the returned module is functional.
@@ -82,6 +82,18 @@ pub fn has_ibc_entry_points(module: &impl ExportInfo) -> bool {
         .all(|required| available_exports.contains(*required))
 }
 
+/// The name of the custom section written by `cosmwasm_std::embed_build_info!`
+pub const BUILD_INFO_SECTION: &str = "cosmwasm_build_info";
+
+/// Returns the build information embedded via `cosmwasm_std::embed_build_info!`,
+/// if the section exists and contains valid UTF-8.
+pub fn build_info(module: &Module) -> Option<String> {
+    module
+        .custom_sections()
+        .find(|section| section.name() == BUILD_INFO_SECTION)
+        .and_then(|section| String::from_utf8(section.payload().to_vec()).ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,6 +249,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn build_info_works() {
+        let mut wasm = wat::parse_str("(module)").unwrap();
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(build_info(&module), None);
+
+        // append custom section (id 0, length, name length, name, payload)
+        let payload = br#"{"name":"foo","version":"1.2.3","builder":""}"#;
+        let section_len = 1 + BUILD_INFO_SECTION.len() + payload.len();
+        assert!(section_len < 128, "length must fit into a one byte LEB128");
+        wasm.extend([0u8, section_len as u8, BUILD_INFO_SECTION.len() as u8]);
+        wasm.extend(BUILD_INFO_SECTION.as_bytes());
+        wasm.extend(payload);
+        let module = deserialize_wasm(&wasm).unwrap();
+        assert_eq!(
+            build_info(&module).unwrap(),
+            r#"{"name":"foo","version":"1.2.3","builder":""}"#
+        );
+    }
+
     #[test]
     fn has_ibc_entry_points_works() {
         // Non-IBC contract
@@ -6,6 +6,7 @@ use std::collections::HashSet;
 
 use crate::capabilities::capabilities_from_csv;
 use crate::compatibility::check_wasm;
+use crate::environment::GasConfig;
 use crate::instance::{Instance, InstanceOptions};
 use crate::size::Size;
 use crate::{Backend, BackendApi, Querier, Storage};
@@ -91,6 +92,11 @@ pub struct MockInstanceOptions<'a> {
     /// Gas limit measured in [CosmWasm gas](https://github.com/CosmWasm/cosmwasm/blob/main/docs/GAS.md).
     pub gas_limit: u64,
     pub print_debug: bool,
+    pub gas_config: GasConfig,
+    /// Maximum size of query responses in bytes
+    pub query_response_limit: Option<Size>,
+    /// Answer repeated identical queries from a per-instance cache
+    pub memoize_queries: bool,
     /// Memory limit in bytes. Use a value that is divisible by the Wasm page size 65536, e.g. full MiBs.
     pub memory_limit: Option<Size>,
 }
@@ -98,7 +104,9 @@ pub struct MockInstanceOptions<'a> {
 impl MockInstanceOptions<'_> {
     fn default_capabilities() -> HashSet<String> {
         #[allow(unused_mut)]
-        let mut out = capabilities_from_csv("iterator,staking,cosmwasm_1_1");
+        let mut out = capabilities_from_csv(
+            "iterator,staking,cosmwasm_1_1,cosmwasm_1_2,raw_range,db_next_batch",
+        );
         #[cfg(feature = "stargate")]
         out.insert("stargate".to_string());
         out
@@ -117,6 +125,9 @@ impl Default for MockInstanceOptions<'_> {
             available_capabilities: Self::default_capabilities(),
             gas_limit: DEFAULT_GAS_LIMIT,
             print_debug: DEFAULT_PRINT_DEBUG,
+            gas_config: GasConfig::DEFAULT,
+            query_response_limit: None,
+            memoize_queries: false,
             memory_limit: DEFAULT_MEMORY_LIMIT,
         }
     }
@@ -154,6 +165,9 @@ pub fn mock_instance_with_options(
     let options = InstanceOptions {
         gas_limit: options.gas_limit,
         print_debug: options.print_debug,
+        gas_config: options.gas_config,
+        query_response_limit: options.query_response_limit,
+        memoize_queries: options.memoize_queries,
     };
     Instance::from_code(wasm, backend, options, memory_limit).unwrap()
 }
@@ -164,6 +178,9 @@ pub fn mock_instance_options() -> (InstanceOptions, Option<Size>) {
         InstanceOptions {
             gas_limit: DEFAULT_GAS_LIMIT,
             print_debug: DEFAULT_PRINT_DEBUG,
+            gas_config: GasConfig::DEFAULT,
+            query_response_limit: None,
+            memoize_queries: false,
         },
         DEFAULT_MEMORY_LIMIT,
     )
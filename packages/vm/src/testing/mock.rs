@@ -2,6 +2,7 @@ use cosmwasm_std::testing::{digit_sum, riffle_shuffle};
 use cosmwasm_std::{
     Addr, BlockInfo, Coin, ContractInfo, Env, MessageInfo, Timestamp, TransactionInfo,
 };
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use super::querier::MockQuerier;
 use super::storage::MockStorage;
@@ -51,6 +52,38 @@ pub struct MockApi {
     canonical_length: usize,
     /// When set, all calls to the API fail with BackendError::Unknown containing this message
     backend_error: Option<&'static str>,
+    /// Failure programmed for `canonical_address` only
+    canonical_address_failure: Option<MethodFailure>,
+    /// Failure programmed for `human_address` only
+    human_address_failure: Option<MethodFailure>,
+}
+
+/// The methods of [`MockApi`] for which failures can be programmed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MockApiMethod {
+    CanonicalAddress,
+    HumanAddress,
+}
+
+#[derive(Copy, Clone)]
+struct MethodFailure {
+    backend_error: &'static str,
+    /// When set, only the call with this 1-based number fails. Copies of the API share the counter.
+    on_call: Option<(u64, &'static AtomicU64)>,
+}
+
+impl MethodFailure {
+    fn check(&self) -> Option<BackendError> {
+        let fails = match self.on_call {
+            Some((n, calls)) => calls.fetch_add(1, Ordering::SeqCst) + 1 == n,
+            None => true,
+        };
+        if fails {
+            Some(BackendError::unknown(self.backend_error))
+        } else {
+            None
+        }
+    }
 }
 
 impl MockApi {
@@ -65,6 +98,55 @@ impl MockApi {
             ..MockApi::default()
         }
     }
+
+    /// Makes all calls to the given method fail with `BackendError::Unknown`
+    /// containing `backend_error`. Other methods keep working.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use cosmwasm_vm::BackendApi;
+    /// # use cosmwasm_vm::testing::{MockApi, MockApiMethod};
+    /// let api = MockApi::default().with_failure(MockApiMethod::HumanAddress, "Temporarily unavailable");
+    /// let canonical = api.canonical_address("foobar").0.unwrap();
+    /// assert!(api.human_address(&canonical).0.is_err());
+    /// ```
+    pub fn with_failure(self, method: MockApiMethod, backend_error: &'static str) -> Self {
+        self.set_failure(
+            method,
+            MethodFailure {
+                backend_error,
+                on_call: None,
+            },
+        )
+    }
+
+    /// Makes only the `n`-th call (1-based) to the given method fail with `BackendError::Unknown`
+    /// containing `backend_error`. Calls are counted across all copies of this API.
+    pub fn with_failure_on_call(
+        self,
+        method: MockApiMethod,
+        n: u64,
+        backend_error: &'static str,
+    ) -> Self {
+        // The API must be Copy, so the counter is leaked. This is fine for test code.
+        let calls: &'static AtomicU64 = Box::leak(Box::new(AtomicU64::new(0)));
+        self.set_failure(
+            method,
+            MethodFailure {
+                backend_error,
+                on_call: Some((n, calls)),
+            },
+        )
+    }
+
+    fn set_failure(mut self, method: MockApiMethod, failure: MethodFailure) -> Self {
+        match method {
+            MockApiMethod::CanonicalAddress => self.canonical_address_failure = Some(failure),
+            MockApiMethod::HumanAddress => self.human_address_failure = Some(failure),
+        }
+        self
+    }
 }
 
 impl Default for MockApi {
@@ -72,6 +154,8 @@ impl Default for MockApi {
         MockApi {
             canonical_length: CANONICAL_LENGTH,
             backend_error: None,
+            canonical_address_failure: None,
+            human_address_failure: None,
         }
     }
 }
@@ -86,6 +170,9 @@ impl BackendApi for MockApi {
         if let Some(backend_error) = self.backend_error {
             return (Err(BackendError::unknown(backend_error)), gas_info);
         }
+        if let Some(err) = self.canonical_address_failure.and_then(|f| f.check()) {
+            return (Err(err), gas_info);
+        }
 
         // Dummy input validation. This is more sophisticated for formats like bech32, where format and checksum are validated.
         if normalized.len() < 3 {
@@ -124,6 +211,9 @@ impl BackendApi for MockApi {
         if let Some(backend_error) = self.backend_error {
             return (Err(BackendError::unknown(backend_error)), gas_info);
         }
+        if let Some(err) = self.human_address_failure.and_then(|f| f.check()) {
+            return (Err(err), gas_info);
+        }
 
         if canonical.len() != self.canonical_length {
             return (
@@ -261,4 +351,38 @@ mod tests {
             err => panic!("Unexpected error: {:?}", err),
         }
     }
+
+    #[test]
+    fn with_failure_only_affects_given_method() {
+        let api =
+            MockApi::default().with_failure(MockApiMethod::HumanAddress, "Temporarily unavailable");
+        let canonical = api.canonical_address("foobar").0.unwrap();
+        match api.human_address(&canonical).0.unwrap_err() {
+            BackendError::Unknown { msg } => assert_eq!(msg, "Temporarily unavailable"),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+
+        let api = MockApi::default()
+            .with_failure(MockApiMethod::CanonicalAddress, "Temporarily unavailable");
+        match api.canonical_address("foobar").0.unwrap_err() {
+            BackendError::Unknown { msg } => assert_eq!(msg, "Temporarily unavailable"),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        let canonical = MockApi::default().canonical_address("foobar").0.unwrap();
+        assert_eq!(api.human_address(&canonical).0.unwrap(), "foobar");
+    }
+
+    #[test]
+    fn with_failure_on_call_fails_nth_call_only() {
+        let api =
+            MockApi::default().with_failure_on_call(MockApiMethod::CanonicalAddress, 2, "Oops");
+        api.canonical_address("foobar").0.unwrap();
+        // copies share the call counter
+        let copy = api;
+        match copy.canonical_address("foobar").0.unwrap_err() {
+            BackendError::Unknown { msg } => assert_eq!(msg, "Oops"),
+            err => panic!("Unexpected error: {:?}", err),
+        }
+        api.canonical_address("foobar").0.unwrap();
+    }
 }
@@ -18,7 +18,8 @@ pub use instance::{
     test_io, MockInstanceOptions,
 };
 pub use mock::{
-    mock_backend, mock_backend_with_balances, mock_env, mock_info, MockApi, MOCK_CONTRACT_ADDR,
+    mock_backend, mock_backend_with_balances, mock_env, mock_info, MockApi, MockApiMethod,
+    MOCK_CONTRACT_ADDR,
 };
 pub use querier::MockQuerier;
 pub use storage::MockStorage;
@@ -45,6 +45,11 @@ impl<C: CustomQuery + DeserializeOwned> MockQuerier<C> {
         self.querier.update_staking(denom, validators, delegations);
     }
 
+    #[cfg(feature = "stargate")]
+    pub fn update_ibc(&mut self, port_id: &str, channels: &[cosmwasm_std::IbcChannel]) {
+        self.querier.update_ibc(port_id, channels);
+    }
+
     pub fn update_wasm<WH: 'static>(&mut self, handler: WH)
     where
         WH: Fn(&cosmwasm_std::WasmQuery) -> cosmwasm_std::QuerierResult,
@@ -52,6 +57,30 @@ impl<C: CustomQuery + DeserializeOwned> MockQuerier<C> {
         self.querier.update_wasm(handler)
     }
 
+    /// Registers a handler for Wasm queries to the given contract address.
+    /// See [`cosmwasm_std::testing::MockQuerier::update_wasm_contract`].
+    pub fn update_wasm_contract<WH: 'static>(
+        &mut self,
+        contract_addr: impl Into<String>,
+        handler: WH,
+    ) where
+        WH: Fn(&cosmwasm_std::WasmQuery) -> cosmwasm_std::QuerierResult,
+    {
+        self.querier.update_wasm_contract(contract_addr, handler)
+    }
+
+    pub fn with_wasm_handler<WH: 'static>(
+        mut self,
+        contract_addr: impl Into<String>,
+        handler: WH,
+    ) -> Self
+    where
+        WH: Fn(&cosmwasm_std::WasmQuery) -> cosmwasm_std::QuerierResult,
+    {
+        self.querier = self.querier.with_wasm_handler(contract_addr, handler);
+        self
+    }
+
     pub fn with_custom_handler<CH: 'static>(mut self, handler: CH) -> Self
     where
         CH: Fn(&C) -> MockQuerierCustomHandlerResult,
@@ -113,7 +142,9 @@ impl MockQuerier {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::{coin, from_binary, AllBalanceResponse, BalanceResponse, BankQuery, Empty};
+    use cosmwasm_std::{
+        coin, from_binary, AllBalanceResponse, BalanceResponse, BankQuery, Empty, WasmQuery,
+    };
 
     const DEFAULT_QUERY_GAS_LIMIT: u64 = 300_000;
 
@@ -231,4 +262,45 @@ mod tests {
         let res: BalanceResponse = from_binary(&miss).unwrap();
         assert_eq!(res.amount, coin(0, "ELF"));
     }
+
+    #[test]
+    fn wasm_querier_with_contract_handler() {
+        let querier: MockQuerier = MockQuerier::new(&[]).with_wasm_handler("contract1", |_| {
+            SystemResult::Ok(ContractResult::Ok(Binary::from(b"pong")))
+        });
+
+        let res = querier
+            .query::<Empty>(
+                &WasmQuery::Smart {
+                    contract_addr: String::from("contract1"),
+                    msg: Binary::from(b"{}"),
+                }
+                .into(),
+                DEFAULT_QUERY_GAS_LIMIT,
+            )
+            .0
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(res, b"pong");
+
+        let err = querier
+            .query::<Empty>(
+                &WasmQuery::Smart {
+                    contract_addr: String::from("contract2"),
+                    msg: Binary::from(b"{}"),
+                }
+                .into(),
+                DEFAULT_QUERY_GAS_LIMIT,
+            )
+            .0
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            SystemError::NoSuchContract {
+                addr: String::from("contract2")
+            }
+        );
+    }
 }
@@ -11,6 +11,10 @@ use super::store::make_compile_time_store;
 /// The given memory limit (in bytes) is used when memories are created.
 /// If no memory limit is passed, the resulting compiled module should
 /// not be used for execution.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(code_len = code.len()))
+)]
 pub fn compile(
     code: &[u8],
     memory_limit: Option<Size>,
@@ -0,0 +1,120 @@
+use std::io;
+use std::path::Path;
+
+use wasmer::{DeserializeError, Module};
+
+use crate::errors::{VmError, VmResult};
+use crate::size::Size;
+
+use super::compile::compile;
+use super::store::make_runtime_store;
+
+/// The module related operations the VM needs from a Wasm compiler:
+/// compiling, serializing and loading modules.
+///
+/// This is not an engine abstraction. Instantiation is not covered and the callers
+/// (`Cache`, `Instance`, `validate_contract`) use [`WasmerBackend`] directly, while
+/// [`Instance`](crate::Instance) and the host functions in `imports.rs` work with
+/// Wasmer types. Swapping the engine still requires changes to those.
+pub trait CompilerBackend {
+    /// A compiled module that can be instantiated
+    type Module: Clone;
+
+    /// Compiles the given Wasm bytecode into a module.
+    /// Instances created from the module get the given memory limit (in bytes).
+    /// If no memory limit is passed, the module should not be used for execution.
+    fn compile(&self, code: &[u8], memory_limit: Option<Size>) -> VmResult<Self::Module>;
+
+    /// Serializes a compiled module into the given file.
+    fn serialize_to_file(&self, module: &Self::Module, path: &Path) -> VmResult<()>;
+
+    /// Loads a module that was serialized using [`CompilerBackend::serialize_to_file`].
+    /// Returns `Ok(None)` if the file does not exist.
+    ///
+    /// # Safety
+    ///
+    /// The file contents are trusted and loaded as executable code.
+    unsafe fn deserialize_from_file(
+        &self,
+        path: &Path,
+        memory_limit: Option<Size>,
+    ) -> VmResult<Option<Self::Module>>;
+
+    /// Returns the estimated in-memory size of a compiled module in bytes
+    fn module_size(&self, module: &Self::Module) -> usize;
+}
+
+/// Wasmer's Universal engine with the Singlepass compiler
+/// (or Cranelift when the `cranelift` feature is enabled)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WasmerBackend;
+
+impl CompilerBackend for WasmerBackend {
+    type Module = Module;
+
+    fn compile(&self, code: &[u8], memory_limit: Option<Size>) -> VmResult<Module> {
+        compile(code, memory_limit, &[])
+    }
+
+    fn serialize_to_file(&self, module: &Module, path: &Path) -> VmResult<()> {
+        module
+            .serialize_to_file(path)
+            .map_err(|e| VmError::cache_err(format!("Error writing module to disk: {}", e)))
+    }
+
+    unsafe fn deserialize_from_file(
+        &self,
+        path: &Path,
+        memory_limit: Option<Size>,
+    ) -> VmResult<Option<Module>> {
+        let store = make_runtime_store(memory_limit);
+        match Module::deserialize_from_file(&store, path) {
+            Ok(module) => Ok(Some(module)),
+            Err(DeserializeError::Io(err)) => match err.kind() {
+                io::ErrorKind::NotFound => Ok(None),
+                _ => Err(VmError::cache_err(format!(
+                    "Error opening module file: {}",
+                    err
+                ))),
+            },
+            Err(err) => Err(VmError::cache_err(format!(
+                "Error deserializing module: {}",
+                err
+            ))),
+        }
+    }
+
+    fn module_size(&self, module: &Module) -> usize {
+        loupe::size_of_val(module)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    static CONTRACT: &[u8] = include_bytes!("../../testdata/hackatom.wasm");
+
+    #[test]
+    fn wasmer_backend_serialization_roundtrip() {
+        let tmp_dir = TempDir::new().unwrap();
+        let path = tmp_dir.path().join("module");
+
+        let module = WasmerBackend.compile(CONTRACT, None).unwrap();
+        assert!(WasmerBackend.module_size(&module) > 0);
+
+        let loaded = unsafe { WasmerBackend.deserialize_from_file(&path, None) }.unwrap();
+        assert!(loaded.is_none());
+
+        WasmerBackend.serialize_to_file(&module, &path).unwrap();
+        let loaded = unsafe { WasmerBackend.deserialize_from_file(&path, None) }
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            loaded.exports().count(),
+            module.exports().count(),
+            "loaded module must have the same exports"
+        );
+    }
+}
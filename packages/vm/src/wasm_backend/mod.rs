@@ -1,8 +1,10 @@
 mod compile;
+mod engine;
 mod gatekeeper;
 mod limiting_tunables;
 mod store;
 
 pub use compile::compile;
+pub use engine::{CompilerBackend, WasmerBackend};
 pub use limiting_tunables::LimitingTunables;
 pub use store::make_runtime_store;